@@ -1,8 +1,14 @@
 extern crate kolmogorov_smirnov as ks;
+#[cfg(unix)]
+extern crate libc;
+extern crate rand;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
 
-use ks::calculate_critical_value;
-
-use std::env;
+#[path = "ks/cli/mod.rs"]
+mod cli;
 
 /// Calculate critical values dataset.
 ///
@@ -16,24 +22,11 @@ use std::env;
 /// test for samples of size `<num_samples>` against samples of sizes 16
 /// through `<limit>` inclusive at the specified confidence level.
 ///
-/// `<num_samples>` and `<limit>` must be positive integers, `<confidence>` must
-/// be a floating point number strictly between zero and one.
+/// `<num_samples>` must be an integer greater than 7 and `<limit>` a positive
+/// integer, `<confidence>` must be a floating point number strictly between
+/// zero and one.
+///
+/// This is an alias of `ks critical-values`.
 fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    let confidence: f64 = args[1].parse().expect("<confidence> must be a floating point number.");
-    let n1: usize = args[2].parse().expect("<num_samples> must be an integer.");
-    let limit: usize = args[3].parse().expect("<limit> must be an integer.");
-
-    assert!(n1 > 0 && limit > 0);
-    assert!(0.0 < confidence && confidence < 1.0);
-
-    println!("n1\tn2\tconfidence\tcritical_value");
-    for n2 in 16..(limit + 1) {
-        println!("{}\t{}\t{}\t{}",
-                 n1,
-                 n2,
-                 confidence,
-                 calculate_critical_value(n1, n2, confidence));
-    }
+    cli::main(&["critical-values"], &["confidence", "num-samples", "limit"]);
 }
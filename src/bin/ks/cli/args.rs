@@ -0,0 +1,245 @@
+//! Command line option parsing.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Command line errors.
+#[derive(Debug)]
+pub enum Error {
+    /// Invalid command line usage, e.g. an unknown or missing option.
+    Usage(String),
+    /// Invalid or unreadable input data.
+    Input(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Usage(ref message) | Error::Input(ref message) => message.fmt(f),
+        }
+    }
+}
+
+/// Parsed command line options and positional arguments.
+pub struct Matches {
+    flags: Vec<String>,
+    options: Vec<(String, String)>,
+    positional: Vec<String>,
+}
+
+/// Parse command line arguments given the names of the flags and of the
+/// options taking a value that are accepted.
+///
+/// Options are written `--name value` or `--name=value` and may be repeated.
+/// Arguments after `--` are positional. The `--help` and `-h` flags are always
+/// accepted.
+pub fn parse(args: &[String], flags: &[&str], options: &[&str]) -> Result<Matches, Error> {
+    let mut matches = Matches {
+        flags: Vec::new(),
+        options: Vec::new(),
+        positional: Vec::new(),
+    };
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            matches.positional.extend(args.cloned());
+            break;
+        }
+
+        if arg == "-h" || arg == "--help" {
+            matches.flags.push("help".to_string());
+        } else if let Some(option) = arg.strip_prefix("--") {
+            let (name, value) = match option.find('=') {
+                Some(i) => (&option[..i], Some(option[i + 1..].to_string())),
+                None => (option, None),
+            };
+
+            if flags.contains(&name) {
+                if value.is_some() {
+                    return Err(Error::Usage(format!("option --{} does not take a value", name)));
+                }
+                matches.flags.push(name.to_string());
+            } else if options.contains(&name) {
+                let value = match value.or_else(|| args.next().cloned()) {
+                    Some(value) => value,
+                    None => return Err(Error::Usage(format!("missing value for option --{}", name))),
+                };
+                matches.options.push((name.to_string(), value));
+            } else {
+                return Err(Error::Usage(format!("unknown option --{}", name)));
+            }
+        } else if arg.len() > 1 && arg.starts_with('-') && arg.parse::<f64>().is_err() {
+            return Err(Error::Usage(format!("unknown option {}", arg)));
+        } else {
+            matches.positional.push(arg.clone());
+        }
+    }
+
+    Ok(matches)
+}
+
+impl Matches {
+    /// Return whether a flag is present.
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    /// Return all values of an option in command line order.
+    pub fn values(&self, name: &str) -> Vec<&str> {
+        self.options
+            .iter()
+            .filter(|&(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    /// Return the last value of an option, if present.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values(name).pop()
+    }
+
+    /// Parse the last value of an option, if present.
+    pub fn parse<T>(&self, name: &str) -> Result<Option<T>, Error>
+        where T: FromStr,
+              T::Err: fmt::Display
+    {
+        match self.value(name) {
+            Some(value) => parse_value(name, value).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Parse the last value of an option, which must be present.
+    pub fn required<T>(&self, name: &str) -> Result<T, Error>
+        where T: FromStr,
+              T::Err: fmt::Display
+    {
+        match self.parse(name)? {
+            Some(value) => Ok(value),
+            None => Err(Error::Usage(format!("missing required option --{}", name))),
+        }
+    }
+
+    /// Move the leading positional arguments into the named options, in order,
+    /// skipping options given explicitly. Any further positional arguments
+    /// are left in place.
+    pub fn name_positional(&mut self, names: &[&str]) {
+        let names: Vec<&str> = names.iter().cloned().filter(|name| self.value(name).is_none()).collect();
+        let count = names.len().min(self.positional.len());
+
+        for (name, value) in names.into_iter().zip(self.positional.drain(..count)) {
+            self.options.push((name.to_string(), value));
+        }
+    }
+
+    /// Return the positional arguments, checking there are between min and
+    /// max of them inclusive.
+    pub fn positional(&self, min: usize, max: usize) -> Result<&[String], Error> {
+        let count = self.positional.len();
+
+        if count < min {
+            Err(Error::Usage(format!("expected {} argument{}, found {}",
+                                     min,
+                                     if min == 1 { "" } else { "s" },
+                                     count)))
+        } else if count > max {
+            Err(Error::Usage(format!("unexpected argument {}", self.positional[max])))
+        } else {
+            Ok(&self.positional)
+        }
+    }
+}
+
+/// Parse the value of a named option.
+pub fn parse_value<T>(name: &str, value: &str) -> Result<T, Error>
+    where T: FromStr,
+          T::Err: fmt::Display
+{
+    value.parse()
+         .map_err(|err| Error::Usage(format!("invalid value '{}' for --{}: {}", value, name, err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Error};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_flags_options_and_positional_arguments() {
+        let matches = parse(&args(&["--qq", "a", "--plot", "x.svg", "--mean=-1", "b"]),
+                            &["qq", "pp"],
+                            &["plot", "mean"])
+                          .unwrap();
+
+        assert!(matches.flag("qq") && !matches.flag("pp"));
+        assert_eq!(matches.value("plot"), Some("x.svg"));
+        assert_eq!(matches.parse::<f64>("mean").unwrap(), Some(-1.0));
+        assert_eq!(matches.positional(2, 2).unwrap(), &args(&["a", "b"])[..]);
+    }
+
+    #[test]
+    fn parse_repeated_options_and_arguments_after_separator() {
+        let matches = parse(&args(&["--p", "1", "--p", "2", "--", "--p"]), &[], &["p"]).unwrap();
+
+        assert_eq!(matches.values("p"), vec!["1", "2"]);
+        assert_eq!(matches.value("p"), Some("2"));
+        assert_eq!(matches.positional(1, 1).unwrap(), &args(&["--p"])[..]);
+    }
+
+    #[test]
+    fn parse_rejects_invalid_usage() {
+        fn usage<T>(result: Result<T, Error>) -> String {
+            match result {
+                Err(Error::Usage(message)) => message,
+                _ => panic!("Expected usage error."),
+            }
+        }
+
+        assert_eq!(usage(parse(&args(&["--x"]), &[], &[])),
+                   "unknown option --x");
+        assert_eq!(usage(parse(&args(&["-x"]), &[], &[])),
+                   "unknown option -x");
+        assert_eq!(usage(parse(&args(&["--qq=1"]), &["qq"], &[])),
+                   "option --qq does not take a value");
+        assert_eq!(usage(parse(&args(&["--plot"]), &[], &["plot"])),
+                   "missing value for option --plot");
+
+        let matches = parse(&args(&["--count", "x", "a"]), &[], &["count"]).unwrap();
+        assert_eq!(usage(matches.required::<u32>("count")),
+                   "invalid value 'x' for --count: invalid digit found in string");
+        assert_eq!(usage(matches.required::<u32>("limit")),
+                   "missing required option --limit");
+        assert_eq!(usage(matches.positional(2, 2)),
+                   "expected 2 arguments, found 1");
+        assert_eq!(usage(matches.positional(0, 0)), "unexpected argument a");
+    }
+
+    #[test]
+    fn name_positional_arguments_after_options() {
+        let names = ["count", "mean", "variance"];
+
+        let mut matches = parse(&args(&["--seed", "3", "10", "0", "1"]), &[], &["seed"]).unwrap();
+        matches.name_positional(&names);
+        assert_eq!(matches.value("seed"), Some("3"));
+        assert_eq!(matches.value("count"), Some("10"));
+        assert_eq!(matches.value("mean"), Some("0"));
+        assert_eq!(matches.value("variance"), Some("1"));
+        assert_eq!(matches.positional(0, 0).unwrap().len(), 0);
+
+        let mut matches = parse(&args(&["--mean", "2", "10", "3", "x"]), &[], &["mean"]).unwrap();
+        matches.name_positional(&names);
+        assert_eq!(matches.value("count"), Some("10"));
+        assert_eq!(matches.value("mean"), Some("2"));
+        assert_eq!(matches.value("variance"), Some("3"));
+        assert_eq!(matches.positional(0, 1).unwrap(), &args(&["x"])[..]);
+
+        let mut matches = parse(&args(&["--help"]), &[], &[]).unwrap();
+        matches.name_positional(&names);
+        assert!(matches.flag("help") && matches.value("count").is_none());
+    }
+}
@@ -0,0 +1,1287 @@
+//! Subcommand implementations.
+
+use std::str::FromStr;
+
+use ks::{self, Alternative, Ecdf, KsTest, Method, Numeric, OrderableF64, Summary, TestOutcome,
+         TestResult, TwoSampleTest};
+use ks::bootstrap::{BlockBootstrap, Scheme};
+use ks::change_point;
+use ks::correction::{self, Correction};
+use ks::distributions::Normal;
+use ks::fit::{self, Estimator, Family};
+use ks::mann_whitney::{MannWhitneyResult, MannWhitneyTest};
+use ks::pairwise::{self, Comparison, PairwiseResult};
+use ks::power_law;
+use ks::qq;
+#[cfg(feature = "plot")]
+use ks::plot;
+
+use rand::{self, SeedableRng, StdRng};
+
+use super::args::{parse_value, Error, Matches};
+use super::distribution::{Distribution, Mixture, Spec};
+use super::input::{Input, Sample};
+use super::output::{print_json, Format, Json};
+use super::Status;
+use super::report::{ChangePointReport, CriticalValue, FitReport, MannWhitneyReport, NamedSummary,
+                    OneSampleReport, PairwiseReport, PowerLawReport, Quantile, TestReport};
+
+/// Test whether two samples are from the same distribution.
+pub fn test(matches: &Matches) -> Result<Status, Error> {
+    if matches.flag("integer") {
+        run_test::<i64>(matches)
+    } else {
+        run_test::<OrderableF64>(matches)
+    }
+}
+
+fn run_test<T: Sample>(matches: &Matches) -> Result<Status, Error> {
+    if let Some(spec) = matches.value("distribution") {
+        let spec: Spec = parse_value("distribution", spec)?;
+        return run_one_sample_test::<T>(matches, &spec);
+    }
+
+    let paths = matches.positional(1, 2)?;
+    let input = Input::new(matches)?;
+
+    if matches.flag("qq") && matches.flag("pp") {
+        return Err(Error::Usage("options --qq and --pp cannot be used together".to_string()));
+    }
+    if matches.flag("bands") && matches.value("plot").is_none() {
+        return Err(Error::Usage("option --bands requires --plot".to_string()));
+    }
+
+    let format = Format::from_matches(matches)?;
+    if matches.flag("text") && format != Format::Text {
+        return Err(Error::Usage("option --text requires --format text".to_string()));
+    }
+
+    let confidences = confidence_levels(matches)?;
+    let alternative: Alternative = matches.parse("alternative")?.unwrap_or(Alternative::TwoSided);
+    let kind: TestKind = matches.parse("test")?.unwrap_or(TestKind::KolmogorovSmirnov);
+    if kind == TestKind::MannWhitney {
+        for &option in ["block-length", "block-scheme", "bootstrap", "seed"].iter() {
+            if matches.value(option).is_some() {
+                return Err(Error::Usage(format!("option --{} cannot be used with --test \
+                                                 mann-whitney",
+                                                option)));
+            }
+        }
+    }
+    let method: Option<Method> = matches.parse("method")?;
+    let block_bootstrap = parse_block_bootstrap(matches)?;
+
+    let (xs, ys) = input.read_pair::<T>(paths)?;
+    let (name1, name2) = (&xs.name, &ys.name);
+    let (xs, ys) = (&xs.samples, &ys.samples);
+
+    write_plot(matches, name1, xs, name2, ys)?;
+
+    if matches.flag("qq") {
+        print_qq(format, xs, ys)?;
+        return Ok(Status::Success);
+    }
+    if matches.flag("pp") {
+        print_pp(format, xs, ys)?;
+        return Ok(Status::Success);
+    }
+
+    check_size(name1, xs.len())?;
+    check_size(name2, ys.len())?;
+
+    if kind == TestKind::MannWhitney {
+        let mut mann_whitney = MannWhitneyTest::new().alternative(alternative);
+        if let Some(method) = method {
+            mann_whitney = mann_whitney.method(method);
+        }
+
+        let results: Vec<_> = confidences.iter()
+            .map(|&confidence| mann_whitney.confidence(confidence).run(xs, ys))
+            .collect();
+        return print_mann_whitney(matches, alternative, name1, xs, name2, ys, &results);
+    }
+
+    let method = method.unwrap_or(Method::Asymptotic);
+    let mut ks_test = KsTest::two_sample().alternative(alternative).method(method);
+    if let Some(block_bootstrap) = block_bootstrap {
+        if block_bootstrap.block_length > xs.len().min(ys.len()) {
+            return Err(Error::Usage("--block-length must not exceed the sample sizes".to_string()));
+        }
+        ks_test = ks_test.block_bootstrap(block_bootstrap);
+    }
+
+    let results: Vec<_> = confidences.iter()
+        .map(|&confidence| ks_test.confidence(confidence).run(xs, ys))
+        .collect();
+
+    let status = test_status(&results);
+    let method = match block_bootstrap {
+        Some(ref block_bootstrap) => format!("{}-bootstrap", block_bootstrap.scheme),
+        None => method.to_string(),
+    };
+
+    match format {
+        Format::Json => {
+            print_json(&TestReport {
+                test: ks_test.name(),
+                alternative: alternative,
+                method: &method,
+                bootstrap: block_bootstrap,
+                samples: vec![named_summary(name1, xs), named_summary(name2, ys)],
+                results: &results,
+            })?;
+            return Ok(status);
+        }
+        Format::Tsv => {
+            print_results(alternative, &method, xs.len(), ys.len(), &results);
+            return Ok(status);
+        }
+        Format::Text => {}
+    }
+
+    if alternative != Alternative::TwoSided || method != Method::Asymptotic.to_string() {
+        println!("alternative = {}", alternative);
+        println!("method = {}", method);
+    }
+    if let Some(block_bootstrap) = block_bootstrap {
+        println!("block length = {}", block_bootstrap.block_length);
+        println!("resamples = {}", block_bootstrap.resamples);
+        println!("seed = {}", block_bootstrap.seed);
+    }
+
+    print_verdict(&results,
+                  "Samples are from the same distribution.",
+                  "Samples are from different distributions.");
+
+    print_text(matches, name1, xs, name2, ys)?;
+
+    println!();
+    print_summaries(&[name1, name2],
+                    &[Summary::new(&Ecdf::new(xs)), Summary::new(&Ecdf::new(ys))]);
+
+    Ok(status)
+}
+
+/// Print the results of Mann-Whitney U tests of two samples at one or more
+/// confidence levels.
+fn print_mann_whitney<T: Sample>(matches: &Matches,
+                                 alternative: Alternative,
+                                 name1: &str,
+                                 xs: &[T],
+                                 name2: &str,
+                                 ys: &[T],
+                                 results: &[MannWhitneyResult])
+                                 -> Result<Status, Error> {
+    let status = test_status(results);
+
+    match Format::from_matches(matches)? {
+        Format::Json => {
+            print_json(&MannWhitneyReport {
+                test: MannWhitneyTest::new().name(),
+                alternative: alternative,
+                samples: vec![named_summary(name1, xs), named_summary(name2, ys)],
+                results: results,
+            })?;
+            return Ok(status);
+        }
+        Format::Tsv => {
+            println!("alternative\tmethod\tn1\tn2\tconfidence\tstatistic\tz\teffect_size\t\
+                      p_value\tis_rejected");
+            for result in results {
+                println!("{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                         alternative,
+                         result.method,
+                         xs.len(),
+                         ys.len(),
+                         result.confidence,
+                         result.statistic,
+                         result.z,
+                         result.effect_size,
+                         result.p_value,
+                         result.is_rejected);
+            }
+            return Ok(status);
+        }
+        Format::Text => {}
+    }
+
+    println!("test = mann-whitney");
+    if alternative != Alternative::TwoSided {
+        println!("alternative = {}", alternative);
+    }
+    println!("method = {}", results[0].method);
+
+    if results.len() == 1 {
+        if results[0].is_rejected {
+            println!("Samples are from different distributions.");
+        } else {
+            println!("Samples are from the same distribution.");
+        }
+    }
+
+    println!("test statistic = {}", results[0].statistic);
+    println!("z = {}", results[0].z);
+    println!("effect size = {}", results[0].effect_size);
+    println!("p-value = {}", results[0].p_value);
+
+    if results.len() > 1 {
+        println!();
+        println!("confidence\tresult");
+        for result in results.iter() {
+            println!("{}\t{}",
+                     result.confidence,
+                     if result.is_rejected { "different" } else { "same" });
+        }
+    }
+
+    print_text(matches, name1, xs, name2, ys)?;
+
+    println!();
+    print_summaries(&[name1, name2],
+                    &[Summary::new(&Ecdf::new(xs)), Summary::new(&Ecdf::new(ys))]);
+
+    Ok(status)
+}
+
+/// Two sample test of the test and pairwise commands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TestKind {
+    KolmogorovSmirnov,
+    MannWhitney,
+}
+
+impl FromStr for TestKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<TestKind, String> {
+        match s {
+            "kolmogorov-smirnov" | "ks" => Ok(TestKind::KolmogorovSmirnov),
+            "mann-whitney" => Ok(TestKind::MannWhitney),
+            _ => Err("expected kolmogorov-smirnov or mann-whitney".to_string()),
+        }
+    }
+}
+
+/// Number of block bootstrap resamples of the two sample test by default.
+const DEFAULT_RESAMPLES: usize = 999;
+
+/// Parse the block bootstrap options of the two sample test, returning None if
+/// the p-values are calculated by the test method instead.
+fn parse_block_bootstrap(matches: &Matches) -> Result<Option<BlockBootstrap>, Error> {
+    let block_length: usize = match matches.parse("block-length")? {
+        Some(block_length) => block_length,
+        None => {
+            for &option in ["block-scheme", "bootstrap", "seed"].iter() {
+                if matches.value(option).is_some() {
+                    return Err(Error::Usage(format!("option --{} requires --block-length", option)));
+                }
+            }
+            return Ok(None);
+        }
+    };
+
+    if matches.value("method").is_some() {
+        return Err(Error::Usage("option --method cannot be used with --block-length".to_string()));
+    }
+    if block_length == 0 {
+        return Err(Error::Usage("--block-length must be positive".to_string()));
+    }
+
+    let resamples: usize = matches.parse("bootstrap")?.unwrap_or(DEFAULT_RESAMPLES);
+    if resamples == 0 {
+        return Err(Error::Usage("--bootstrap must be positive".to_string()));
+    }
+
+    Ok(Some(BlockBootstrap {
+        scheme: matches.parse("block-scheme")?.unwrap_or(Scheme::MovingBlock),
+        block_length: block_length,
+        resamples: resamples,
+        seed: matches.parse("seed")?.unwrap_or_else(rand::random),
+    }))
+}
+
+/// Test whether a sample is from a distribution.
+fn run_one_sample_test<T: Sample>(matches: &Matches, spec: &Spec) -> Result<Status, Error> {
+    let paths = matches.positional(1, 1)?;
+    let input = Input::new(matches)?;
+
+    for &flag in ["qq", "pp", "bands", "text"].iter() {
+        if matches.flag(flag) {
+            return Err(Error::Usage(format!("option --{} cannot be used with --distribution", flag)));
+        }
+    }
+    for &option in ["plot", "block-length", "block-scheme", "bootstrap", "seed", "test"].iter() {
+        if matches.value(option).is_some() {
+            return Err(Error::Usage(format!("option --{} cannot be used with --distribution",
+                                            option)));
+        }
+    }
+
+    let format = Format::from_matches(matches)?;
+    let confidences = confidence_levels(matches)?;
+    let alternative: Alternative = matches.parse("alternative")?.unwrap_or(Alternative::TwoSided);
+    let method: Method = matches.parse("method")?.unwrap_or(Method::Asymptotic);
+
+    if method != Method::Asymptotic {
+        return Err(Error::Usage("option --distribution only supports --method asymptotic"
+            .to_string()));
+    }
+
+    let mut samples = input.read::<T>(paths)?;
+    if samples.len() != 1 {
+        return Err(Error::Input(format!("expected one sample to test, found {}", samples.len())));
+    }
+    let xs = samples.pop().unwrap();
+    let (name, xs) = (&xs.name, &xs.samples);
+
+    check_size(name, xs.len())?;
+
+    let results: Vec<_> = confidences.iter()
+        .map(|&confidence| spec.distribution.test(xs, confidence, alternative))
+        .collect();
+
+    let status = test_status(&results);
+    let distribution = spec.to_string();
+
+    match format {
+        Format::Json => {
+            print_json(&OneSampleReport {
+                distribution: &distribution,
+                alternative: alternative,
+                sample: named_summary(name, xs),
+                results: &results,
+            })?;
+            return Ok(status);
+        }
+        Format::Tsv => {
+            print_one_sample_results(&distribution, alternative, xs.len(), &results);
+            return Ok(status);
+        }
+        Format::Text => {}
+    }
+
+    println!("distribution = {}", distribution);
+    if alternative != Alternative::TwoSided {
+        println!("alternative = {}", alternative);
+    }
+
+    print_verdict(&results,
+                  "Sample is from the distribution.",
+                  "Sample is not from the distribution.");
+
+    println!();
+    print_summaries(&[name], &[Summary::new(&Ecdf::new(xs))]);
+
+    Ok(status)
+}
+
+/// Exit status of tests at one or more confidence levels, rejected if any
+/// test rejects.
+fn test_status<R: TestOutcome>(results: &[R]) -> Status {
+    if results.iter().any(|result| result.is_rejected()) {
+        Status::Rejected
+    } else {
+        Status::Success
+    }
+}
+
+/// Print the outcome of tests at one or more confidence levels, followed by
+/// a table of the critical value and result at each level if several.
+fn print_verdict(results: &[TestResult], same: &str, different: &str) {
+    if results.len() == 1 {
+        let result = &results[0];
+
+        if result.is_rejected {
+            println!("{}", different);
+        } else {
+            println!("{}", same);
+        }
+
+        println!("test statistic = {}", result.statistic);
+        println!("critical value = {}", result.critical_value);
+        println!("reject probability = {}", result.reject_probability);
+        println!("p-value = {}", result.p_value);
+    } else {
+        println!("test statistic = {}", results[0].statistic);
+        println!("reject probability = {}", results[0].reject_probability);
+        println!("p-value = {}", results[0].p_value);
+
+        println!();
+        println!("confidence\tcritical value\tresult");
+        for result in results.iter() {
+            println!("{}\t{}\t{}",
+                     result.confidence,
+                     result.critical_value,
+                     if result.is_rejected { "different" } else { "same" });
+        }
+    }
+}
+
+/// Check a sample is large enough for the test, i.e. has more than 7 samples.
+fn check_size(name: &str, count: usize) -> Result<(), Error> {
+    if count <= 7 {
+        return Err(Error::Input(format!("{}: the test requires more than 7 samples, found {}",
+                                        name,
+                                        count)));
+    }
+
+    Ok(())
+}
+
+/// Parse confidence levels, which may be repeated options or comma separated
+/// lists, defaulting to 0.95.
+fn confidence_levels(matches: &Matches) -> Result<Vec<f64>, Error> {
+    let mut confidences = Vec::new();
+
+    for value in matches.values("confidence") {
+        for c in value.split(',') {
+            let c: f64 = parse_value("confidence", c.trim())?;
+            if !(0.0 < c && c < 1.0) {
+                return Err(Error::Usage(format!("confidence {} must be in (0, 1)", c)));
+            }
+            confidences.push(c);
+        }
+    }
+
+    if confidences.is_empty() {
+        confidences.push(0.95);
+    }
+
+    Ok(confidences)
+}
+
+/// Print the test results as a table with a row for each confidence level.
+fn print_results(alternative: Alternative,
+                 method: &str,
+                 n1: usize,
+                 n2: usize,
+                 results: &[TestResult]) {
+    println!("alternative\tmethod\tn1\tn2\tconfidence\tstatistic\tcritical_value\t\
+              reject_probability\tp_value\tis_rejected");
+    for result in results {
+        println!("{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                 alternative,
+                 method,
+                 n1,
+                 n2,
+                 result.confidence,
+                 result.statistic,
+                 result.critical_value,
+                 result.reject_probability,
+                 result.p_value,
+                 result.is_rejected);
+    }
+}
+
+/// Print the one sample test results as a table with a row for each
+/// confidence level.
+fn print_one_sample_results(distribution: &str,
+                            alternative: Alternative,
+                            n: usize,
+                            results: &[TestResult]) {
+    println!("distribution\talternative\tn\tconfidence\tstatistic\tcritical_value\t\
+              reject_probability\tp_value\tis_rejected");
+    for result in results {
+        println!("{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                 distribution,
+                 alternative,
+                 n,
+                 result.confidence,
+                 result.statistic,
+                 result.critical_value,
+                 result.reject_probability,
+                 result.p_value,
+                 result.is_rejected);
+    }
+}
+
+/// Print the quantile-quantile plot data of the two samples.
+fn print_qq<T: Sample>(format: Format, xs: &[T], ys: &[T]) -> Result<(), Error> {
+    let points = qq::qq(&Ecdf::new(xs), &Ecdf::new(ys));
+
+    if format == Format::Json {
+        return print_json(&points);
+    }
+
+    println!("probability\tx\ty");
+    for point in points {
+        println!("{}\t{}\t{}", point.probability, point.x, point.y);
+    }
+
+    Ok(())
+}
+
+/// Print the probability-probability plot data of the two samples.
+fn print_pp<T: Sample>(format: Format, xs: &[T], ys: &[T]) -> Result<(), Error> {
+    let points = qq::pp(&Ecdf::new(xs), &Ecdf::new(ys));
+
+    if format == Format::Json {
+        return print_json(&points);
+    }
+
+    println!("value\tx\ty");
+    for point in points {
+        println!("{}\t{}\t{}", point.value, point.x, point.y);
+    }
+
+    Ok(())
+}
+
+/// Write an SVG plot of the ECDFs of the two samples if requested.
+#[cfg(feature = "plot")]
+fn write_plot<T: Sample>(matches: &Matches,
+                         name1: &str,
+                         xs: &[T],
+                         name2: &str,
+                         ys: &[T])
+                         -> Result<(), Error> {
+    use std::fs::File;
+    use std::io::Write;
+
+    let path = match matches.value("plot") {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+
+    let options = plot::Options {
+        confidence_band: if matches.flag("bands") { Some(0.95) } else { None },
+        ..plot::Options::default()
+    };
+    let svg = plot::svg(&[(name1, &Ecdf::new(xs)), (name2, &Ecdf::new(ys))], &options);
+
+    File::create(path)
+        .and_then(|mut file| file.write_all(svg.as_bytes()))
+        .map_err(|err| Error::Input(format!("{}: {}", path, err)))
+}
+
+#[cfg(not(feature = "plot"))]
+fn write_plot<T: Sample>(matches: &Matches, _: &str, _: &[T], _: &str, _: &[T]) -> Result<(), Error> {
+    match matches.value("plot") {
+        Some(_) => Err(Error::Usage("option --plot requires the plot feature".to_string())),
+        None => Ok(()),
+    }
+}
+
+/// Print a text plot of the ECDFs of the two samples sized to the terminal if
+/// requested.
+#[cfg(feature = "plot")]
+fn print_text<T: Sample>(matches: &Matches,
+                         name1: &str,
+                         xs: &[T],
+                         name2: &str,
+                         ys: &[T])
+                         -> Result<(), Error> {
+    use std::io::{self, IsTerminal};
+
+    if !matches.flag("text") {
+        return Ok(());
+    }
+
+    let options = plot::TextOptions {
+        columns: terminal_columns().max(plot::MIN_TEXT_COLUMNS),
+        colour: io::stdout().is_terminal(),
+        ..plot::TextOptions::default()
+    };
+
+    println!();
+    print!("{}", plot::text(&[(name1, &Ecdf::new(xs)), (name2, &Ecdf::new(ys))], &options));
+
+    Ok(())
+}
+
+/// Width of the terminal in columns, from the `COLUMNS` environment variable
+/// if set, which shells rarely export, otherwise the size of the terminal on
+/// standard output, error or input, or 80 if there is no terminal.
+#[cfg(feature = "plot")]
+fn terminal_columns() -> usize {
+    use std::env;
+
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .or_else(terminal_size_columns)
+        .unwrap_or(80)
+}
+
+#[cfg(all(feature = "plot", unix))]
+fn terminal_size_columns() -> Option<usize> {
+    use libc::{ioctl, winsize, STDERR_FILENO, STDIN_FILENO, STDOUT_FILENO, TIOCGWINSZ};
+
+    [STDOUT_FILENO, STDERR_FILENO, STDIN_FILENO].iter()
+        .filter_map(|&fd| {
+            let mut size = winsize {
+                ws_row: 0,
+                ws_col: 0,
+                ws_xpixel: 0,
+                ws_ypixel: 0,
+            };
+            // Safety: TIOCGWINSZ only writes the window size to the winsize.
+            let result = unsafe { ioctl(fd, TIOCGWINSZ, &mut size) };
+
+            if result == 0 && size.ws_col > 0 { Some(size.ws_col as usize) } else { None }
+        })
+        .next()
+}
+
+#[cfg(all(feature = "plot", not(unix)))]
+fn terminal_size_columns() -> Option<usize> {
+    None
+}
+
+#[cfg(not(feature = "plot"))]
+fn print_text<T: Sample>(matches: &Matches, _: &str, _: &[T], _: &str, _: &[T]) -> Result<(), Error> {
+    if matches.flag("text") {
+        Err(Error::Usage("option --text requires the plot feature".to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Descriptive statistics of a named sample for JSON output.
+fn named_summary<'a, T: Sample>(name: &'a str, xs: &[T]) -> NamedSummary<'a> {
+    NamedSummary {
+        name: name,
+        summary: Summary::new(&Ecdf::new(xs)),
+    }
+}
+
+/// Print the descriptive statistics of samples side by side.
+fn print_summaries(names: &[&str], summaries: &[Summary]) {
+    fn row<F: Fn(&Summary) -> String>(label: &str, summaries: &[Summary], f: F) {
+        let values: Vec<String> = summaries.iter().map(f).collect();
+        println!("{}\t{}", label, values.join("\t"));
+    }
+
+    println!("statistic\t{}", names.join("\t"));
+    row("count", summaries, |s| s.count.to_string());
+    row("mean", summaries, |s| s.mean.to_string());
+    row("variance", summaries, |s| s.variance.to_string());
+    row("skewness", summaries, |s| s.skewness.to_string());
+    row("min", summaries, |s| s.min.to_string());
+    row("lower quartile", summaries, |s| s.lower_quartile.to_string());
+    row("median", summaries, |s| s.median.to_string());
+    row("upper quartile", summaries, |s| s.upper_quartile.to_string());
+    row("max", summaries, |s| s.max.to_string());
+    row("iqr", summaries, |s| s.iqr.to_string());
+    row("mad", summaries, |s| s.mad.to_string());
+}
+
+/// Read a single sample from a file.
+fn read_single<T: Sample>(matches: &Matches, paths: &[String]) -> Result<Vec<T>, Error> {
+    let mut samples = Input::new(matches)?.read::<T>(paths)?;
+
+    if samples.len() != 1 {
+        return Err(Error::Usage(format!("expected a single sample, found {}, select one with \
+                                         --column or --groups",
+                                        samples.len())));
+    }
+
+    Ok(samples.pop().unwrap().samples)
+}
+
+/// Print the empirical cumulative distribution function of a sample.
+pub fn ecdf(matches: &Matches) -> Result<Status, Error> {
+    if matches.flag("integer") {
+        run_ecdf::<i64>(matches)
+    } else {
+        run_ecdf::<OrderableF64>(matches)
+    }
+}
+
+fn run_ecdf<T: Sample>(matches: &Matches) -> Result<Status, Error> {
+    let paths = matches.positional(1, 1)?;
+    let format = Format::from_matches(matches)?;
+    let xs = read_single::<T>(matches, paths)?;
+    let ecdf = Ecdf::new(&xs);
+
+    if format == Format::Json {
+        print_json(&ecdf.steps().collect::<Vec<_>>())?;
+        return Ok(Status::Success);
+    }
+
+    println!("value\tcount\tprobability");
+    for step in ecdf.steps() {
+        println!("{}\t{}\t{}", step.value, step.count, step.probability);
+    }
+
+    Ok(Status::Success)
+}
+
+/// Probabilities at which quantiles are printed by default.
+const DEFAULT_PROBABILITIES: [f64; 9] = [0.01, 0.05, 0.1, 0.25, 0.5, 0.75, 0.9, 0.95, 0.99];
+
+/// Print quantiles of a sample.
+pub fn quantiles(matches: &Matches) -> Result<Status, Error> {
+    if matches.flag("integer") {
+        run_quantiles::<i64>(matches)
+    } else {
+        run_quantiles::<OrderableF64>(matches)
+    }
+}
+
+fn run_quantiles<T: Sample>(matches: &Matches) -> Result<Status, Error> {
+    let paths = matches.positional(1, 1)?;
+    let format = Format::from_matches(matches)?;
+
+    // Probabilities may be repeated options or comma separated lists.
+    let mut probabilities = Vec::new();
+    for value in matches.values("probability") {
+        for p in value.split(',') {
+            let p: f64 = parse_value("probability", p.trim())?;
+            if !(0.0 < p && p <= 1.0) {
+                return Err(Error::Usage(format!("probability {} must be in (0, 1]", p)));
+            }
+            probabilities.push(p);
+        }
+    }
+    if probabilities.is_empty() {
+        probabilities.extend_from_slice(&DEFAULT_PROBABILITIES);
+    }
+
+    let xs = read_single::<T>(matches, paths)?;
+    let ecdf = Ecdf::new(&xs);
+
+    let quantiles: Vec<Quantile<T>> = probabilities.into_iter()
+        .map(|p| {
+            Quantile {
+                probability: p,
+                quantile: ecdf.quantile(p),
+            }
+        })
+        .collect();
+
+    if format == Format::Json {
+        print_json(&quantiles)?;
+        return Ok(Status::Success);
+    }
+
+    println!("probability\tquantile");
+    for q in quantiles {
+        println!("{}\t{}", q.probability, q.quantile);
+    }
+
+    Ok(Status::Success)
+}
+
+/// Print critical values of the two sample test.
+pub fn critical_values(matches: &Matches) -> Result<Status, Error> {
+    matches.positional(0, 0)?;
+
+    let confidence: f64 = matches.required("confidence")?;
+    let n1: usize = matches.required("num-samples")?;
+    let limit: usize = matches.required("limit")?;
+    let format = Format::from_matches(matches)?;
+
+    if !(0.0 < confidence && confidence < 1.0) {
+        return Err(Error::Usage("--confidence must be strictly between zero and one".to_string()));
+    }
+    // Only supports samples of size > 7.
+    if n1 <= 7 {
+        return Err(Error::Usage("--num-samples must be greater than 7".to_string()));
+    }
+    if limit == 0 {
+        return Err(Error::Usage("--limit must be positive".to_string()));
+    }
+
+    let values: Vec<CriticalValue> = (16..(limit + 1))
+        .map(|n2| {
+            CriticalValue {
+                n1: n1,
+                n2: n2,
+                confidence: confidence,
+                critical_value: ks::calculate_critical_value(n1, n2, confidence),
+            }
+        })
+        .collect();
+
+    if format == Format::Json {
+        print_json(&values)?;
+        return Ok(Status::Success);
+    }
+
+    println!("n1\tn2\tconfidence\tcritical_value");
+    for value in values {
+        println!("{}\t{}\t{}\t{}",
+                 value.n1,
+                 value.n2,
+                 value.confidence,
+                 value.critical_value);
+    }
+
+    Ok(Status::Success)
+}
+
+/// Print random variates of a distribution or mixture of distributions.
+pub fn generate(matches: &Matches) -> Result<Status, Error> {
+    matches.positional(0, 0)?;
+
+    let n: u32 = matches.required("count")?;
+    let format = Format::from_matches(matches)?;
+
+    if n == 0 {
+        return Err(Error::Usage("--count must be positive".to_string()));
+    }
+
+    let mixture = parse_mixture(matches)?;
+
+    let mut rng = seeded_rng(matches)?;
+
+    let xs: Vec<f64> = (0..n).map(|_| mixture.sample(&mut rng)).collect();
+
+    if format == Format::Json {
+        print_json(&xs)?;
+        return Ok(Status::Success);
+    }
+
+    for x in xs {
+        println!("{}", x);
+    }
+
+    Ok(Status::Success)
+}
+
+/// Random number generator seeded by the --seed option, or randomly if absent.
+fn seeded_rng(matches: &Matches) -> Result<StdRng, Error> {
+    Ok(match matches.parse::<u64>("seed")? {
+        Some(seed) => SeedableRng::from_seed(&[seed as usize][..]),
+        None => SeedableRng::from_seed(&[rand::random::<usize>()][..]),
+    })
+}
+
+/// Parse the distributions and weights of the mixture to generate, which is
+/// a Normal distribution given by the mean and variance if no distribution is
+/// specified.
+fn parse_mixture(matches: &Matches) -> Result<Mixture, Error> {
+    let specs = matches.values("distribution");
+
+    if specs.is_empty() {
+        if matches.value("weights").is_some() {
+            return Err(Error::Usage("option --weights requires --distribution".to_string()));
+        }
+
+        let mean: f64 = matches.parse("mean")?.unwrap_or(0.0);
+        let variance: f64 = matches.parse("variance")?.unwrap_or(1.0);
+
+        if !mean.is_finite() {
+            return Err(Error::Usage("--mean must be finite".to_string()));
+        }
+        if !(variance > 0.0 && variance.is_finite()) {
+            return Err(Error::Usage("--variance must be positive".to_string()));
+        }
+
+        let normal = Normal::new(mean, variance.sqrt());
+        return Ok(Mixture::new(vec![(1.0, Distribution::Continuous(Box::new(normal)))]));
+    }
+
+    if matches.value("mean").is_some() || matches.value("variance").is_some() {
+        return Err(Error::Usage("options --mean and --variance cannot be used with --distribution"
+            .to_string()));
+    }
+
+    let distributions = specs.iter()
+        .map(|spec| parse_value::<Spec>("distribution", spec).map(|spec| spec.distribution))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let weights: Vec<f64> = match matches.value("weights") {
+        Some(value) => {
+            value.split(',')
+                 .map(|w| parse_value("weights", w.trim()))
+                 .collect::<Result<_, _>>()?
+        }
+        None => vec![1.0; distributions.len()],
+    };
+
+    if weights.len() != distributions.len() {
+        return Err(Error::Usage(format!("expected {} weights, one for each --distribution, found {}",
+                                        distributions.len(),
+                                        weights.len())));
+    }
+    if !weights.iter().all(|&w| w > 0.0 && w.is_finite()) {
+        return Err(Error::Usage("--weights must be positive".to_string()));
+    }
+
+    Ok(Mixture::new(weights.into_iter().zip(distributions).collect()))
+}
+
+/// Fit families of distributions to a sample and rank them by goodness-of-fit.
+pub fn fit(matches: &Matches) -> Result<Status, Error> {
+    let paths = matches.positional(1, 1)?;
+    let format = Format::from_matches(matches)?;
+
+    // Families may be repeated options or comma separated lists.
+    let mut families = Vec::new();
+    for value in matches.values("family") {
+        for family in value.split(',') {
+            families.push(parse_value::<Family>("family", family.trim())?);
+        }
+    }
+    if families.is_empty() {
+        families.extend_from_slice(&Family::ALL);
+    }
+
+    let estimator: Estimator = matches.parse("estimator")?.unwrap_or(Estimator::MaximumLikelihood);
+    let bootstraps: usize = matches.parse("bootstrap")?.unwrap_or(DEFAULT_BOOTSTRAPS);
+    let mut rng = seeded_rng(matches)?;
+
+    let mut samples = Input::new(matches)?.read::<OrderableF64>(paths)?;
+    if samples.len() != 1 {
+        return Err(Error::Input(format!("expected one sample to fit, found {}", samples.len())));
+    }
+    let xs = samples.pop().unwrap();
+    let (name, xs) = (&xs.name, &xs.samples);
+
+    check_size(name, xs.len())?;
+
+    let values: Vec<f64> = xs.iter().map(|x| x.to_f64()).collect();
+    let fits = fit::rank(&values, &families, estimator, bootstraps, &mut rng);
+
+    if format == Format::Json {
+        print_json(&FitReport {
+            estimator: estimator,
+            bootstraps: bootstraps,
+            sample: named_summary(name, xs),
+            fits: &fits,
+        })?;
+        return Ok(Status::Success);
+    }
+
+    if format == Format::Text {
+        println!("estimator = {}", estimator);
+        println!("bootstraps = {}", bootstraps);
+        println!();
+    }
+
+    println!("rank\tfamily\tdistribution\tstatistic\tp_value");
+    for (i, fit) in fits.iter().enumerate() {
+        println!("{}\t{}\t{}\t{}\t{}",
+                 i + 1,
+                 fit.family,
+                 fit.distribution,
+                 fit.statistic,
+                 fit.p_value.map_or("NA".to_string(), |p| p.to_string()));
+    }
+
+    Ok(Status::Success)
+}
+
+/// Number of parametric bootstrap samples of each fit by default.
+const DEFAULT_BOOTSTRAPS: usize = 100;
+
+/// Fit a power law to the tail of a sample.
+pub fn power_law(matches: &Matches) -> Result<Status, Error> {
+    let paths = matches.positional(1, 1)?;
+    let format = Format::from_matches(matches)?;
+    let bootstraps: usize = matches.parse("bootstrap")?.unwrap_or(DEFAULT_BOOTSTRAPS);
+    let mut rng = seeded_rng(matches)?;
+
+    let mut samples = Input::new(matches)?.read::<OrderableF64>(paths)?;
+    if samples.len() != 1 {
+        return Err(Error::Input(format!("expected one sample to fit, found {}", samples.len())));
+    }
+    let xs = samples.pop().unwrap();
+    let (name, xs) = (&xs.name, &xs.samples);
+
+    check_size(name, xs.len())?;
+
+    let values: Vec<f64> = xs.iter().map(|x| x.to_f64()).collect();
+    let power_law = match power_law::fit(&values, bootstraps, &mut rng) {
+        Some(power_law) => power_law,
+        None => {
+            return Err(Error::Input(format!("{}: no tail of more than 7 distinct positive values \
+                                             to fit",
+                                            name)))
+        }
+    };
+    let p_value = power_law.p_value.map_or("NA".to_string(), |p| p.to_string());
+
+    match format {
+        Format::Json => {
+            print_json(&PowerLawReport {
+                bootstraps: bootstraps,
+                sample: named_summary(name, xs),
+                power_law: &power_law,
+            })?;
+        }
+        Format::Tsv => {
+            println!("xmin\talpha\talpha_error\ttail_count\tstatistic\tp_value");
+            println!("{}\t{}\t{}\t{}\t{}\t{}",
+                     power_law.xmin,
+                     power_law.alpha,
+                     power_law.alpha_error,
+                     power_law.tail_count,
+                     power_law.statistic,
+                     p_value);
+        }
+        Format::Text => {
+            println!("xmin = {}", power_law.xmin);
+            println!("alpha = {}", power_law.alpha);
+            println!("alpha standard error = {}", power_law.alpha_error);
+            println!("tail count = {} of {}", power_law.tail_count, xs.len());
+            println!("test statistic = {}", power_law.statistic);
+            println!("p-value = {}", p_value);
+        }
+    }
+
+    Ok(Status::Success)
+}
+
+/// Detect change points in a series.
+pub fn change_points(matches: &Matches) -> Result<Status, Error> {
+    if matches.flag("integer") {
+        run_change_points::<i64>(matches)
+    } else {
+        run_change_points::<OrderableF64>(matches)
+    }
+}
+
+fn run_change_points<T: Sample>(matches: &Matches) -> Result<Status, Error> {
+    let paths = matches.positional(1, 1)?;
+    let format = Format::from_matches(matches)?;
+
+    let confidence: f64 = matches.parse("confidence")?.unwrap_or(0.95);
+    if !(0.0 < confidence && confidence < 1.0) {
+        return Err(Error::Usage(format!("confidence {} must be in (0, 1)", confidence)));
+    }
+    let permutations: usize = matches.parse("permutations")?.unwrap_or(DEFAULT_PERMUTATIONS);
+    if permutations == 0 {
+        return Err(Error::Usage("--permutations must be positive".to_string()));
+    }
+    let mut rng = seeded_rng(matches)?;
+
+    let mut samples = Input::new(matches)?.read::<T>(paths)?;
+    if samples.len() != 1 {
+        return Err(Error::Input(format!("expected one series, found {}", samples.len())));
+    }
+    let series = samples.pop().unwrap();
+    let (name, series) = (&series.name, &series.samples);
+
+    let change_points = change_point::detect(series, confidence, permutations, &mut rng);
+    let status = if change_points.is_empty() {
+        Status::Success
+    } else {
+        Status::Rejected
+    };
+
+    if format == Format::Json {
+        print_json(&ChangePointReport {
+            confidence: confidence,
+            permutations: permutations,
+            series: named_summary(name, series),
+            change_points: &change_points,
+        })?;
+        return Ok(status);
+    }
+
+    if format == Format::Text && change_points.is_empty() {
+        println!("No change points.");
+        return Ok(status);
+    }
+
+    println!("index\tstart\tend\tstatistic\tp_value");
+    for change_point in change_points {
+        println!("{}\t{}\t{}\t{}\t{}",
+                 change_point.index,
+                 change_point.start,
+                 change_point.end,
+                 change_point.statistic,
+                 change_point.p_value);
+    }
+
+    Ok(status)
+}
+
+/// Number of permutations of each segment scanned for change points by
+/// default.
+const DEFAULT_PERMUTATIONS: usize = 199;
+
+/// Test every pair of samples for differences in distribution.
+pub fn pairwise(matches: &Matches) -> Result<Status, Error> {
+    if matches.flag("integer") {
+        run_pairwise::<i64>(matches)
+    } else {
+        run_pairwise::<OrderableF64>(matches)
+    }
+}
+
+fn run_pairwise<T: Sample + Sync>(matches: &Matches) -> Result<Status, Error> {
+    let paths = matches.positional(1, usize::MAX)?;
+    let format = Format::from_matches(matches)?;
+
+    let confidence: f64 = matches.parse("confidence")?.unwrap_or(0.95);
+    if !(0.0 < confidence && confidence < 1.0) {
+        return Err(Error::Usage(format!("confidence {} must be in (0, 1)", confidence)));
+    }
+    let kind: TestKind = matches.parse("test")?.unwrap_or(TestKind::KolmogorovSmirnov);
+    let method: Option<Method> = matches.parse("method")?;
+    let correction: Correction = matches.parse("correction")?.unwrap_or(Correction::Bonferroni);
+
+    let samples = Input::new(matches)?.read::<T>(paths)?;
+    if samples.len() < 2 {
+        return Err(Error::Usage(format!("expected at least two samples, found {}", samples.len())));
+    }
+    for xs in samples.iter() {
+        check_size(&xs.name, xs.samples.len())?;
+    }
+
+    let names: Vec<&str> = samples.iter().map(|xs| xs.name.as_str()).collect();
+    let slices: Vec<&[T]> = samples.iter().map(|xs| &xs.samples[..]).collect();
+
+    match kind {
+        TestKind::KolmogorovSmirnov => {
+            let method = method.unwrap_or(Method::Asymptotic);
+            let result = pairwise::pairwise(&slices, confidence, method, correction);
+            let ks_test = KsTest::two_sample().method(method);
+
+            print_pairwise(format, &ks_test, &names, confidence, Some(method), correction, &result)
+        }
+        TestKind::MannWhitney => {
+            let mut mann_whitney = MannWhitneyTest::new().confidence(confidence);
+            if let Some(method) = method {
+                mann_whitney = mann_whitney.method(method);
+            }
+            let result = pairwise::pairwise_with(&slices, &mann_whitney, confidence, correction);
+
+            print_pairwise(format, &mann_whitney, &names, confidence, method, correction, &result)
+        }
+    }
+}
+
+/// Print the comparisons of pairwise tests of samples.
+fn print_pairwise<S, R>(format: Format,
+                        test: &S,
+                        names: &[&str],
+                        confidence: f64,
+                        method: Option<Method>,
+                        correction: Correction,
+                        result: &PairwiseResult<R>)
+                        -> Result<Status, Error>
+    where S: TwoSampleTest,
+          R: TestOutcome + Json
+{
+    let significant = result.significant();
+    let status = if significant.is_empty() { Status::Success } else { Status::Rejected };
+
+    match format {
+        Format::Json => {
+            print_json(&PairwiseReport {
+                test: test.name(),
+                samples: names.to_vec(),
+                confidence: confidence,
+                method: method,
+                correction: correction,
+                statistics: result.statistics(),
+                comparisons: &result.comparisons,
+            })?;
+        }
+        Format::Tsv => print_comparisons(names, &result.comparisons),
+        Format::Text => {
+            println!("sample\t{}", names.join("\t"));
+            for (name, row) in names.iter().zip(result.statistics()) {
+                let row: Vec<String> = row.iter().map(|d| d.to_string()).collect();
+                println!("{}\t{}", name, row.join("\t"));
+            }
+
+            println!();
+            println!("{} of {} pairs differ at confidence {} with {} correction.",
+                     significant.len(),
+                     result.comparisons.len(),
+                     confidence,
+                     correction);
+
+            if !significant.is_empty() {
+                println!();
+                print_comparisons(names, significant);
+            }
+        }
+    }
+
+    Ok(status)
+}
+
+/// Print a table of comparisons of pairs of samples.
+fn print_comparisons<'a, I, R>(names: &[&str], comparisons: I)
+    where I: IntoIterator<Item = &'a Comparison<R>>,
+          R: TestOutcome + 'a
+{
+    println!("sample1\tsample2\tstatistic\tp_value\tadjusted_p_value\tis_significant");
+    for comparison in comparisons {
+        println!("{}\t{}\t{}\t{}\t{}\t{}",
+                 names[comparison.i],
+                 names[comparison.j],
+                 comparison.result.statistic(),
+                 comparison.result.p_value(),
+                 comparison.adjusted_p_value,
+                 comparison.is_significant);
+    }
+}
+
+/// Adjust a batch of p-values for multiple testing.
+pub fn adjust(matches: &Matches) -> Result<Status, Error> {
+    let paths = matches.positional(1, 1)?;
+    let format = Format::from_matches(matches)?;
+
+    let confidence: f64 = matches.parse("confidence")?.unwrap_or(0.95);
+    if !(0.0 < confidence && confidence < 1.0) {
+        return Err(Error::Usage(format!("confidence {} must be in (0, 1)", confidence)));
+    }
+    let correction: Correction = matches.parse("correction")?.unwrap_or(Correction::Bonferroni);
+
+    let p_values: Vec<f64> = read_single::<OrderableF64>(matches, paths)?
+        .iter()
+        .map(|p| p.to_f64())
+        .collect();
+    if let Some(p) = p_values.iter().find(|p| !(0.0..=1.0).contains(*p)) {
+        return Err(Error::Input(format!("p-value {} must be in [0, 1]", p)));
+    }
+
+    let decisions = correction::correct(&p_values, correction, confidence);
+    let status = if decisions.iter().any(|decision| decision.is_rejected) {
+        Status::Rejected
+    } else {
+        Status::Success
+    };
+
+    if format == Format::Json {
+        print_json(&decisions)?;
+        return Ok(status);
+    }
+
+    println!("p_value\tadjusted_p_value\tis_rejected");
+    for decision in decisions {
+        println!("{}\t{}\t{}",
+                 decision.p_value,
+                 decision.adjusted_p_value,
+                 decision.is_rejected);
+    }
+
+    Ok(status)
+}
+
+/// Print descriptive statistics of samples side by side.
+pub fn summary(matches: &Matches) -> Result<Status, Error> {
+    if matches.flag("integer") {
+        run_summary::<i64>(matches)
+    } else {
+        run_summary::<OrderableF64>(matches)
+    }
+}
+
+fn run_summary<T: Sample>(matches: &Matches) -> Result<Status, Error> {
+    let paths = matches.positional(1, usize::MAX)?;
+    let format = Format::from_matches(matches)?;
+    let samples = Input::new(matches)?.read::<T>(paths)?;
+
+    if format == Format::Json {
+        let summaries: Vec<NamedSummary> = samples.iter()
+            .map(|xs| named_summary(&xs.name, &xs.samples))
+            .collect();
+        print_json(&summaries)?;
+        return Ok(Status::Success);
+    }
+
+    let names: Vec<&str> = samples.iter().map(|xs| xs.name.as_str()).collect();
+    let summaries: Vec<Summary> = samples.iter().map(|xs| Summary::new(&Ecdf::new(&xs.samples))).collect();
+    print_summaries(&names, &summaries);
+
+    Ok(Status::Success)
+}
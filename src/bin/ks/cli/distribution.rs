@@ -0,0 +1,268 @@
+//! Distributions given on the command line.
+//!
+//! Distributions are specified as a name followed by a colon and comma
+//! separated parameters, e.g. `gamma:2,1.5`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use rand::Rng;
+
+use ks::{Alternative, Numeric, TestResult};
+use ks::distributions::{Beta, Binomial, Cauchy, ContinuousDistribution, DiscreteDistribution,
+                        Exponential, Gamma, Geometric, LogNormal, Normal, Pareto, Poisson,
+                        StudentT, Uniform, Weibull};
+use ks::test::{test_one_sample, test_one_sample_discrete};
+
+/// Help text listing the distributions and their parameters.
+pub const DISTRIBUTIONS_USAGE: &str = "\
+Distributions:
+  uniform:<low>,<high>        Uniform on [low, high] [default: 0,1]
+  normal:<mean>,<std-dev>     Normal [default: 0,1]
+  lognormal:<mu>,<sigma>      Log-normal, exp of Normal(mu, sigma) [default: 0,1]
+  exponential:<rate>          Exponential [default: 1]
+  gamma:<shape>,<scale>       Gamma
+  beta:<alpha>,<beta>         Beta
+  pareto:<scale>,<shape>      Pareto with minimum value scale
+  weibull:<scale>,<shape>     Weibull
+  cauchy:<location>,<scale>   Cauchy [default: 0,1]
+  student-t:<df>              Student's t with df degrees of freedom
+  poisson:<lambda>            Poisson with mean lambda
+  binomial:<trials>,<p>       Binomial
+  geometric:<p>               Number of failures before the first success";
+
+/// Distribution given by a specification.
+pub enum Distribution {
+    Continuous(Box<dyn ContinuousDistribution>),
+    Discrete(Box<dyn DiscreteDistribution>),
+}
+
+impl Distribution {
+    /// Generate a random variate.
+    pub fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        match *self {
+            Distribution::Continuous(ref distribution) => distribution.sample(rng),
+            Distribution::Discrete(ref distribution) => distribution.sample(rng) as f64,
+        }
+    }
+
+    /// Perform a one sample test of whether a sample is from the distribution.
+    pub fn test<T: Numeric>(&self,
+                            xs: &[T],
+                            confidence: f64,
+                            alternative: Alternative)
+                            -> TestResult {
+        match *self {
+            Distribution::Continuous(ref distribution) => {
+                test_one_sample(xs, &**distribution, confidence, alternative)
+            }
+            Distribution::Discrete(ref distribution) => {
+                test_one_sample_discrete(xs, &**distribution, confidence, alternative)
+            }
+        }
+    }
+}
+
+/// Distribution with its specification in canonical form.
+pub struct Spec {
+    pub distribution: Distribution,
+    spec: String,
+}
+
+impl fmt::Display for Spec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.spec.fmt(f)
+    }
+}
+
+fn continuous<D: ContinuousDistribution + fmt::Display + 'static>(distribution: D) -> Spec {
+    Spec {
+        spec: distribution.to_string(),
+        distribution: Distribution::Continuous(Box::new(distribution)),
+    }
+}
+
+fn discrete<D: DiscreteDistribution + fmt::Display + 'static>(distribution: D) -> Spec {
+    Spec {
+        spec: distribution.to_string(),
+        distribution: Distribution::Discrete(Box::new(distribution)),
+    }
+}
+
+impl FromStr for Spec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Spec, String> {
+        let (name, parameters) = match s.find(':') {
+            Some(i) => (&s[..i], Some(&s[i + 1..])),
+            None => (s, None),
+        };
+
+        let (usage, defaults): (&str, &[f64]) = match name {
+            "uniform" => ("uniform:<low>,<high>", &[0.0, 1.0]),
+            "normal" => ("normal:<mean>,<std-dev>", &[0.0, 1.0]),
+            "lognormal" => ("lognormal:<mu>,<sigma>", &[0.0, 1.0]),
+            "exponential" => ("exponential:<rate>", &[1.0]),
+            "gamma" => ("gamma:<shape>,<scale>", &[]),
+            "beta" => ("beta:<alpha>,<beta>", &[]),
+            "pareto" => ("pareto:<scale>,<shape>", &[]),
+            "weibull" => ("weibull:<scale>,<shape>", &[]),
+            "cauchy" => ("cauchy:<location>,<scale>", &[0.0, 1.0]),
+            "student-t" => ("student-t:<df>", &[]),
+            "poisson" => ("poisson:<lambda>", &[]),
+            "binomial" => ("binomial:<trials>,<p>", &[]),
+            "geometric" => ("geometric:<p>", &[]),
+            _ => return Err(format!("unknown distribution '{}'", name)),
+        };
+        let expected = || format!("expected {}", usage);
+
+        let values: Vec<f64> = match parameters {
+            Some(parameters) => {
+                parameters.split(',')
+                          .map(|value| value.trim().parse::<f64>().map_err(|_| expected()))
+                          .collect::<Result<_, _>>()?
+            }
+            None => defaults.to_vec(),
+        };
+
+        // Number of parameters from the placeholders in the usage.
+        if values.len() != usage.matches('<').count() || values.iter().any(|x| !x.is_finite()) {
+            return Err(expected());
+        }
+
+        // Check the parameters here as the distributions panic on invalid ones.
+        let positive = |x: f64| x > 0.0;
+        let probability = |x: f64| (0.0..=1.0).contains(&x);
+        let (a, b) = (values[0], values.get(1).cloned().unwrap_or(1.0));
+
+        let valid = match name {
+            "uniform" => a < b,
+            "normal" | "lognormal" | "cauchy" => positive(b),
+            "exponential" | "student-t" => positive(a),
+            "poisson" => a >= 0.0,
+            "binomial" => a >= 0.0 && a.fract() == 0.0 && probability(b),
+            "geometric" => positive(a) && probability(a),
+            _ => positive(a) && positive(b),
+        };
+
+        if !valid {
+            return Err(format!("invalid parameters for {}", usage));
+        }
+
+        Ok(match name {
+            "uniform" => continuous(Uniform::new(a, b)),
+            "normal" => continuous(Normal::new(a, b)),
+            "lognormal" => continuous(LogNormal::new(a, b)),
+            "exponential" => continuous(Exponential::new(a)),
+            "gamma" => continuous(Gamma::new(a, b)),
+            "beta" => continuous(Beta::new(a, b)),
+            "pareto" => continuous(Pareto::new(a, b)),
+            "weibull" => continuous(Weibull::new(a, b)),
+            "cauchy" => continuous(Cauchy::new(a, b)),
+            "student-t" => continuous(StudentT::new(a)),
+            "poisson" => discrete(Poisson::new(a)),
+            "binomial" => discrete(Binomial::new(a as u64, b)),
+            _ => discrete(Geometric::new(a)),
+        })
+    }
+}
+
+/// Mixture of distributions, sampling each component with probability
+/// proportional to its weight.
+pub struct Mixture {
+    components: Vec<(f64, Distribution)>,
+    total: f64,
+}
+
+impl Mixture {
+    /// Create a mixture of weighted components. The weights must be positive.
+    pub fn new(components: Vec<(f64, Distribution)>) -> Mixture {
+        assert!(!components.is_empty() && components.iter().all(|&(weight, _)| weight > 0.0));
+
+        let total = components.iter().map(|&(weight, _)| weight).sum();
+
+        Mixture {
+            components: components,
+            total: total,
+        }
+    }
+
+    /// Generate a random variate.
+    pub fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        let mut u = rng.next_f64() * self.total;
+
+        for &(weight, ref distribution) in self.components.iter() {
+            if u < weight {
+                return distribution.sample(rng);
+            }
+            u -= weight;
+        }
+
+        // Rounding may leave u just above the last weight.
+        self.components.last().unwrap().1.sample(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, StdRng};
+
+    use ks::{Alternative, Numeric, OrderableF64};
+
+    use super::{Mixture, Spec};
+
+    fn parse(spec: &str) -> Result<Spec, String> {
+        spec.parse()
+    }
+
+    #[test]
+    fn distributions_round_trip_through_strings() {
+        let specs = ["uniform:-1,2", "normal:1,0.5", "lognormal:0,1", "exponential:2",
+                     "gamma:2,3", "beta:2,5", "pareto:1,3", "weibull:2,1.5", "cauchy:0,2",
+                     "student-t:3", "poisson:4.5", "binomial:10,0.25", "geometric:0.5"];
+
+        for &spec in specs.iter() {
+            assert_eq!(parse(spec).unwrap().to_string(), spec);
+        }
+
+        assert_eq!(parse("normal").unwrap().to_string(), "normal:0,1");
+        assert_eq!(parse("gamma").err(),
+                   Some("expected gamma:<shape>,<scale>".to_string()));
+        assert_eq!(parse("gamma:1,-1").err(),
+                   Some("invalid parameters for gamma:<shape>,<scale>".to_string()));
+        assert_eq!(parse("binomial:2.5,0.5").err(),
+                   Some("invalid parameters for binomial:<trials>,<p>".to_string()));
+        assert_eq!(parse("geometric:0").err(),
+                   Some("invalid parameters for geometric:<p>".to_string()));
+        assert!(parse("levy").is_err());
+    }
+
+    #[test]
+    fn seeded_samples_are_reproducible() {
+        let mixture = Mixture::new(vec![(1.0, parse("normal:0,1").unwrap().distribution),
+                                        (3.0, parse("poisson:10").unwrap().distribution)]);
+
+        let mut rng1 = StdRng::from_seed(&[7]);
+        let mut rng2 = StdRng::from_seed(&[7]);
+
+        for _ in 0..100 {
+            assert_eq!(mixture.sample(&mut rng1), mixture.sample(&mut rng2));
+        }
+    }
+
+    #[test]
+    fn samples_are_tested_against_their_distribution() {
+        let mut rng = StdRng::from_seed(&[42]);
+
+        for &spec in ["gamma:2,3", "student-t:3", "poisson:4.5", "binomial:20,0.25"].iter() {
+            let distribution = parse(spec).unwrap().distribution;
+            let xs: Vec<OrderableF64> = (0..1000)
+                .map(|_| OrderableF64::new(distribution.sample(&mut rng)))
+                .collect();
+            let ys: Vec<OrderableF64> = xs.iter().map(|x| OrderableF64::new(x.to_f64() + 1.0)).collect();
+
+            assert!(!distribution.test(&xs, 0.99, Alternative::TwoSided).is_rejected);
+            assert!(distribution.test(&ys, 0.99, Alternative::TwoSided).is_rejected);
+        }
+    }
+}
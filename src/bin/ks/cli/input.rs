@@ -0,0 +1,564 @@
+//! Sample data file input.
+//!
+//! Data files are delimited text files, e.g. TSV or CSV, with an optional
+//! header line. Samples are read from a selected column, by default the first,
+//! and may be split into groups by the values of a label column.
+
+use ks::{Numeric, OrderableF64};
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::args::{Error, Matches};
+
+/// Sample types that can be read from data files.
+#[cfg(not(feature = "serde"))]
+pub trait Sample: Numeric + fmt::Display {
+    fn parse(s: &str) -> Result<Self, String>;
+}
+
+/// Sample types that can be read from data files, and written as JSON.
+#[cfg(feature = "serde")]
+pub trait Sample: Numeric + fmt::Display + Serialize {
+    fn parse(s: &str) -> Result<Self, String>;
+}
+
+impl Sample for i64 {
+    fn parse(s: &str) -> Result<i64, String> {
+        s.parse().map_err(|_| "not an integer".to_string())
+    }
+}
+
+impl Sample for OrderableF64 {
+    fn parse(s: &str) -> Result<OrderableF64, String> {
+        match s.parse::<f64>() {
+            Ok(x) if x.is_nan() => Err("NaN is not orderable".to_string()),
+            Ok(x) => Ok(OrderableF64::new(x)),
+            Err(_) => Err("not a floating point number".to_string()),
+        }
+    }
+}
+
+/// Flags controlling data file input.
+pub const INPUT_FLAGS: [&str; 2] = ["header", "skip-invalid"];
+
+/// Options controlling data file input.
+pub const INPUT_OPTIONS: [&str; 5] = ["column", "delimiter", "comment", "group-by", "groups"];
+
+/// Help text for the data file input options.
+pub const INPUT_USAGE: &str = "\
+Input options:
+  --header           The first line of each file is a header of column names
+  --column <c>       Column of samples by name or index from 1 [default: 1]
+  --delimiter <d>    Field delimiter, a character or tab [default: comma for
+                     .csv files, tab otherwise]
+  --comment <c>      Lines starting with this character are ignored [default: #]
+  --skip-invalid     Skip rows with a missing or invalid sample instead of
+                     failing, reporting the number of rows skipped
+  --group-by <c>     Split the samples of a file into groups by the values of
+                     this label column
+  --groups <a,b,..>  Select groups by label, in order [default: all groups]
+
+A file named - is read from standard input, and a directory is read as each
+of the files in it in name order.";
+
+/// Path of a data file read from standard input.
+const STDIN_PATH: &str = "-";
+
+/// Name of standard input in sample names and messages.
+const STDIN_NAME: &str = "stdin";
+
+/// Column of a data file, by name or index from zero.
+#[derive(Debug, Clone, PartialEq)]
+enum Column {
+    Index(usize),
+    Name(String),
+}
+
+impl Column {
+    /// Parse a column by name, or index counting from one.
+    fn parse(name: &str, value: &str) -> Result<Column, Error> {
+        match value.parse::<usize>() {
+            Ok(0) => Err(Error::Usage(format!("--{} indices count from 1", name))),
+            Ok(index) => Ok(Column::Index(index - 1)),
+            Err(_) => Ok(Column::Name(value.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Column::Index(index) => (index + 1).fmt(f),
+            Column::Name(ref name) => name.fmt(f),
+        }
+    }
+}
+
+/// Data file input options.
+pub struct Input {
+    header: bool,
+    columns: Vec<Column>,
+    delimiter: Option<char>,
+    comment: char,
+    skip_invalid: bool,
+    group_by: Option<Column>,
+    groups: Vec<String>,
+}
+
+/// Named sample read from a data file.
+pub struct Samples<T> {
+    pub name: String,
+    pub samples: Vec<T>,
+}
+
+impl Input {
+    /// Input options from the command line.
+    pub fn new(matches: &Matches) -> Result<Input, Error> {
+        let delimiter = match matches.value("delimiter") {
+            None => None,
+            Some("tab") | Some("\\t") => Some('\t'),
+            Some(d) if d.chars().count() == 1 => d.chars().next(),
+            Some(d) => return Err(Error::Usage(format!("invalid delimiter '{}'", d))),
+        };
+
+        let comment = matches.value("comment").unwrap_or("#");
+        if comment.chars().count() != 1 {
+            return Err(Error::Usage(format!("invalid comment character '{}'", comment)));
+        }
+
+        let columns = matches.values("column")
+                             .iter()
+                             .map(|value| Column::parse("column", value))
+                             .collect::<Result<Vec<_>, _>>()?;
+
+        let group_by = match matches.value("group-by") {
+            Some(value) => Some(Column::parse("group-by", value)?),
+            None => None,
+        };
+
+        let groups: Vec<String> = matches.values("groups")
+                                         .iter()
+                                         .flat_map(|value| value.split(','))
+                                         .map(|group| group.trim().to_string())
+                                         .collect();
+
+        if group_by.is_none() && !groups.is_empty() {
+            return Err(Error::Usage("option --groups requires --group-by".to_string()));
+        }
+
+        Ok(Input {
+            header: matches.flag("header"),
+            columns: columns,
+            delimiter: delimiter,
+            comment: comment.chars().next().unwrap(),
+            skip_invalid: matches.flag("skip-invalid"),
+            group_by: group_by,
+            groups: groups,
+        })
+    }
+
+    /// Read the samples of each file, or of each group when grouping by a
+    /// label column.
+    ///
+    /// Each file is read from the same column. If there is a single file,
+    /// several columns may be specified instead to read each of them.
+    pub fn read<T: Sample>(&self, paths: &[String]) -> Result<Vec<Samples<T>>, Error> {
+        let paths = &expand_directories(paths)?;
+
+        if self.columns.len() > 1 && (paths.len() > 1 || self.group_by.is_some()) {
+            return Err(Error::Usage("several --column options require a single file and no \
+                                     --group-by"
+                                        .to_string()));
+        }
+
+        if paths.iter().filter(|&path| path == STDIN_PATH).count() > 1 {
+            return Err(Error::Usage("standard input can only be read once".to_string()));
+        }
+
+        let mut samples = Vec::new();
+
+        for path in paths {
+            let table = self.read_table(path)?;
+            // Name samples after the table, where standard input is stdin.
+            let path = &table.path;
+
+            match (&self.group_by, self.columns.len()) {
+                (Some(group_by), _) => {
+                    for mut group in table.groups(self.column(0), group_by, &self.groups, self)? {
+                        // Distinguish groups with the same label in several files.
+                        if paths.len() > 1 {
+                            group.name = format!("{}:{}", path, group.name);
+                        }
+                        samples.push(group);
+                    }
+                }
+                (None, 0) | (None, 1) => {
+                    let column = table.index(self.column(0))?;
+                    let name = match self.columns.first() {
+                        Some(column) => format!("{}:{}", path, column),
+                        None => path.clone(),
+                    };
+
+                    samples.push(Samples {
+                        name: name,
+                        samples: table.samples(column, None, self)?,
+                    });
+                }
+                (None, _) => {
+                    for column in self.columns.iter() {
+                        samples.push(Samples {
+                            name: format!("{}:{}", path, column),
+                            samples: table.samples(table.index(column)?, None, self)?,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(samples)
+    }
+
+    /// Read exactly two samples for a two sample comparison, from two files,
+    /// two columns of one file, or two groups of one file.
+    pub fn read_pair<T: Sample>(&self, paths: &[String]) -> Result<(Samples<T>, Samples<T>), Error> {
+        let mut samples = self.read(paths)?;
+
+        if samples.len() != 2 {
+            let message = if self.group_by.is_some() {
+                format!("expected two groups, found {}, select two with --groups",
+                        samples.len())
+            } else {
+                "expected two files, two columns of one file, or --group-by".to_string()
+            };
+            return Err(Error::Usage(message));
+        }
+
+        let ys = samples.pop().unwrap();
+        let xs = samples.pop().unwrap();
+
+        Ok((xs, ys))
+    }
+
+    fn column(&self, i: usize) -> &Column {
+        self.columns.get(i).unwrap_or(&Column::Index(0))
+    }
+
+    fn read_table(&self, path: &str) -> Result<Table, Error> {
+        if path == STDIN_PATH {
+            let stdin = io::stdin();
+            return self.parse_table(STDIN_NAME, stdin.lock());
+        }
+
+        let file = File::open(path).map_err(|err| Error::Input(format!("{}: {}", path, err)))?;
+
+        self.parse_table(path, BufReader::new(file))
+    }
+
+    /// Parse delimited lines into a table, where path names the input in
+    /// messages and decides the default delimiter.
+    fn parse_table<R: BufRead>(&self, path: &str, reader: R) -> Result<Table, Error> {
+        let delimiter = self.delimiter.unwrap_or(if path.ends_with(".csv") { ',' } else { '\t' });
+
+        let mut table = Table {
+            path: path.to_string(),
+            header: None,
+            rows: Vec::new(),
+        };
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(|err| Error::Input(format!("{}: {}", path, err)))?;
+
+            if line.trim().is_empty() || line.trim_start().starts_with(self.comment) {
+                continue;
+            }
+
+            let fields: Vec<String> = line.split(delimiter).map(|f| f.trim().to_string()).collect();
+
+            if self.header && table.header.is_none() {
+                table.header = Some(fields);
+            } else {
+                table.rows.push((i + 1, fields));
+            }
+        }
+
+        Ok(table)
+    }
+}
+
+/// Replace the paths of directories with the paths of the files in them,
+/// sorted by name and excluding hidden files.
+fn expand_directories(paths: &[String]) -> Result<Vec<String>, Error> {
+    let mut expanded = Vec::new();
+
+    for path in paths {
+        let is_dir = path != STDIN_PATH && fs::metadata(path).is_ok_and(|metadata| metadata.is_dir());
+        if !is_dir {
+            expanded.push(path.clone());
+            continue;
+        }
+
+        let error = |err| Error::Input(format!("{}: {}", path, err));
+        let mut files = Vec::new();
+
+        for entry in fs::read_dir(path).map_err(error)? {
+            let entry = entry.map_err(error)?;
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+
+            if !hidden && entry.file_type().map_err(error)?.is_file() {
+                files.push(entry.path().to_string_lossy().into_owned());
+            }
+        }
+
+        if files.is_empty() {
+            return Err(Error::Input(format!("{}: no data files in directory", path)));
+        }
+
+        files.sort();
+        expanded.extend(files);
+    }
+
+    Ok(expanded)
+}
+
+/// Delimited data file contents.
+struct Table {
+    path: String,
+    header: Option<Vec<String>>,
+    /// Rows of fields with their line numbers.
+    rows: Vec<(usize, Vec<String>)>,
+}
+
+impl Table {
+    /// Index of a column.
+    fn index(&self, column: &Column) -> Result<usize, Error> {
+        match (column, &self.header) {
+            (Column::Index(index), _) => Ok(*index),
+            (Column::Name(name), Some(header)) => {
+                header.iter().position(|field| field == name).ok_or_else(|| {
+                    Error::Input(format!("{}: no column named '{}'", self.path, name))
+                })
+            }
+            (Column::Name(name), None) => {
+                Err(Error::Usage(format!("column '{}' is selected by name but --header is not \
+                                          set",
+                                         name)))
+            }
+        }
+    }
+
+    /// Read the samples in a column, optionally restricted to rows with a
+    /// label in another column.
+    ///
+    /// Blank and comment lines are ignored. Rows with a missing or invalid
+    /// sample are an error unless skipping invalid rows, when the number
+    /// skipped is reported.
+    fn samples<T: Sample>(&self,
+                          column: usize,
+                          label: Option<(usize, &str)>,
+                          input: &Input)
+                          -> Result<Vec<T>, Error> {
+        let mut samples = Vec::new();
+        let mut invalid = 0;
+
+        for &(line, ref fields) in self.rows.iter() {
+            if let Some((label_column, label)) = label {
+                if fields.get(label_column).map(|field| field.as_str()) != Some(label) {
+                    continue;
+                }
+            }
+
+            let sample = match fields.get(column) {
+                Some(field) => T::parse(field).map_err(|err| format!("invalid sample '{}': {}", field, err)),
+                None => Err(format!("missing column {}", column + 1)),
+            };
+
+            match sample {
+                Ok(sample) => samples.push(sample),
+                Err(_) if input.skip_invalid => invalid += 1,
+                Err(err) => {
+                    let hint = if samples.is_empty() && !input.header {
+                        " (use --header if the file has a header line)"
+                    } else {
+                        ""
+                    };
+                    return Err(Error::Input(format!("{}:{}: {}{}", self.path, line, err, hint)));
+                }
+            }
+        }
+
+        if invalid > 0 {
+            eprintln!("warning: {}: skipped {} invalid row{} in column {}{}",
+                      self.path,
+                      invalid,
+                      if invalid == 1 { "" } else { "s" },
+                      column + 1,
+                      label.map(|(_, label)| format!(" of group '{}'", label)).unwrap_or_default());
+        }
+
+        if samples.is_empty() {
+            return Err(Error::Input(format!("{}: no samples", self.path)));
+        }
+
+        Ok(samples)
+    }
+
+    /// Read the samples in a column split into groups by the labels in
+    /// another column, either the specified groups or all in order of first
+    /// appearance.
+    fn groups<T: Sample>(&self,
+                         column: &Column,
+                         group_by: &Column,
+                         groups: &[String],
+                         input: &Input)
+                         -> Result<Vec<Samples<T>>, Error> {
+        let column = self.index(column)?;
+        let label_column = self.index(group_by)?;
+
+        let mut labels: Vec<String> = groups.to_vec();
+        if labels.is_empty() {
+            for (_, fields) in self.rows.iter() {
+                if let Some(label) = fields.get(label_column) {
+                    if !labels.contains(label) {
+                        labels.push(label.clone());
+                    }
+                }
+            }
+        }
+
+        labels.iter()
+              .map(|label| {
+                  Ok(Samples {
+                      name: label.clone(),
+                      samples: self.samples(column, Some((label_column, label)), input)
+                                   .map_err(|err| match err {
+                                       Error::Input(message) => {
+                                           Error::Input(format!("{} in group '{}'", message, label))
+                                       }
+                                       err => err,
+                                   })?,
+                  })
+              })
+              .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{self, File};
+    use std::io::Write;
+
+    use ks::OrderableF64;
+
+    use super::super::args::{parse, Error};
+    use super::{expand_directories, Column, Input, INPUT_FLAGS, INPUT_OPTIONS, STDIN_NAME};
+
+    /// Write a temporary data file with a unique name for each test.
+    fn write(name: &str, contents: &str) -> String {
+        let path = env::temp_dir().join(format!("ks-input-{}-{}", ::std::process::id(), name));
+        File::create(&path).unwrap().write_all(contents.as_bytes()).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    fn input(args: &[&str]) -> Result<Input, Error> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        Input::new(&parse(&args, &INPUT_FLAGS, &INPUT_OPTIONS)?)
+    }
+
+    #[test]
+    fn column_parses_name_or_index_from_one() {
+        assert_eq!(Column::parse("column", "3").unwrap(), Column::Index(2));
+        assert_eq!(Column::parse("column", "ttime").unwrap(),
+                   Column::Name("ttime".to_string()));
+        assert!(Column::parse("column", "0").is_err());
+    }
+
+    #[test]
+    fn read_columns_by_name_skipping_comments_and_blank_lines() {
+        let paths = vec![write("columns.csv", "a,b\n# comment\n1,10\n\n2,20\n3,30\n")];
+
+        let samples = input(&["--header", "--column", "b", "--column", "a"])
+                          .unwrap()
+                          .read::<i64>(&paths)
+                          .unwrap();
+
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].name, format!("{}:b", paths[0]));
+        assert_eq!(samples[0].samples, vec![10, 20, 30]);
+        assert_eq!(samples[1].samples, vec![1, 2, 3]);
+
+        fs::remove_file(&paths[0]).unwrap();
+    }
+
+    #[test]
+    fn read_groups_skipping_invalid_rows() {
+        let paths = vec![write("groups.tsv", "x\t1.5\ny\t2.5\nx\tbad\ny\n\nx\t0.5\n")];
+
+        let samples = input(&["--column", "2", "--group-by", "1", "--skip-invalid"])
+                          .unwrap()
+                          .read::<i64>(&paths);
+        assert!(samples.is_err());
+
+        let (xs, ys) = input(&["--column", "2", "--group-by", "1", "--skip-invalid"])
+                           .unwrap()
+                           .read_pair::<OrderableF64>(&paths)
+                           .unwrap();
+
+        assert_eq!((xs.name.as_str(), xs.samples.len()), ("x", 2));
+        assert_eq!((ys.name.as_str(), ys.samples.len()), ("y", 1));
+
+        match input(&["--column", "2", "--group-by", "1"]).unwrap().read::<OrderableF64>(&paths) {
+            Err(Error::Input(message)) => {
+                assert!(message.ends_with(":3: invalid sample 'bad': not a floating point number \
+                                           in group 'x'"))
+            }
+            _ => panic!("Expected input error."),
+        }
+
+        fs::remove_file(&paths[0]).unwrap();
+    }
+
+    #[test]
+    fn parse_table_names_standard_input() {
+        let input = input(&["--header", "--column", "b"]).unwrap();
+        let table = input.parse_table(STDIN_NAME, "a\tb\n1\t2\n3\tx\n".as_bytes()).unwrap();
+
+        assert_eq!(table.path, "stdin");
+
+        let column = table.index(&Column::Name("b".to_string())).unwrap();
+        match table.samples::<i64>(column, None, &input) {
+            Err(Error::Input(message)) => {
+                assert_eq!(message, "stdin:3: invalid sample 'x': not an integer")
+            }
+            _ => panic!("Expected input error."),
+        }
+
+        let paths = vec!["-".to_string(), "-".to_string()];
+        match input.read::<i64>(&paths) {
+            Err(Error::Usage(message)) => assert_eq!(message, "standard input can only be read once"),
+            _ => panic!("Expected usage error."),
+        }
+    }
+
+    #[test]
+    fn expand_directories_to_sorted_visible_files() {
+        let dir = env::temp_dir().join(format!("ks-input-{}-dir", ::std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        for name in ["b.tsv", "a.tsv", ".hidden"].iter() {
+            File::create(dir.join(name)).unwrap();
+        }
+
+        let path = dir.to_str().unwrap().to_string();
+        let paths = expand_directories(&[path.clone(), "-".to_string()]).unwrap();
+
+        assert_eq!(paths,
+                   vec![format!("{}/a.tsv", path), format!("{}/b.tsv", path), "-".to_string()]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
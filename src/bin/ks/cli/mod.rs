@@ -0,0 +1,494 @@
+//! Command line interface of the `ks` tool, shared with the single purpose
+//! binaries which are aliases of its subcommands.
+
+// Struct fields are initialised as `field: field`, as in the library.
+#![allow(clippy::redundant_field_names)]
+
+mod args;
+mod commands;
+mod distribution;
+mod input;
+mod output;
+mod report;
+
+use std::env;
+use std::path::Path;
+use std::process;
+
+use self::args::{Error, Matches};
+
+/// Outcome of a command that ran successfully, deciding the exit status.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Status {
+    /// Exit status 0.
+    Success,
+    /// A test rejected the null hypothesis, exit status 1.
+    Rejected,
+}
+
+/// Exit status of commands that fail, e.g. on invalid usage or input.
+const ERROR_EXIT_STATUS: i32 = 2;
+
+/// Subcommand of the `ks` tool.
+struct Command {
+    name: &'static str,
+    summary: &'static str,
+    usage: &'static str,
+    flags: &'static [&'static str],
+    options: &'static [&'static str],
+    /// Whether the command reads data files and accepts the input options.
+    input: bool,
+    run: fn(&Matches) -> Result<Status, Error>,
+}
+
+const COMMANDS: [Command; 11] = [Command {
+                                    name: "test",
+                                    summary: "Test whether two samples are from the same distribution",
+                                    usage: TEST_USAGE,
+                                    flags: &["integer", "qq", "pp", "bands", "text"],
+                                    options: &["confidence", "alternative", "method", "plot", "format",
+                                               "distribution", "block-length", "block-scheme",
+                                               "bootstrap", "seed", "test"],
+                                    input: true,
+                                    run: commands::test,
+                                },
+                                Command {
+                                    name: "pairwise",
+                                    summary: "Test every pair of several samples",
+                                    usage: PAIRWISE_USAGE,
+                                    flags: &["integer"],
+                                    options: &["confidence", "test", "method", "correction", "format"],
+                                    input: true,
+                                    run: commands::pairwise,
+                                },
+                                Command {
+                                    name: "adjust",
+                                    summary: "Adjust p-values of a batch of tests for multiple testing",
+                                    usage: ADJUST_USAGE,
+                                    flags: &[],
+                                    options: &["confidence", "correction", "format"],
+                                    input: true,
+                                    run: commands::adjust,
+                                },
+                                Command {
+                                    name: "ecdf",
+                                    summary: "Print the empirical cumulative distribution function",
+                                    usage: ECDF_USAGE,
+                                    flags: &["integer"],
+                                    options: &["format"],
+                                    input: true,
+                                    run: commands::ecdf,
+                                },
+                                Command {
+                                    name: "quantiles",
+                                    summary: "Print quantiles of a sample",
+                                    usage: QUANTILES_USAGE,
+                                    flags: &["integer"],
+                                    options: &["probability", "format"],
+                                    input: true,
+                                    run: commands::quantiles,
+                                },
+                                Command {
+                                    name: "critical-values",
+                                    summary: "Print critical values of the two sample test",
+                                    usage: CRITICAL_VALUES_USAGE,
+                                    flags: &[],
+                                    options: &["confidence", "num-samples", "limit", "format"],
+                                    input: false,
+                                    run: commands::critical_values,
+                                },
+                                Command {
+                                    name: "generate",
+                                    summary: "Print random variates of a distribution",
+                                    usage: GENERATE_USAGE,
+                                    flags: &[],
+                                    options: &["count", "distribution", "weights", "mean", "variance",
+                                               "seed", "format"],
+                                    input: false,
+                                    run: commands::generate,
+                                },
+                                Command {
+                                    name: "fit",
+                                    summary: "Fit distributions to a sample and rank them by goodness-of-fit",
+                                    usage: FIT_USAGE,
+                                    flags: &[],
+                                    options: &["family", "estimator", "bootstrap", "seed", "format"],
+                                    input: true,
+                                    run: commands::fit,
+                                },
+                                Command {
+                                    name: "power-law",
+                                    summary: "Fit a power law to the tail of a sample",
+                                    usage: POWER_LAW_USAGE,
+                                    flags: &[],
+                                    options: &["bootstrap", "seed", "format"],
+                                    input: true,
+                                    run: commands::power_law,
+                                },
+                                Command {
+                                    name: "change-points",
+                                    summary: "Detect changes in distribution along a series",
+                                    usage: CHANGE_POINTS_USAGE,
+                                    flags: &["integer"],
+                                    options: &["confidence", "permutations", "seed", "format"],
+                                    input: true,
+                                    run: commands::change_points,
+                                },
+                                Command {
+                                    name: "summary",
+                                    summary: "Print descriptive statistics of samples",
+                                    usage: SUMMARY_USAGE,
+                                    flags: &["integer"],
+                                    options: &["format"],
+                                    input: true,
+                                    run: commands::summary,
+                                }];
+
+const TEST_USAGE: &str = "\
+Usage: ks test [options] <file1> <file2>
+       ks test [options] --column <a> --column <b> <file>
+       ks test [options] --group-by <label> [--groups <a,b>] <file>
+       ks test [options] --distribution <d> <file>
+
+Test whether two samples are from the same distribution, and print
+descriptive statistics of the samples. The samples are read from two files,
+two columns of one file, or two groups of one file. With --distribution, test
+whether a single sample is from the given distribution instead.
+
+The exit status is 0 if the samples are from the same distribution, 1 if the
+test rejects at any of the confidence levels, and 2 on invalid usage or input.
+
+With several confidence levels, a table of the critical value and result at
+each level is printed.
+
+Samples of time series, e.g. per-second response times, are often serially
+correlated, which makes the test reject too often. With --block-length, the
+p-value is instead calculated by a block bootstrap, resampling blocks of
+consecutive values of the samples to preserve the correlation.
+
+With --test mann-whitney, the Mann-Whitney U test is used instead, which is
+more powerful against one sample tending to be larger than the other but does
+not detect other differences, e.g. in spread. Its statistic U counts the pairs
+of values where the value of file1 is larger, and the effect size is U as a
+proportion of the pairs. The exact p-value is used by default for samples with
+fewer than 50 values each and no ties.
+
+Options:
+  --integer          Read samples as integers instead of floating point numbers
+  --confidence <c>   Confidence level in (0, 1), may be repeated or a comma
+                     separated list [default: 0.95]
+  --test <t>         Two sample test, kolmogorov-smirnov (ks) or mann-whitney
+                     [default: kolmogorov-smirnov]
+  --alternative <h>  Alternative hypothesis, two-sided, less or greater, where
+                     less means the distribution function of file1 lies below
+                     that of file2 [default: two-sided]
+  --method <m>       Method of calculating the p-value, asymptotic or exact
+                     [default: asymptotic, or exact for small mann-whitney
+                     samples]
+  --qq               Print quantile-quantile plot data instead of the test
+  --pp               Print probability-probability plot data instead of the test
+  --plot <file.svg>  Write an SVG plot of the ECDFs
+  --bands            Draw 95% confidence bands in the SVG plot
+  --text             Print a text plot of the ECDFs sized to the terminal
+  --distribution <d> Distribution of a one sample test, see below
+  --block-length <l> Length of the blocks of a block bootstrap p-value, longer
+                     than the lag over which values are correlated
+  --block-scheme <s> Block bootstrap scheme, moving-block for blocks of fixed
+                     length or stationary for blocks of random length with
+                     mean l [default: moving-block]
+  --bootstrap <b>    Number of block bootstrap resamples [default: 999]
+  --seed <s>         Seed for a reproducible block bootstrap [default: random]
+  --format <f>       Output format, text, tsv or json, where tsv prints a row
+                     for each confidence level and json also includes the
+                     sample statistics [default: text]
+  -h, --help         Print this help";
+
+const PAIRWISE_USAGE: &str = "\
+Usage: ks pairwise [options] <file>...
+       ks pairwise [options] <directory>
+       ks pairwise [options] --group-by <label> <file>
+
+Test every pair of several samples for differences in distribution, and print
+the matrix of test statistics and the pairs that differ significantly. The
+samples are read from files, the files in a directory, columns of one file, or
+groups of one file. P-values are adjusted for the number of pairs with a
+multiple testing correction.
+
+The exit status is 0 if no pair differs, 1 if any pair differs, and 2 on
+invalid usage or input.
+
+Options:
+  --integer          Read samples as integers instead of floating point numbers
+  --confidence <c>   Confidence level in (0, 1) [default: 0.95]
+  --test <t>         Two sample test, kolmogorov-smirnov (ks) or mann-whitney,
+                     whose statistic is U of the first sample of each pair
+                     [default: kolmogorov-smirnov]
+  --method <m>       Method of calculating the p-values, asymptotic or exact
+                     [default: asymptotic, or exact for small mann-whitney
+                     samples]
+  --correction <m>   Multiple testing correction, see 'ks help adjust'
+                     [default: bonferroni]
+  --format <f>       Output format, text, tsv or json, where tsv prints a row
+                     for each pair [default: text]
+  -h, --help         Print this help";
+
+const ADJUST_USAGE: &str = "\
+Usage: ks adjust [options] <file>
+
+Adjust the p-values of a batch of tests for multiple testing, e.g. the p_value
+column of 'ks test --format tsv' output collected over many metrics, and print
+the adjusted p-values and which tests are rejected, in the input order.
+
+The exit status is 0 if no test is rejected, 1 if any test is rejected, and 2
+on invalid usage or input.
+
+Options:
+  --confidence <c>   Confidence level in (0, 1) of the batch as a whole
+                     [default: 0.95]
+  --correction <m>   Multiple testing correction [default: bonferroni]
+                       bonferroni            family-wise error rate
+                       holm                  family-wise error rate, more
+                                             powerful than bonferroni
+                       hochberg              family-wise error rate for
+                                             independent tests
+                       benjamini-hochberg    false discovery rate for
+                       (bh)                  independent tests
+                       benjamini-yekutieli   false discovery rate for any
+                       (by)                  dependence between tests
+  --format <f>       Output format, text, tsv or json [default: text]
+  -h, --help         Print this help";
+
+const ECDF_USAGE: &str = "\
+Usage: ks ecdf [options] <file>
+
+Print the distinct sample values with their counts and empirical cumulative
+distribution function values.
+
+Options:
+  --integer       Read samples as integers instead of floating point numbers
+  --format <f>    Output format, text, tsv or json [default: text]
+  -h, --help      Print this help";
+
+const QUANTILES_USAGE: &str = "\
+Usage: ks quantiles [options] <file>
+
+Print quantiles of a sample using the Nearest Rank method.
+
+Options:
+  --integer          Read samples as integers instead of floating point numbers
+  --probability <p>  Probability in (0, 1] at which to calculate a quantile,
+                     may be repeated or a comma separated list
+                     [default: 0.01,0.05,0.1,0.25,0.5,0.75,0.9,0.95,0.99]
+  --format <f>       Output format, text, tsv or json [default: text]
+  -h, --help         Print this help";
+
+const CRITICAL_VALUES_USAGE: &str = "\
+Usage: ks critical-values --confidence <c> --num-samples <n> --limit <m>
+
+Print critical values of the two sample test for samples of size n against
+samples of sizes 16 through m inclusive.
+
+Options:
+  --confidence <c>   Confidence level strictly between zero and one
+  --num-samples <n>  Size of the first sample, greater than 7
+  --limit <m>        Positive maximum size of the second sample
+  --format <f>       Output format, text, tsv or json [default: text]
+  -h, --help         Print this help";
+
+const GENERATE_USAGE: &str = "\
+Usage: ks generate --count <n> [options]
+
+Print random variates, one per line, of a distribution or of a mixture of
+distributions when --distribution is repeated. Variates are Normal with the
+given mean and variance if no distribution is specified.
+
+Options:
+  --count <n>           Positive number of variates
+  --distribution <d>    Distribution specified as name:parameters, may be
+                        repeated for a mixture, see below
+                        [default: normal:0,1]
+  --weights <w>         Comma separated positive weights of the mixture
+                        components [default: equal weights]
+  --mean <mu>           Mean of the Normal distribution [default: 0]
+  --variance <s2>       Positive variance of the Normal distribution
+                        [default: 1]
+  --seed <s>            Seed for reproducible variates [default: random]
+  --format <f>          Output format, text, tsv or json [default: text]
+  -h, --help            Print this help";
+
+const FIT_USAGE: &str = "\
+Usage: ks fit [options] <file>
+
+Fit families of distributions to a sample, estimating their parameters, and
+print the fitted distributions ranked by the test statistic, the distance
+between the sample ECDF and the fitted distribution function. The p-value of
+each fit is calculated by a parametric bootstrap, fitting the family to
+samples drawn from the fitted distribution, as the one sample test overstates
+p-values when the parameters are estimated from the sample. Families limited to
+positive values are left out if the sample is not positive.
+
+Options:
+  --family <f>       Family to fit, normal, lognormal, exponential, gamma,
+                     weibull, pareto or uniform, may be repeated or a comma
+                     separated list [default: all]
+  --estimator <e>    Parameter estimator, mle for maximum likelihood or
+                     moments for the method of moments [default: mle]
+  --bootstrap <b>    Number of bootstrap samples of each fit, where 0 skips
+                     the p-values [default: 100]
+  --seed <s>         Seed for reproducible p-values [default: random]
+  --format <f>       Output format, text, tsv or json, where json also
+                     includes the sample statistics [default: text]
+  -h, --help         Print this help";
+
+const POWER_LAW_USAGE: &str = "\
+Usage: ks power-law [options] <file>
+
+Fit a power law with density proportional to x^-alpha to the tail of a sample
+above xmin, using the method of Clauset, Shalizi and Newman. Each distinct
+positive value is tried as xmin, estimating alpha by maximum likelihood, and
+the xmin with the least distance between the ECDF of the tail and the power law
+is chosen. The p-value is calculated by a bootstrap of synthetic samples with
+the fitted tail, where a p-value below 0.1 rules out the power law.
+
+Options:
+  --bootstrap <b>    Number of bootstrap samples, where 0 skips the p-value
+                     [default: 100]
+  --seed <s>         Seed for a reproducible p-value [default: random]
+  --format <f>       Output format, text, tsv or json, where json also
+                     includes the sample statistics [default: text]
+  -h, --help         Print this help";
+
+const CHANGE_POINTS_USAGE: &str = "\
+Usage: ks change-points [options] <file>
+
+Detect changes in distribution along a series, e.g. a time series in file
+order, and print the index of the first value after each change. Every split of
+the series is tested, and the split with the most significant statistic is a
+change point if its significance exceeds that of random permutations of the
+series at the confidence level. The segments either side are then scanned in
+turn by binary segmentation. Segments have more than 7 values.
+
+The exit status is 0 if there are no change points, 1 if there are, and 2 on
+invalid usage or input.
+
+Options:
+  --integer          Read samples as integers instead of floating point numbers
+  --confidence <c>   Confidence level in (0, 1) [default: 0.95]
+  --permutations <p> Number of permutations of each segment [default: 199]
+  --seed <s>         Seed for reproducible p-values [default: random]
+  --format <f>       Output format, text, tsv or json, where json also
+                     includes the series statistics [default: text]
+  -h, --help         Print this help";
+
+const SUMMARY_USAGE: &str = "\
+Usage: ks summary [options] <file>...
+
+Print descriptive statistics of samples side by side, one for each file,
+column of a single file, or group.
+
+Options:
+  --integer       Read samples as integers instead of floating point numbers
+  --format <f>    Output format, text, tsv or json [default: text]
+  -h, --help      Print this help";
+
+/// Print the top level usage of the `ks` tool.
+fn print_usage() {
+    println!("Usage: ks <command> [options]");
+    println!();
+    println!("Kolmogorov-Smirnov tests and empirical distribution tools.");
+    println!();
+    println!("Commands:");
+    for command in COMMANDS.iter() {
+        println!("  {:<17}{}", command.name, command.summary);
+    }
+    println!();
+    println!("Run 'ks <command> --help' for the options of a command.");
+}
+
+/// Print the usage of a command.
+fn print_command_usage(command: &Command) {
+    println!("{}", command.usage);
+    if command.input {
+        println!();
+        println!("{}", input::INPUT_USAGE);
+    }
+    if command.options.contains(&"distribution") {
+        println!();
+        println!("{}", distribution::DISTRIBUTIONS_USAGE);
+    }
+}
+
+fn find(name: &str) -> Result<&'static Command, Error> {
+    COMMANDS.iter()
+            .find(|command| command.name == name)
+            .ok_or_else(|| Error::Usage(format!("unknown command '{}'", name)))
+}
+
+fn run(args: &[String], names: &[&str]) -> Result<Status, Error> {
+    let name = match args.first().map(|arg| arg.as_str()) {
+        None | Some("-h") | Some("--help") => {
+            print_usage();
+            return Ok(Status::Success);
+        }
+        Some("help") => {
+            match args.get(1) {
+                Some(name) => print_command_usage(find(name)?),
+                None => print_usage(),
+            }
+            return Ok(Status::Success);
+        }
+        Some(name) => name,
+    };
+
+    let command = find(name)?;
+
+    let mut flags = command.flags.to_vec();
+    let mut options = command.options.to_vec();
+    if command.input {
+        flags.extend_from_slice(&input::INPUT_FLAGS);
+        options.extend_from_slice(&input::INPUT_OPTIONS);
+    }
+
+    let mut matches = args::parse(&args[1..], &flags, &options)?;
+    matches.name_positional(names);
+
+    if matches.flag("help") {
+        print_command_usage(command);
+        return Ok(Status::Success);
+    }
+
+    (command.run)(&matches)
+}
+
+/// Run the `ks` tool, exiting with status 1 if a test rejects and 2 on error.
+///
+/// Single purpose binaries run a `ks` subcommand by specifying its leading
+/// arguments in command, and option names for their leading positional
+/// arguments in names. Positional arguments are named after parsing, so they
+/// may follow options, and any remaining arguments are passed through, so
+/// missing arguments are reported by the subcommand rather than panicking.
+pub fn main(command: &[&str], names: &[&str]) {
+    let mut argv = env::args();
+    let program = argv.next()
+                      .as_ref()
+                      .and_then(|arg| Path::new(arg).file_stem())
+                      .and_then(|stem| stem.to_str())
+                      .map(|stem| stem.to_string())
+                      .unwrap_or_else(|| "ks".to_string());
+
+    let mut args: Vec<String> = command.iter().map(|arg| arg.to_string()).collect();
+    args.extend(argv);
+
+    match run(&args, names) {
+        Ok(Status::Success) => {}
+        Ok(Status::Rejected) => process::exit(1),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            if let Error::Usage(_) = err {
+                eprintln!();
+                eprintln!("Run '{} --help' for usage.", program);
+            }
+            process::exit(ERROR_EXIT_STATUS);
+        }
+    }
+}
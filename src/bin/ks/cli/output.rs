@@ -0,0 +1,101 @@
+//! Output formats of command results.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde_json;
+
+use super::args::{Error, Matches};
+
+/// Output format of a command, selected with the `--format` option.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// Human readable text, the default.
+    Text,
+    /// Tab-separated values with a header line.
+    Tsv,
+    /// A JSON document. Requires the serde feature.
+    Json,
+}
+
+impl Format {
+    /// Parse the `--format` option, defaulting to text.
+    pub fn from_matches(matches: &Matches) -> Result<Format, Error> {
+        let format = matches.parse("format")?.unwrap_or(Format::Text);
+
+        if format == Format::Json && !cfg!(feature = "serde") {
+            return Err(Error::Usage("option --format json requires the serde feature".to_string()));
+        }
+
+        Ok(format)
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Format::Text => "text".fmt(f),
+            Format::Tsv => "tsv".fmt(f),
+            Format::Json => "json".fmt(f),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "text" => Ok(Format::Text),
+            "tsv" => Ok(Format::Tsv),
+            "json" => Ok(Format::Json),
+            _ => Err("expected text, tsv or json".to_string()),
+        }
+    }
+}
+
+/// Values that can be printed as JSON documents, i.e. serializable with the
+/// serde feature and any value without it.
+#[cfg(feature = "serde")]
+pub trait Json: Serialize {}
+
+#[cfg(feature = "serde")]
+impl<S: Serialize> Json for S {}
+
+#[cfg(not(feature = "serde"))]
+pub trait Json {}
+
+#[cfg(not(feature = "serde"))]
+impl<S> Json for S {}
+
+/// Print a value as a JSON document.
+#[cfg(feature = "serde")]
+pub fn print_json<S: Json>(value: &S) -> Result<(), Error> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|err| Error::Input(format!("cannot write JSON: {}", err)))?;
+    println!("{}", json);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "serde"))]
+pub fn print_json<S: Json>(_: &S) -> Result<(), Error> {
+    Err(Error::Usage("option --format json requires the serde feature".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Format;
+
+    #[test]
+    fn format_round_trips_through_strings() {
+        for &format in [Format::Text, Format::Tsv, Format::Json].iter() {
+            assert_eq!(format.to_string().parse::<Format>(), Ok(format));
+        }
+
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,133 @@
+//! JSON documents written by commands with `--format json`.
+//!
+//! The field names form the stable schema of the JSON output. They are only
+//! read when serializing, so are unused without the serde feature.
+
+#![cfg_attr(not(feature = "serde"), allow(dead_code))]
+
+use ks::{Alternative, Method, Summary, TestResult};
+use ks::mann_whitney::MannWhitneyResult;
+use ks::change_point::ChangePoint;
+use ks::bootstrap::BlockBootstrap;
+use ks::correction::Correction;
+use ks::fit::{Estimator, Fit};
+use ks::pairwise::Comparison;
+use ks::power_law::PowerLaw;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Result of a two sample test at one or more confidence levels.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TestReport<'a> {
+    /// Name of the test, `kolmogorov-smirnov`.
+    pub test: &'a str,
+    pub alternative: Alternative,
+    /// Method of calculating the p-values, a test method or a block
+    /// bootstrap scheme, e.g. `moving-block-bootstrap`.
+    pub method: &'a str,
+    /// Configuration of the block bootstrap, if used.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub bootstrap: Option<BlockBootstrap>,
+    /// The two samples, with their sizes in the count field.
+    pub samples: Vec<NamedSummary<'a>>,
+    /// Test results in the order of the confidence levels.
+    pub results: &'a [TestResult],
+}
+
+/// Result of a Mann-Whitney U test at one or more confidence levels.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct MannWhitneyReport<'a> {
+    /// Name of the test, `mann-whitney`.
+    pub test: &'a str,
+    pub alternative: Alternative,
+    /// The two samples, with their sizes in the count field.
+    pub samples: Vec<NamedSummary<'a>>,
+    /// Test results in the order of the confidence levels.
+    pub results: &'a [MannWhitneyResult],
+}
+
+/// Result of a one sample test at one or more confidence levels.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct OneSampleReport<'a> {
+    /// Specification of the distribution, e.g. `normal:0,1`.
+    pub distribution: &'a str,
+    pub alternative: Alternative,
+    pub sample: NamedSummary<'a>,
+    /// Test results in the order of the confidence levels.
+    pub results: &'a [TestResult],
+}
+
+/// Distributions fitted to a sample, best first.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct FitReport<'a> {
+    pub estimator: Estimator,
+    /// Number of parametric bootstrap samples of each fit, zero if the
+    /// p-values were not calculated.
+    pub bootstraps: usize,
+    pub sample: NamedSummary<'a>,
+    /// Fits in order of increasing test statistic.
+    pub fits: &'a [Fit],
+}
+
+/// Power law fitted to the tail of a sample.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PowerLawReport<'a> {
+    /// Number of bootstrap samples of the p-value, zero if not calculated.
+    pub bootstraps: usize,
+    pub sample: NamedSummary<'a>,
+    pub power_law: &'a PowerLaw,
+}
+
+/// Change points detected in a series.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ChangePointReport<'a> {
+    pub confidence: f64,
+    /// Number of permutations of each segment scanned.
+    pub permutations: usize,
+    pub series: NamedSummary<'a>,
+    /// Change points in order of their index in the series.
+    pub change_points: &'a [ChangePoint],
+}
+
+/// Descriptive statistics of a named sample.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct NamedSummary<'a> {
+    pub name: &'a str,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub summary: Summary,
+}
+
+/// Quantile of a sample at a probability.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Quantile<T> {
+    pub probability: f64,
+    pub quantile: T,
+}
+
+/// Critical value of the two sample test for a pair of sample sizes.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct CriticalValue {
+    pub n1: usize,
+    pub n2: usize,
+    pub confidence: f64,
+    pub critical_value: f64,
+}
+
+/// Comparisons of every pair of several samples.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PairwiseReport<'a, R: 'a> {
+    /// Name of the test, e.g. `kolmogorov-smirnov`.
+    pub test: &'a str,
+    /// Names of the samples, indexed by the comparisons.
+    pub samples: Vec<&'a str>,
+    pub confidence: f64,
+    /// Method of calculating the p-values, if set.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub method: Option<Method>,
+    /// Multiple testing correction of the adjusted p-values.
+    pub correction: Correction,
+    /// Matrix of test statistics of each pair of samples.
+    pub statistics: Vec<Vec<f64>>,
+    pub comparisons: &'a [Comparison<R>],
+}
@@ -0,0 +1,26 @@
+extern crate kolmogorov_smirnov as ks;
+#[cfg(unix)]
+extern crate libc;
+extern crate rand;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+
+mod cli;
+
+/// Kolmogorov-Smirnov tests and empirical distribution tools.
+///
+/// # Examples
+///
+/// ```bash
+/// cargo run --bin ks -- <command> [options]
+/// ```
+///
+/// The commands are `test`, `pairwise`, `adjust`, `ecdf`, `quantiles`,
+/// `critical-values`, `generate`, `fit`, `power-law`, `change-points` and
+/// `summary`. Run `ks --help` for a description of each and
+/// `ks <command> --help` for its options.
+fn main() {
+    cli::main(&[], &[]);
+}
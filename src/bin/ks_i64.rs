@@ -1,52 +1,48 @@
 extern crate kolmogorov_smirnov as ks;
+#[cfg(unix)]
+extern crate libc;
+extern crate rand;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
 
-use ks::test;
-
-use std::env;
-use std::io::{BufReader, BufRead};
-use std::fs::File;
-use std::path::Path;
-
-fn parse_int(s: String) -> i64 {
-    s.parse::<i64>().expect("Not an integer.")
-}
+#[path = "ks/cli/mod.rs"]
+mod cli;
 
 /// Runs a Kolmogorov-Smirnov test on integer data files.
 ///
-/// Input files must be single-column headerless data files. The data samples
-/// are tested against each other at the 95% confidence level.
+/// Input files are single-column headerless data files by default, or
+/// delimited files with the `--header` and `--column` options. The data
+/// samples are tested against each other at the 95% confidence level.
 ///
 /// # Examples
 ///
 /// ```bash
-/// cargo run --bin ks_i64 <file1> <file2>
+/// cargo run --bin ks_i64 [--qq | --pp] [--plot <file.svg> [--bands]] [--text] <file1> <file2>
 /// ```
 ///
-/// This will print the test result to standard output.
+/// This will print the test result to standard output, followed by a summary
+/// of the descriptive statistics of each sample. Either file may be `-` to
+/// read the sample from standard input.
+///
+/// The exit status is 0 if the samples are from the same distribution, 1 if
+/// they are from different distributions, and 2 on invalid usage or input, so
+/// the test can gate a shell pipeline or CI step.
+///
+/// With the `--qq` or `--pp` option, the tab-separated quantile-quantile or
+/// probability-probability plot data of the samples is printed instead.
+///
+/// With the `--plot <file.svg>` option, an SVG plot of the two ECDFs with the
+/// test statistic highlighted is also written to the specified file. Adding
+/// `--bands` draws 95% confidence bands around each ECDF in the plot.
+///
+/// With the `--text` option, a plot of the two ECDFs drawn with Unicode
+/// braille characters is printed after the test result, sized to the terminal
+/// width, or the `COLUMNS` environment variable if set, or 80 columns if there
+/// is no terminal.
+///
+/// This is an alias of `ks test --integer`.
 fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    let path1 = Path::new(&args[1]);
-    let path2 = Path::new(&args[2]);
-
-    let file1 = BufReader::new(File::open(&path1).unwrap());
-    let file2 = BufReader::new(File::open(&path2).unwrap());
-
-    let lines1 = file1.lines().map(|line| line.unwrap());
-    let lines2 = file2.lines().map(|line| line.unwrap());
-
-    let xs: Vec<i64> = lines1.map(parse_int).collect();
-    let ys: Vec<i64> = lines2.map(parse_int).collect();
-
-    let result = ks::test(&xs, &ys, 0.95);
-
-    if result.is_rejected {
-        println!("Samples are from different distributions.");
-    } else {
-        println!("Samples are from the same distribution.");
-    }
-
-    println!("test statistic = {}", result.statistic);
-    println!("critical value = {}", result.critical_value);
-    println!("reject probability = {}", result.reject_probability);
+    cli::main(&["test", "--integer"], &[]);
 }
@@ -1,7 +1,14 @@
+extern crate kolmogorov_smirnov as ks;
+#[cfg(unix)]
+extern crate libc;
 extern crate rand;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
 
-use std::env;
-use rand::distributions::{Normal, IndependentSample};
+#[path = "ks/cli/mod.rs"]
+mod cli;
 
 /// Prints a sequence of Normal deviates.
 ///
@@ -16,20 +23,11 @@ use rand::distributions::{Normal, IndependentSample};
 ///
 /// `<num_deviates>` must be a positive integer, `<mean>` and `<variance>` may
 /// be integers or floating point numbers but `<variance>` must be positive.
+///
+/// Further options of `ks generate`, e.g. `--seed <s>` for reproducible
+/// deviates, may follow the positional arguments.
+///
+/// This is an alias of `ks generate`.
 fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    let n: u32 = args[1].parse().expect("<num_deviates> must be an integer.");
-    let mean: f64 = args[2].parse().expect("<mean> must be a floating point number.");
-    let variance: f64 = args[3].parse().expect("<variance> must be a floating point number.");
-
-    assert!(n > 0 && variance.is_sign_positive());
-
-    let mut rng = rand::thread_rng();
-    let normal = Normal::new(mean, variance.sqrt());
-
-    for _ in 0..n {
-        let x = normal.ind_sample(&mut rng);
-        println!("{}", x)
-    }
+    cli::main(&["generate"], &["count", "mean", "variance"]);
 }
@@ -0,0 +1,355 @@
+//! Block bootstrap two sample tests for serially dependent samples.
+//!
+//! The reject probability of the two sample test assumes the values of each
+//! sample are independent, so samples of time series, e.g. per-second
+//! response times, are rejected too often when successive values are
+//! correlated. The block bootstrap instead calibrates the test statistic by
+//! resampling blocks of consecutive values of the pooled samples, preserving
+//! the serial dependence within blocks, as in Lahiri, "Resampling Methods for
+//! Dependent Data" (2003).
+
+use std::fmt;
+use std::str::FromStr;
+
+use rand::{Rng, SeedableRng, StdRng};
+
+use ecdf::Ecdf;
+use test::{max_difference, Alternative, TestResult};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Scheme of resampling blocks of consecutive values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "kebab-case"))]
+pub enum Scheme {
+    /// Moving block bootstrap, resampling overlapping blocks of a fixed
+    /// length.
+    MovingBlock,
+    /// Stationary bootstrap of Politis and Romano, resampling blocks of
+    /// geometrically distributed length with the block length as the mean,
+    /// wrapping around the end of each sample.
+    Stationary,
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Scheme::MovingBlock => "moving-block".fmt(f),
+            Scheme::Stationary => "stationary".fmt(f),
+        }
+    }
+}
+
+impl FromStr for Scheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Scheme, String> {
+        match s {
+            "moving-block" => Ok(Scheme::MovingBlock),
+            "stationary" => Ok(Scheme::Stationary),
+            _ => Err("expected moving-block or stationary".to_string()),
+        }
+    }
+}
+
+/// Configuration of a block bootstrap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct BlockBootstrap {
+    pub scheme: Scheme,
+    /// Length of the blocks, or mean length for the stationary bootstrap.
+    /// Should exceed the lag over which values are correlated.
+    pub block_length: usize,
+    /// Number of bootstrap resamples of the samples.
+    pub resamples: usize,
+    /// Seed of the random resampling, so p-values are reproducible.
+    pub seed: u64,
+}
+
+/// Perform a two sample Kolmogorov-Smirnov test of serially dependent samples,
+/// calculating the p-value by block bootstrap.
+///
+/// Under the null hypothesis both samples are from the same process, so
+/// bootstrap samples of the sizes of xs and ys are built from blocks of either
+/// sample, with blocks never crossing from one sample to the other. The
+/// p-value is the proportion of bootstrap samples with a test statistic at
+/// least as large as that of xs and ys, and the critical value is the quantile
+/// of the bootstrap statistics at the confidence level. Blocks of length one
+/// give a permutation-like test of independent samples.
+///
+/// The samples must have length > 7 elements for the test to be valid.
+///
+/// # Panics
+///
+/// There are assertion panics if either sample has <= 7 elements, if the
+/// confidence level is not strictly between zero and one, if there are no
+/// resamples, or if the block length is zero or exceeds the length of either
+/// sample.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::bootstrap::{test_block_bootstrap, BlockBootstrap, Scheme};
+/// use ks::test::Alternative;
+///
+/// // Slowly varying series, with ys shifted upwards.
+/// let xs: Vec<i64> = (0..100).map(|i| (i / 10) % 5).collect();
+/// let ys: Vec<i64> = xs.iter().map(|x| x + 3).collect();
+///
+/// let bootstrap = BlockBootstrap {
+///     scheme: Scheme::MovingBlock,
+///     block_length: 10,
+///     resamples: 199,
+///     seed: 42,
+/// };
+///
+/// let result = test_block_bootstrap(&xs, &ys, 0.95, Alternative::TwoSided, &bootstrap);
+///
+/// assert!(result.is_rejected);
+/// assert!(result.p_value < 0.05);
+/// ```
+pub fn test_block_bootstrap<T: Ord + Clone>(xs: &[T],
+                                            ys: &[T],
+                                            confidence: f64,
+                                            alternative: Alternative,
+                                            bootstrap: &BlockBootstrap)
+                                            -> TestResult {
+    assert!(0.0 < confidence && confidence < 1.0);
+    assert!(bootstrap.resamples > 0);
+    assert!(bootstrap.block_length > 0);
+    assert!(bootstrap.block_length <= xs.len() && bootstrap.block_length <= ys.len());
+
+    // Only supports samples of size > 7.
+    assert!(xs.len() > 7 && ys.len() > 7);
+
+    let statistic = max_difference(&Ecdf::new(xs), &Ecdf::new(ys), alternative).statistic;
+
+    let mut rng: StdRng = SeedableRng::from_seed(&[bootstrap.seed as usize][..]);
+    let samples = [xs, ys];
+
+    let mut statistics: Vec<f64> = (0..bootstrap.resamples)
+        .map(|_| {
+            let resample_xs = resample(&samples, xs.len(), bootstrap, &mut rng);
+            let resample_ys = resample(&samples, ys.len(), bootstrap, &mut rng);
+
+            max_difference(&Ecdf::new(&resample_xs), &Ecdf::new(&resample_ys), alternative)
+                .statistic
+        })
+        .collect();
+
+    // Count the samples themselves so the p-value is never zero.
+    let extreme = statistics.iter().filter(|&&s| s >= statistic).count();
+    let p_value = (extreme + 1) as f64 / (bootstrap.resamples + 1) as f64;
+
+    statistics.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let rank = (confidence * (bootstrap.resamples + 1) as f64).ceil() as usize;
+    let critical_value = statistics[rank.clamp(1, statistics.len()) - 1];
+
+    TestResult {
+        is_rejected: 1.0 - p_value > confidence,
+        statistic: statistic,
+        reject_probability: 1.0 - p_value,
+        p_value: p_value,
+        critical_value: critical_value,
+        confidence: confidence,
+    }
+}
+
+/// Build a bootstrap sample of a length from blocks of consecutive values of
+/// the samples, each block from a single sample.
+fn resample<T: Clone>(samples: &[&[T]],
+                      length: usize,
+                      bootstrap: &BlockBootstrap,
+                      rng: &mut dyn Rng)
+                      -> Vec<T> {
+    let block_length = bootstrap.block_length;
+    let mut resample = Vec::with_capacity(length);
+
+    while resample.len() < length {
+        match bootstrap.scheme {
+            Scheme::MovingBlock => {
+                // Choose uniformly between the blocks of every sample.
+                let num_blocks = |xs: &[T]| xs.len() - block_length + 1;
+                let total: usize = samples.iter().map(|xs| num_blocks(xs)).sum();
+                let mut start = random_index(total, rng);
+
+                for xs in samples.iter() {
+                    if start < num_blocks(xs) {
+                        let end = (start + block_length).min(start + length - resample.len());
+                        resample.extend_from_slice(&xs[start..end]);
+                        break;
+                    }
+                    start -= num_blocks(xs);
+                }
+            }
+            Scheme::Stationary => {
+                // Start anywhere in the samples, continuing the block with
+                // probability 1 - 1 / block length at each value.
+                let total: usize = samples.iter().map(|xs| xs.len()).sum();
+                let mut start = random_index(total, rng);
+
+                for xs in samples.iter() {
+                    if start < xs.len() {
+                        let mut i = start;
+                        loop {
+                            resample.push(xs[i].clone());
+                            i = (i + 1) % xs.len();
+
+                            if resample.len() == length ||
+                               rng.next_f64() < 1.0 / block_length as f64 {
+                                break;
+                            }
+                        }
+                        break;
+                    }
+                    start -= xs.len();
+                }
+            }
+        }
+    }
+
+    resample
+}
+
+/// Choose an index uniformly at random below a length.
+fn random_index(length: usize, rng: &mut dyn Rng) -> usize {
+    ((rng.next_f64() * length as f64) as usize).min(length - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use self::rand::{SeedableRng, StdRng};
+
+    use super::{resample, test_block_bootstrap, BlockBootstrap, Scheme};
+    use distributions::{ContinuousDistribution, Normal};
+    use numeric::{Numeric, OrderableF64};
+    use test::{test_with, Alternative, Method};
+
+    /// Generate an autoregressive series with lag one correlation phi and
+    /// standard Normal marginal distribution.
+    fn autoregressive(n: usize, phi: f64, rng: &mut StdRng) -> Vec<OrderableF64> {
+        let innovation = Normal::new(0.0, (1.0 - phi * phi).sqrt());
+        let mut x = Normal::new(0.0, 1.0).sample(rng);
+
+        (0..n)
+            .map(|_| {
+                x = phi * x + innovation.sample(rng);
+                OrderableF64::new(x)
+            })
+            .collect()
+    }
+
+    fn bootstrap(scheme: Scheme, seed: u64) -> BlockBootstrap {
+        BlockBootstrap {
+            scheme: scheme,
+            block_length: 20,
+            resamples: 99,
+            seed: seed,
+        }
+    }
+
+    #[test]
+    fn block_bootstrap_calibrates_autocorrelated_samples() {
+        let mut rng = StdRng::from_seed(&[42]);
+        let trials = 40;
+
+        for &scheme in [Scheme::MovingBlock, Scheme::Stationary].iter() {
+            let mut naive_rejections = 0;
+            let mut bootstrap_rejections = 0;
+
+            for trial in 0..trials {
+                let xs = autoregressive(200, 0.9, &mut rng);
+                let ys = autoregressive(200, 0.9, &mut rng);
+
+                if test_with(&xs, &ys, 0.95, Alternative::TwoSided, Method::Asymptotic)
+                    .is_rejected {
+                    naive_rejections += 1;
+                }
+                if test_block_bootstrap(&xs,
+                                        &ys,
+                                        0.95,
+                                        Alternative::TwoSided,
+                                        &bootstrap(scheme, trial))
+                    .is_rejected {
+                    bootstrap_rejections += 1;
+                }
+            }
+
+            // The nominal rejection rate is 5%, or 2 of 40 trials.
+            assert!(naive_rejections >= 12, "{} {}", scheme, naive_rejections);
+            assert!(bootstrap_rejections <= 6, "{} {}", scheme, bootstrap_rejections);
+        }
+    }
+
+    #[test]
+    fn block_bootstrap_rejects_shifted_samples() {
+        let mut rng = StdRng::from_seed(&[7]);
+
+        let xs = autoregressive(300, 0.9, &mut rng);
+        let ys: Vec<OrderableF64> = autoregressive(300, 0.9, &mut rng)
+            .iter()
+            .map(|y| OrderableF64::new(y.to_f64() + 1.5))
+            .collect();
+
+        for &scheme in [Scheme::MovingBlock, Scheme::Stationary].iter() {
+            let bootstrap = bootstrap(scheme, 1);
+
+            let result = test_block_bootstrap(&xs, &ys, 0.95, Alternative::TwoSided, &bootstrap);
+
+            assert!(result.is_rejected);
+            assert!(result.statistic > result.critical_value);
+
+            // The distribution function of xs lies above that of ys.
+            let result = test_block_bootstrap(&xs, &ys, 0.95, Alternative::Less, &bootstrap);
+            assert!(!result.is_rejected);
+        }
+    }
+
+    #[test]
+    fn block_bootstrap_is_reproducible_with_seed() {
+        let mut rng = StdRng::from_seed(&[1]);
+        let xs = autoregressive(50, 0.5, &mut rng);
+        let ys = autoregressive(50, 0.5, &mut rng);
+
+        for &scheme in [Scheme::MovingBlock, Scheme::Stationary].iter() {
+            let bootstrap = bootstrap(scheme, 3);
+
+            let first = test_block_bootstrap(&xs, &ys, 0.9, Alternative::TwoSided, &bootstrap);
+            let second = test_block_bootstrap(&xs, &ys, 0.9, Alternative::TwoSided, &bootstrap);
+
+            assert_eq!(first.p_value, second.p_value);
+            assert_eq!(first.critical_value, second.critical_value);
+            assert_eq!(first.reject_probability, 1.0 - first.p_value);
+        }
+    }
+
+    #[test]
+    fn resample_keeps_blocks_of_consecutive_values() {
+        let mut rng = StdRng::from_seed(&[5]);
+        let xs: Vec<u32> = (0..30).collect();
+        let ys: Vec<u32> = (100..130).collect();
+        let samples: [&[u32]; 2] = [&xs, &ys];
+
+        let moving = BlockBootstrap { block_length: 5, ..bootstrap(Scheme::MovingBlock, 0) };
+        let zs = resample(&samples, 23, &moving, &mut rng);
+
+        assert_eq!(zs.len(), 23);
+        for block in zs.chunks(5) {
+            assert!(block.windows(2).all(|w| w[1] == w[0] + 1), "{:?}", zs);
+        }
+
+        let stationary = BlockBootstrap { block_length: 5, ..bootstrap(Scheme::Stationary, 0) };
+        let zs = resample(&samples, 1000, &stationary, &mut rng);
+
+        // Blocks wrap around within a sample, and have mean length 5.
+        let breaks = zs.windows(2)
+            .filter(|w| w[1] != w[0] + 1 && !(w[0] % 100 == 29 && w[1] % 100 == 0))
+            .count();
+        assert!(150 < breaks && breaks < 250, "{}", breaks);
+    }
+}
@@ -0,0 +1,466 @@
+//! Change-point detection in ordered series.
+//!
+//! Scans every split point of a series, e.g. a time series, comparing the
+//! segments either side with the two sample Kolmogorov-Smirnov statistic, so
+//! changes in any aspect of the distribution are detected rather than only
+//! shifts in the mean. The ECDFs of the segments are updated incrementally as
+//! the split point slides over the series, moving one value from the right
+//! segment to the left at a time, so the series is only sorted once.
+//!
+//! Multiple change points are found by binary segmentation, scanning each
+//! segment either side of a significant change point in turn.
+
+use std::cmp::max;
+
+use rand::Rng;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Change in distribution between two segments of a series.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ChangePoint {
+    /// Index of the first value after the change, i.e. the segments either
+    /// side are series[start..index] and series[index..end].
+    pub index: usize,
+    /// Start of the segment of the series scanned to find the change point.
+    pub start: usize,
+    /// End of the segment of the series scanned, exclusive.
+    pub end: usize,
+    /// Two sample test statistic of the segments either side.
+    pub statistic: f64,
+    /// Permutation p-value of the maximum scaled statistic over the splits
+    /// of the segment scanned.
+    pub p_value: f64,
+}
+
+/// Minimum length of a segment either side of a split, as for the two sample
+/// test.
+const MIN_SEGMENT: usize = 8;
+
+/// Calculate the two sample test statistic of every split of a series.
+///
+/// Element k of the result is the statistic of series[..k] against
+/// series[k..], for k from 0 to the length of the series inclusive, and is
+/// zero when either segment is empty.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// let series = vec!(1, 2, 1, 2, 7, 8, 7, 8);
+/// let statistics = ks::change_point::split_statistics(&series);
+///
+/// assert_eq!(statistics.len(), 9);
+/// assert_eq!(statistics[4], 1.0);
+/// assert!((statistics[2] - 2.0 / 3.0).abs() < 1e-12);
+/// ```
+pub fn split_statistics<T: Ord + Clone>(series: &[T]) -> Vec<f64> {
+    let mut statistics = vec![0.0; series.len() + 1];
+
+    scan(&dense_ranks(series), 1, |k, statistic| statistics[k] = statistic);
+
+    statistics
+}
+
+/// Detect change points in a series by binary segmentation.
+///
+/// The split of a segment of the series with the largest statistic scaled by
+/// sqrt(n1 n2 / (n1 + n2)), i.e. with the least asymptotic p-value, is a
+/// change point if the maximum is significant at the confidence level. The
+/// significance of the maximum accounts for the number of splits scanned by
+/// comparing it with the maximum of each of a number of random permutations
+/// of the segment. Segments either side of a change point are then scanned in
+/// turn, until no significant change remains.
+///
+/// Segments either side of a split have more than 7 values for the test
+/// statistic to be meaningful, so series shorter than 16 have no change
+/// points.
+///
+/// # Panics
+///
+/// There are assertion panics if the confidence level is not strictly between
+/// zero and one, or if permutations is zero.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+/// extern crate rand;
+///
+/// // The level of the series increases at index 40.
+/// let series: Vec<i64> = (0..80).map(|i| if i < 40 { i % 5 } else { 10 + i % 5 }).collect();
+///
+/// let change_points = ks::change_point::detect(&series, 0.95, 99, &mut rand::thread_rng());
+///
+/// assert_eq!(change_points.len(), 1);
+/// assert_eq!(change_points[0].index, 40);
+/// ```
+pub fn detect<T: Ord + Clone>(series: &[T],
+                              confidence: f64,
+                              permutations: usize,
+                              rng: &mut dyn Rng)
+                              -> Vec<ChangePoint> {
+    assert!(0.0 < confidence && confidence < 1.0);
+    assert!(permutations > 0);
+
+    let mut change_points = Vec::new();
+    let mut segments = vec![(0, series.len())];
+
+    while let Some((start, end)) = segments.pop() {
+        if end - start < 2 * MIN_SEGMENT {
+            continue;
+        }
+
+        let mut ranks = dense_ranks(&series[start..end]);
+        let (index, statistic, scaled) = best_split(&ranks);
+
+        // Permutations of the segment are samples of the maximum over the
+        // splits with no change point.
+        let mut extreme = 0;
+        for _ in 0..permutations {
+            shuffle(&mut ranks, rng);
+            if best_split(&ranks).2 >= scaled {
+                extreme += 1;
+            }
+        }
+        let p_value = (extreme + 1) as f64 / (permutations + 1) as f64;
+
+        if p_value < 1.0 - confidence {
+            change_points.push(ChangePoint {
+                index: start + index,
+                start: start,
+                end: end,
+                statistic: statistic,
+                p_value: p_value,
+            });
+            segments.push((start, start + index));
+            segments.push((start + index, end));
+        }
+    }
+
+    change_points.sort_by_key(|change_point| change_point.index);
+    change_points
+}
+
+/// Find the split of a series of ranks with the largest scaled statistic,
+/// returning the split index, statistic and scaled statistic.
+fn best_split(ranks: &[usize]) -> (usize, f64, f64) {
+    let n = ranks.len() as f64;
+    let mut best = (0, 0.0, 0.0);
+
+    scan(ranks, MIN_SEGMENT, |k, statistic| {
+        let k = k as f64;
+        let scaled = (k * (n - k) / n).sqrt() * statistic;
+
+        if scaled > best.2 {
+            best = (k as usize, statistic, scaled);
+        }
+    });
+
+    best
+}
+
+/// Calculate the two sample statistic of each split of a series of dense
+/// ranks leaving at least min_segment values either side, calling f with the
+/// split index and statistic.
+///
+/// With k values of n in the left segment, and cumulative counts c_l(r) in
+/// the left segment and c(r) overall up to rank r, the difference of the
+/// ECDFs of the segments at rank r is (n c_l(r) - k c(r)) / (k (n - k)). Each
+/// numerator is a line in k whose slope, -c(r), is fixed and whose intercept
+/// increases by n over the ranks at and above that of each value moving over
+/// the split, so the largest and least differences are kept in kinetic
+/// segment trees over the ranks. Moving each value over the split takes time
+/// logarithmic in the number of distinct ranks, and the changes in the ranks
+/// attaining the extremes take amortised polylogarithmic time per split.
+fn scan<F: FnMut(usize, f64)>(ranks: &[usize], min_segment: usize, mut f: F) {
+    let n = ranks.len();
+    if n < 2 * min_segment {
+        return;
+    }
+
+    let num_ranks = ranks.iter().map(|&rank| rank + 1).fold(0, max);
+    let mut total = vec![0; num_ranks];
+    for &rank in ranks.iter() {
+        total[rank] += 1;
+    }
+
+    let mut count = 0;
+    let slopes: Vec<i64> = total.iter()
+        .map(|&total| {
+            count += total;
+            count
+        })
+        .collect();
+
+    let mut upper = KineticTree::new(&slopes.iter().map(|&slope| -slope).collect::<Vec<_>>());
+    let mut lower = KineticTree::new(&slopes);
+
+    for k in 1..(n - min_segment + 1) {
+        upper.advance(k as i64);
+        lower.advance(k as i64);
+        upper.add(ranks[k - 1], n as i64);
+        lower.add(ranks[k - 1], -(n as i64));
+
+        if k >= min_segment {
+            let numerator = max(upper.max(), lower.max());
+            f(k, numerator as f64 / (k * (n - k)) as f64);
+        }
+    }
+}
+
+/// Line a + b t at time t in a kinetic segment tree.
+#[derive(Debug, Clone, Copy)]
+struct Line {
+    intercept: i64,
+    slope: i64,
+}
+
+impl Line {
+    fn at(&self, time: i64) -> i64 {
+        self.intercept + self.slope * time
+    }
+}
+
+/// Node of a kinetic segment tree, holding the line of its range that is
+/// greatest at the current time, an intercept still to be added to the lines
+/// of its children, and the earliest time at which the greatest line of any
+/// node in its subtree changes.
+#[derive(Debug, Clone, Copy)]
+struct KineticNode {
+    line: Line,
+    pending: i64,
+    melt: i64,
+}
+
+/// Kinetic segment tree over a fixed sequence of lines, supporting adding to
+/// the intercepts of a suffix of the lines and the maximum of the lines at a
+/// time that never decreases.
+///
+/// The greatest line of each node only changes when its time passes the melt
+/// time of the node, so advancing the time recalculates only the nodes which
+/// have melted, and adding to the intercepts recalculates only the nodes on
+/// the boundary of the suffix.
+struct KineticTree {
+    nodes: Vec<KineticNode>,
+    len: usize,
+    time: i64,
+}
+
+impl KineticTree {
+    /// Create a tree of lines with the given slopes and zero intercepts at
+    /// time zero.
+    fn new(slopes: &[i64]) -> KineticTree {
+        let leaf = KineticNode {
+            line: Line {
+                intercept: 0,
+                slope: 0,
+            },
+            pending: 0,
+            melt: i64::MAX,
+        };
+        let mut tree = KineticTree {
+            nodes: vec![leaf; 4 * slopes.len()],
+            len: slopes.len(),
+            time: 0,
+        };
+
+        tree.build(1, 0, slopes.len(), slopes);
+        tree
+    }
+
+    /// Maximum of the lines at the current time.
+    fn max(&self) -> i64 {
+        self.nodes[1].line.at(self.time)
+    }
+
+    /// Advance the current time, which must not decrease.
+    fn advance(&mut self, time: i64) {
+        debug_assert!(time >= self.time);
+
+        self.time = time;
+        self.heat(1, 0, self.len);
+    }
+
+    /// Add to the intercepts of the lines from index start onwards.
+    fn add(&mut self, start: usize, value: i64) {
+        let len = self.len;
+        self.add_range(1, 0, len, start, value);
+    }
+
+    fn build(&mut self, node: usize, lo: usize, hi: usize, slopes: &[i64]) {
+        if hi - lo == 1 {
+            self.nodes[node].line.slope = slopes[lo];
+        } else {
+            let mid = (lo + hi) / 2;
+            self.build(2 * node, lo, mid, slopes);
+            self.build(2 * node + 1, mid, hi, slopes);
+            self.pull(node);
+        }
+    }
+
+    fn add_range(&mut self, node: usize, lo: usize, hi: usize, start: usize, value: i64) {
+        if start >= hi {
+            return;
+        }
+
+        if start <= lo {
+            // Adding to every line of the node keeps the greatest line.
+            self.nodes[node].line.intercept += value;
+            self.nodes[node].pending += value;
+        } else {
+            let mid = (lo + hi) / 2;
+            self.push(node);
+            self.add_range(2 * node, lo, mid, start, value);
+            self.add_range(2 * node + 1, mid, hi, start, value);
+            self.pull(node);
+        }
+    }
+
+    /// Recalculate the greatest lines of the nodes that have melted.
+    fn heat(&mut self, node: usize, lo: usize, hi: usize) {
+        if self.nodes[node].melt > self.time {
+            return;
+        }
+
+        let mid = (lo + hi) / 2;
+        self.push(node);
+        self.heat(2 * node, lo, mid);
+        self.heat(2 * node + 1, mid, hi);
+        self.pull(node);
+    }
+
+    /// Add the pending intercept of an internal node to its children.
+    fn push(&mut self, node: usize) {
+        let pending = self.nodes[node].pending;
+
+        if pending != 0 {
+            for child in 2 * node..2 * node + 2 {
+                self.nodes[child].line.intercept += pending;
+                self.nodes[child].pending += pending;
+            }
+            self.nodes[node].pending = 0;
+        }
+    }
+
+    /// Recalculate the greatest line and melt time of an internal node from
+    /// its children.
+    fn pull(&mut self, node: usize) {
+        let (left, right) = (self.nodes[2 * node], self.nodes[2 * node + 1]);
+        let (value_left, value_right) = (left.line.at(self.time), right.line.at(self.time));
+
+        // Ties go to the line with the greater slope, which stays greatest.
+        let (best, other) = if (value_left, left.line.slope) >= (value_right, right.line.slope) {
+            (left.line, right.line)
+        } else {
+            (right.line, left.line)
+        };
+
+        // The other line is greater from the first time after they cross.
+        let melt = if other.slope > best.slope {
+            let gap = best.at(self.time) - other.at(self.time);
+            self.time + gap / (other.slope - best.slope) + 1
+        } else {
+            i64::MAX
+        };
+
+        self.nodes[node].line = best;
+        self.nodes[node].melt = melt.min(left.melt).min(right.melt);
+    }
+}
+
+/// Replace the values of a series by their ranks among the distinct values,
+/// so equal values have equal ranks.
+fn dense_ranks<T: Ord + Clone>(series: &[T]) -> Vec<usize> {
+    let mut values = series.to_vec();
+    values.sort();
+    values.dedup();
+
+    series.iter().map(|x| values.binary_search(x).unwrap()).collect()
+}
+
+/// Shuffle a slice into a uniformly random permutation.
+fn shuffle<T>(values: &mut [T], rng: &mut dyn Rng) {
+    for i in (1..values.len()).rev() {
+        let j = ((rng.next_f64() * (i + 1) as f64) as usize).min(i);
+        values.swap(i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate quickcheck;
+    extern crate rand;
+
+    use self::quickcheck::{quickcheck, TestResult};
+    use self::rand::{SeedableRng, StdRng};
+
+    use super::{detect, split_statistics};
+    use distributions::{ContinuousDistribution, Normal};
+    use ecdf::Ecdf;
+    use numeric::OrderableF64;
+    use test::distance;
+
+    #[test]
+    fn split_statistics_equal_two_sample_distance() {
+        fn prop(series: Vec<u8>) -> TestResult {
+            if series.len() < 2 {
+                return TestResult::discard();
+            }
+
+            let statistics = split_statistics(&series);
+
+            for k in 1..series.len() {
+                let expected = distance(&Ecdf::new(&series[..k]), &Ecdf::new(&series[k..]))
+                    .statistic;
+
+                if (statistics[k] - expected).abs() > 1e-12 {
+                    return TestResult::failed();
+                }
+            }
+
+            TestResult::from_bool(statistics[0] == 0.0 && statistics[series.len()] == 0.0)
+        }
+
+        quickcheck(prop as fn(Vec<u8>) -> TestResult);
+    }
+
+    #[test]
+    fn detect_finds_changes_in_mean_and_variance() {
+        let mut rng = StdRng::from_seed(&[42]);
+
+        let segments = [(0.0, 1.0), (3.0, 1.0), (3.0, 5.0)];
+        let series: Vec<OrderableF64> = segments.iter()
+            .flat_map(|&(mean, std_dev)| {
+                let normal = Normal::new(mean, std_dev);
+                (0..100).map(|_| OrderableF64::new(normal.sample(&mut rng))).collect::<Vec<_>>()
+            })
+            .collect();
+
+        let change_points = detect(&series, 0.99, 99, &mut rng);
+
+        assert_eq!(change_points.len(), 2, "{:?}", change_points);
+        assert!((change_points[0].index as i64 - 100).abs() <= 5);
+        assert!((change_points[1].index as i64 - 200).abs() <= 15, "{:?}", change_points);
+        assert!(change_points.iter().all(|change_point| change_point.p_value <= 0.01));
+    }
+
+    #[test]
+    fn detect_finds_no_change_in_stationary_series() {
+        let mut rng = StdRng::from_seed(&[7]);
+        let normal = Normal::new(0.0, 1.0);
+
+        let series: Vec<OrderableF64> = (0..200)
+            .map(|_| OrderableF64::new(normal.sample(&mut rng)))
+            .collect();
+
+        assert!(detect(&series, 0.99, 99, &mut rng).is_empty());
+
+        // Too short to split into segments of more than 7 values.
+        let short: Vec<u32> = (0..15).map(|i| if i < 7 { 0 } else { 100 }).collect();
+        assert!(detect(&short, 0.95, 99, &mut rng).is_empty());
+    }
+}
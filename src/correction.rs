@@ -0,0 +1,374 @@
+//! Multiple testing corrections.
+//!
+//! Testing many hypotheses at a fixed confidence level leads to many false
+//! rejections. These corrections adjust the p-values of a batch of tests so
+//! that rejecting the adjusted p-values below the significance level controls
+//! either the family-wise error rate, i.e. the probability of any false
+//! rejection, or the false discovery rate, i.e. the expected proportion of
+//! false rejections. The adjusted p-values are as for R's `p.adjust`.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use test::TestOutcome;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Multiple testing correction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "kebab-case"))]
+pub enum Correction {
+    /// Multiplies each p-value by the number of tests. Controls the
+    /// family-wise error rate.
+    Bonferroni,
+    /// Holm's step-down method. Controls the family-wise error rate and is
+    /// uniformly more powerful than Bonferroni.
+    Holm,
+    /// Hochberg's step-up method. Controls the family-wise error rate for
+    /// independent or positively dependent tests.
+    Hochberg,
+    /// Benjamini-Hochberg step-up method. Controls the false discovery rate
+    /// for independent or positively dependent tests.
+    BenjaminiHochberg,
+    /// Benjamini-Yekutieli step-up method. Controls the false discovery rate
+    /// under any dependence between tests.
+    BenjaminiYekutieli,
+}
+
+impl fmt::Display for Correction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Correction::Bonferroni => "bonferroni".fmt(f),
+            Correction::Holm => "holm".fmt(f),
+            Correction::Hochberg => "hochberg".fmt(f),
+            Correction::BenjaminiHochberg => "benjamini-hochberg".fmt(f),
+            Correction::BenjaminiYekutieli => "benjamini-yekutieli".fmt(f),
+        }
+    }
+}
+
+impl FromStr for Correction {
+    type Err = String;
+
+    /// Parse a correction by name, or bh and by for the Benjamini methods.
+    fn from_str(s: &str) -> Result<Correction, String> {
+        match s {
+            "bonferroni" => Ok(Correction::Bonferroni),
+            "holm" => Ok(Correction::Holm),
+            "hochberg" => Ok(Correction::Hochberg),
+            "benjamini-hochberg" | "bh" => Ok(Correction::BenjaminiHochberg),
+            "benjamini-yekutieli" | "by" => Ok(Correction::BenjaminiYekutieli),
+            _ => Err("expected bonferroni, holm, hochberg, benjamini-hochberg (bh) or \
+                      benjamini-yekutieli (by)"
+                         .to_string()),
+        }
+    }
+}
+
+/// Adjusted p-value of a test in a batch and whether it is rejected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Decision {
+    pub p_value: f64,
+    pub adjusted_p_value: f64,
+    /// Whether the adjusted p-value is below the significance level, i.e. one
+    /// minus the confidence level.
+    pub is_rejected: bool,
+}
+
+/// Adjust the p-values of a batch of tests for multiple testing.
+///
+/// # Panics
+///
+/// There are assertion panics if any p-value is NaN or not in [0, 1].
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::correction::{adjust, Correction};
+///
+/// let p_values = vec!(0.01, 0.04, 0.03, 0.005);
+///
+/// assert_eq!(adjust(&p_values, Correction::Bonferroni), vec!(0.04, 0.16, 0.12, 0.02));
+/// assert_eq!(adjust(&p_values, Correction::Holm), vec!(0.03, 0.06, 0.06, 0.02));
+/// ```
+pub fn adjust(p_values: &[f64], correction: Correction) -> Vec<f64> {
+    assert!(p_values.iter().all(|p| (0.0..=1.0).contains(p)));
+
+    let n = p_values.len();
+    let m = n as f64;
+
+    // Indices of the p-values in increasing or decreasing order of p-value.
+    let mut order: Vec<usize> = (0..n).collect();
+    let ascending = |&a: &usize, &b: &usize| {
+        p_values[a].partial_cmp(&p_values[b]).unwrap_or(Ordering::Equal)
+    };
+
+    let mut adjusted = vec![0.0; n];
+
+    match correction {
+        Correction::Bonferroni => {
+            for (i, &p) in p_values.iter().enumerate() {
+                adjusted[i] = p * m;
+            }
+        }
+        Correction::Holm => {
+            // Step down from the smallest p-value, multiplied by the number
+            // of hypotheses remaining, keeping the adjusted p-values monotone.
+            order.sort_by(ascending);
+            let mut running_max: f64 = 0.0;
+            for (rank, &i) in order.iter().enumerate() {
+                running_max = running_max.max((m - rank as f64) * p_values[i]);
+                adjusted[i] = running_max;
+            }
+        }
+        Correction::Hochberg | Correction::BenjaminiHochberg | Correction::BenjaminiYekutieli => {
+            // Step up from the largest p-value, keeping the adjusted p-values
+            // monotone.
+            order.sort_by(|a, b| ascending(b, a));
+
+            let harmonic: f64 = (1..n + 1).map(|i| 1.0 / i as f64).sum();
+            let mut running_min = f64::INFINITY;
+
+            for (k, &i) in order.iter().enumerate() {
+                // Rank of the p-value counting from one at the smallest.
+                let rank = (n - k) as f64;
+                let factor = match correction {
+                    Correction::Hochberg => m - rank + 1.0,
+                    Correction::BenjaminiHochberg => m / rank,
+                    _ => harmonic * m / rank,
+                };
+                running_min = running_min.min(factor * p_values[i]);
+                adjusted[i] = running_min;
+            }
+        }
+    }
+
+    adjusted.into_iter().map(|p| p.min(1.0)).collect()
+}
+
+/// Adjust the p-values of a batch of tests for multiple testing and decide
+/// which tests are rejected at the confidence level.
+///
+/// # Panics
+///
+/// There are assertion panics if the confidence level is not strictly between
+/// zero and one, or if any p-value is NaN or not in [0, 1].
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::correction::{correct, Correction};
+///
+/// let decisions = correct(&[0.01, 0.04, 0.03, 0.005], Correction::Holm, 0.95);
+/// let rejected: Vec<bool> = decisions.iter().map(|d| d.is_rejected).collect();
+///
+/// assert_eq!(rejected, vec!(true, false, false, true));
+/// ```
+pub fn correct(p_values: &[f64], correction: Correction, confidence: f64) -> Vec<Decision> {
+    assert!(0.0 < confidence && confidence < 1.0);
+
+    p_values.iter()
+            .zip(adjust(p_values, correction))
+            .map(|(&p_value, adjusted_p_value)| {
+                Decision {
+                    p_value: p_value,
+                    adjusted_p_value: adjusted_p_value,
+                    is_rejected: adjusted_p_value < 1.0 - confidence,
+                }
+            })
+            .collect()
+}
+
+/// Adjust the p-values of a batch of test results for multiple testing and
+/// decide which tests are rejected at the confidence level.
+///
+/// The confidence level applies to the batch as a whole, e.g. the family-wise
+/// error rate, rather than to each test as for the confidence level of each
+/// result.
+///
+/// # Panics
+///
+/// There is an assertion panic if the confidence level is not strictly between
+/// zero and one.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::correction::{correct_results, Correction};
+///
+/// let xs: Vec<u32> = (0..40).collect();
+/// let ys: Vec<u32> = (20..60).collect();
+/// let results = vec!(ks::test(&xs, &xs, 0.95), ks::test(&xs, &ys, 0.95));
+///
+/// let decisions = correct_results(&results, Correction::Bonferroni, 0.95);
+/// assert!(!decisions[0].is_rejected && decisions[1].is_rejected);
+/// ```
+pub fn correct_results<R: TestOutcome>(results: &[R],
+                                       correction: Correction,
+                                       confidence: f64)
+                                       -> Vec<Decision> {
+    let p_values: Vec<f64> = results.iter()
+        .map(|result| result.p_value().clamp(0.0, 1.0))
+        .collect();
+
+    correct(&p_values, correction, confidence)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate quickcheck;
+    extern crate rand;
+
+    use self::quickcheck::{Arbitrary, Gen, QuickCheck, StdGen, Testable};
+    use std::cmp;
+
+    use super::{adjust, correct, Correction};
+
+    const CORRECTIONS: [Correction; 5] = [Correction::Bonferroni,
+                                          Correction::Holm,
+                                          Correction::Hochberg,
+                                          Correction::BenjaminiHochberg,
+                                          Correction::BenjaminiYekutieli];
+
+    const EPSILON: f64 = 1e-12;
+
+    fn check<A: Testable>(f: A) {
+        let g = StdGen::new(rand::thread_rng(), usize::MAX);
+        QuickCheck::new().gen(g).quickcheck(f);
+    }
+
+    /// Wrapper for generating p-values with QuickCheck.
+    ///
+    /// P-values are stored in thousandths so that ties are common and shrinking
+    /// stays within [0, 1].
+    #[derive(Debug, Clone)]
+    struct PValues {
+        vec: Vec<u16>,
+    }
+
+    impl PValues {
+        fn to_vec(&self) -> Vec<f64> {
+            self.vec.iter().map(|&p| p as f64 / 1000.0).collect()
+        }
+    }
+
+    impl Arbitrary for PValues {
+        fn arbitrary<G: Gen>(g: &mut G) -> PValues {
+            // Limit size of generated batches to 256 and favour small
+            // p-values, as for a batch with some real differences.
+            let max = cmp::min(g.size(), 256);
+
+            let size = g.gen_range(0, max);
+            let vec = (0..size)
+                .map(|_| if g.gen() { g.gen_range(0, 50) } else { g.gen_range(0, 1001) })
+                .collect();
+
+            PValues { vec: vec }
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = PValues>> {
+            let shrunk: Box<dyn Iterator<Item = Vec<u16>>> = self.vec.shrink();
+
+            Box::new(shrunk.map(|v| PValues { vec: v }))
+        }
+    }
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-10, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn adjust_matches_r_p_adjust() {
+        // p.adjust(c(0.01, 0.02, 0.03, 0.04, 0.05, 0.5), method) in R.
+        let p_values = [0.01, 0.02, 0.03, 0.04, 0.05, 0.5];
+
+        assert_close(&adjust(&p_values, Correction::Bonferroni),
+                     &[0.06, 0.12, 0.18, 0.24, 0.30, 1.0]);
+        assert_close(&adjust(&p_values, Correction::Holm),
+                     &[0.06, 0.10, 0.12, 0.12, 0.12, 0.5]);
+        assert_close(&adjust(&p_values, Correction::Hochberg),
+                     &[0.06, 0.10, 0.10, 0.10, 0.10, 0.5]);
+        assert_close(&adjust(&p_values, Correction::BenjaminiHochberg),
+                     &[0.06, 0.06, 0.06, 0.06, 0.06, 0.5]);
+        assert_close(&adjust(&p_values, Correction::BenjaminiYekutieli),
+                     &[0.147, 0.147, 0.147, 0.147, 0.147, 1.0]);
+    }
+
+    #[test]
+    fn adjusted_p_values_are_at_least_p_values_and_at_most_one() {
+        fn prop(xs: PValues) -> bool {
+            let p_values = xs.to_vec();
+
+            CORRECTIONS.iter().all(|&correction| {
+                adjust(&p_values, correction)
+                    .iter()
+                    .zip(p_values.iter())
+                    .all(|(&adjusted, &p)| p <= adjusted + EPSILON && adjusted <= 1.0)
+            })
+        }
+
+        check(prop as fn(PValues) -> bool);
+    }
+
+    #[test]
+    fn adjusted_p_values_preserve_order_of_p_values() {
+        fn prop(xs: PValues) -> bool {
+            let p_values = xs.to_vec();
+
+            CORRECTIONS.iter().all(|&correction| {
+                let adjusted = adjust(&p_values, correction);
+
+                (0..p_values.len()).all(|i| {
+                    (0..p_values.len()).all(|j| {
+                        p_values[i] > p_values[j] || adjusted[i] <= adjusted[j] + EPSILON
+                    })
+                })
+            })
+        }
+
+        check(prop as fn(PValues) -> bool);
+    }
+
+    #[test]
+    fn step_methods_reject_at_least_as_many_as_bonferroni() {
+        fn prop(xs: PValues) -> bool {
+            let p_values = xs.to_vec();
+            let rejections = |correction| {
+                correct(&p_values, correction, 0.95).iter().filter(|d| d.is_rejected).count()
+            };
+
+            let bonferroni = rejections(Correction::Bonferroni);
+            let holm = rejections(Correction::Holm);
+            let hochberg = rejections(Correction::Hochberg);
+            let bh = rejections(Correction::BenjaminiHochberg);
+            let by = rejections(Correction::BenjaminiYekutieli);
+
+            bonferroni <= holm && holm <= hochberg && hochberg <= bh && by <= bh
+        }
+
+        check(prop as fn(PValues) -> bool);
+    }
+
+    #[test]
+    fn correction_round_trips_through_strings() {
+        for &correction in CORRECTIONS.iter() {
+            assert_eq!(correction.to_string().parse::<Correction>(), Ok(correction));
+        }
+
+        assert_eq!("bh".parse::<Correction>(), Ok(Correction::BenjaminiHochberg));
+        assert_eq!("by".parse::<Correction>(), Ok(Correction::BenjaminiYekutieli));
+        assert!("sidak".parse::<Correction>().is_err());
+    }
+}
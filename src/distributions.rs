@@ -0,0 +1,1098 @@
+//! Theoretical probability distributions.
+//!
+//! Continuous and discrete distributions with their distribution functions,
+//! quantiles, densities and random variates, e.g. for one sample tests of
+//! goodness-of-fit and for generating test data.
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use rand::Rng;
+use rand::distributions::{self, IndependentSample};
+
+use special::{beta_inc, gamma_p, gamma_q, ln_beta, ln_gamma, normal_cdf, normal_pdf,
+              normal_quantile};
+
+/// Continuous probability distribution of a real valued random variable.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::distributions::{ContinuousDistribution, Exponential};
+///
+/// let exponential = Exponential::new(2.0);
+///
+/// assert_eq!(exponential.cdf(0.0), 0.0);
+/// assert_eq!(exponential.pdf(0.0), 2.0);
+/// assert!((exponential.quantile(exponential.cdf(1.5)) - 1.5).abs() < 1e-12);
+/// ```
+pub trait ContinuousDistribution {
+    /// Calculate the cumulative distribution function, P(X <= x).
+    fn cdf(&self, x: f64) -> f64;
+
+    /// Calculate the probability density function.
+    fn pdf(&self, x: f64) -> f64;
+
+    /// Calculate the quantile function, the inverse of the cdf, for p in
+    /// [0, 1]. The quantiles of zero and one are the bounds of the support
+    /// and may be infinite.
+    fn quantile(&self, p: f64) -> f64;
+
+    /// Generate a random variate, by default by inverting the cdf at a
+    /// uniform random variate.
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        self.quantile(rng.next_f64())
+    }
+}
+
+/// Discrete probability distribution of a random variable with non-negative
+/// integer values.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::distributions::{DiscreteDistribution, Binomial};
+///
+/// let binomial = Binomial::new(4, 0.5);
+///
+/// assert!((binomial.pmf(2) - 0.375).abs() < 1e-12);
+/// assert!((binomial.cdf(1.5) - 0.3125).abs() < 1e-12);
+/// assert_eq!(binomial.quantile(0.5), 2);
+/// ```
+pub trait DiscreteDistribution {
+    /// Calculate the cumulative distribution function, P(X <= x), for any
+    /// real x.
+    fn cdf(&self, x: f64) -> f64;
+
+    /// Calculate the probability mass function, P(X = k).
+    fn pmf(&self, k: u64) -> f64;
+
+    /// Calculate the quantile function, the smallest k with cdf(k) >= p, for p
+    /// in [0, 1]. The quantile of one may be u64::MAX if the support is
+    /// unbounded.
+    fn quantile(&self, p: f64) -> u64 {
+        assert!((0.0..=1.0).contains(&p));
+
+        // Double the upper bound until it reaches the quantile, then binary
+        // search for the smallest value reaching it.
+        let mut low = 0;
+        let mut high = 1;
+        while self.cdf(high as f64) < p {
+            if high > u64::MAX / 2 {
+                return u64::MAX;
+            }
+            low = high;
+            high *= 2;
+        }
+
+        if self.cdf(low as f64) >= p {
+            return low;
+        }
+
+        // Invariant cdf(low) < p <= cdf(high).
+        while low + 1 < high {
+            let mid = low + (high - low) / 2;
+            if self.cdf(mid as f64) < p {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        high
+    }
+
+    /// Generate a random variate, by default by inverting the cdf at a
+    /// uniform random variate.
+    fn sample(&self, rng: &mut dyn Rng) -> u64 {
+        self.quantile(rng.next_f64())
+    }
+}
+
+/// Uniform random variate on (0, 1], whose logarithm is finite.
+fn open_uniform(rng: &mut dyn Rng) -> f64 {
+    1.0 - rng.next_f64()
+}
+
+/// Invert a continuous cdf by bisection, doubling the initial bracket [low,
+/// high] until it contains the quantile of p in (0, 1).
+fn invert_cdf<F: Fn(f64) -> f64>(cdf: F, p: f64, mut low: f64, mut high: f64) -> f64 {
+    while cdf(low) > p {
+        low -= 2.0 * (high - low);
+    }
+    while cdf(high) < p {
+        high += 2.0 * (high - low);
+    }
+
+    // Bisect until the bracket cannot be narrowed in floating point.
+    for _ in 0..2000 {
+        let mid = low + (high - low) / 2.0;
+        if mid <= low || mid >= high {
+            break;
+        }
+
+        if cdf(mid) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    high
+}
+
+/// Calculate a * ln(x), taking it to be zero when a is zero even if x is zero,
+/// for the density terms x^a at the boundary of a support.
+fn ln_power(x: f64, a: f64) -> f64 {
+    if a == 0.0 { 0.0 } else { a * x.ln() }
+}
+
+/// Uniform distribution on the interval [low, high].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Uniform {
+    low: f64,
+    high: f64,
+}
+
+impl Uniform {
+    /// Create a uniform distribution on [low, high].
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic unless low < high and both are finite.
+    pub fn new(low: f64, high: f64) -> Uniform {
+        assert!(low < high && low.is_finite() && high.is_finite());
+
+        Uniform {
+            low: low,
+            high: high,
+        }
+    }
+}
+
+impl ContinuousDistribution for Uniform {
+    fn cdf(&self, x: f64) -> f64 {
+        ((x - self.low) / (self.high - self.low)).clamp(0.0, 1.0)
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        if self.low <= x && x <= self.high {
+            1.0 / (self.high - self.low)
+        } else {
+            0.0
+        }
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p));
+        self.low + p * (self.high - self.low)
+    }
+}
+
+impl fmt::Display for Uniform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "uniform:{},{}", self.low, self.high)
+    }
+}
+
+/// Normal distribution with a mean and standard deviation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normal {
+    mean: f64,
+    std_dev: f64,
+}
+
+impl Normal {
+    /// Create a Normal distribution.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic unless the mean is finite and the standard
+    /// deviation is positive and finite.
+    pub fn new(mean: f64, std_dev: f64) -> Normal {
+        assert!(mean.is_finite() && std_dev > 0.0 && std_dev.is_finite());
+
+        Normal {
+            mean: mean,
+            std_dev: std_dev,
+        }
+    }
+}
+
+impl ContinuousDistribution for Normal {
+    fn cdf(&self, x: f64) -> f64 {
+        normal_cdf((x - self.mean) / self.std_dev)
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        normal_pdf((x - self.mean) / self.std_dev) / self.std_dev
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        self.mean + self.std_dev * normal_quantile(p)
+    }
+
+    fn sample(&self, mut rng: &mut dyn Rng) -> f64 {
+        distributions::Normal::new(self.mean, self.std_dev).ind_sample(&mut rng)
+    }
+}
+
+impl fmt::Display for Normal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "normal:{},{}", self.mean, self.std_dev)
+    }
+}
+
+/// Log-normal distribution, of exp(X) for X with a Normal distribution with
+/// mean mu and standard deviation sigma.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogNormal {
+    mu: f64,
+    sigma: f64,
+}
+
+impl LogNormal {
+    /// Create a log-normal distribution.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic unless mu is finite and sigma is positive
+    /// and finite.
+    pub fn new(mu: f64, sigma: f64) -> LogNormal {
+        assert!(mu.is_finite() && sigma > 0.0 && sigma.is_finite());
+
+        LogNormal {
+            mu: mu,
+            sigma: sigma,
+        }
+    }
+}
+
+impl ContinuousDistribution for LogNormal {
+    fn cdf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            0.0
+        } else {
+            normal_cdf((x.ln() - self.mu) / self.sigma)
+        }
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            0.0
+        } else {
+            normal_pdf((x.ln() - self.mu) / self.sigma) / (x * self.sigma)
+        }
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        (self.mu + self.sigma * normal_quantile(p)).exp()
+    }
+
+    fn sample(&self, mut rng: &mut dyn Rng) -> f64 {
+        distributions::LogNormal::new(self.mu, self.sigma).ind_sample(&mut rng)
+    }
+}
+
+impl fmt::Display for LogNormal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "lognormal:{},{}", self.mu, self.sigma)
+    }
+}
+
+/// Exponential distribution with a rate, i.e. with mean 1 / rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exponential {
+    rate: f64,
+}
+
+impl Exponential {
+    /// Create an exponential distribution.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic unless the rate is positive and finite.
+    pub fn new(rate: f64) -> Exponential {
+        assert!(rate > 0.0 && rate.is_finite());
+
+        Exponential { rate: rate }
+    }
+}
+
+impl ContinuousDistribution for Exponential {
+    fn cdf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            0.0
+        } else {
+            -(-self.rate * x).exp_m1()
+        }
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            0.0
+        } else {
+            self.rate * (-self.rate * x).exp()
+        }
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p));
+        -(-p).ln_1p() / self.rate
+    }
+}
+
+impl fmt::Display for Exponential {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "exponential:{}", self.rate)
+    }
+}
+
+/// Gamma distribution with a shape and scale, i.e. with mean shape * scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gamma {
+    shape: f64,
+    scale: f64,
+}
+
+impl Gamma {
+    /// Create a Gamma distribution.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic unless the shape and scale are positive
+    /// and finite.
+    pub fn new(shape: f64, scale: f64) -> Gamma {
+        assert!(shape > 0.0 && shape.is_finite() && scale > 0.0 && scale.is_finite());
+
+        Gamma {
+            shape: shape,
+            scale: scale,
+        }
+    }
+}
+
+impl ContinuousDistribution for Gamma {
+    fn cdf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            0.0
+        } else {
+            gamma_p(self.shape, x / self.scale)
+        }
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            return 0.0;
+        }
+
+        let x = x / self.scale;
+        (ln_power(x, self.shape - 1.0) - x - ln_gamma(self.shape)).exp() / self.scale
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p));
+
+        match p {
+            0.0 => 0.0,
+            1.0 => f64::INFINITY,
+            _ => invert_cdf(|x| self.cdf(x), p, 0.0, self.shape * self.scale),
+        }
+    }
+
+    fn sample(&self, mut rng: &mut dyn Rng) -> f64 {
+        distributions::Gamma::new(self.shape, self.scale).ind_sample(&mut rng)
+    }
+}
+
+impl fmt::Display for Gamma {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "gamma:{},{}", self.shape, self.scale)
+    }
+}
+
+/// Beta distribution on [0, 1] with shape parameters alpha and beta.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Beta {
+    alpha: f64,
+    beta: f64,
+}
+
+impl Beta {
+    /// Create a Beta distribution.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic unless alpha and beta are positive and
+    /// finite.
+    pub fn new(alpha: f64, beta: f64) -> Beta {
+        assert!(alpha > 0.0 && alpha.is_finite() && beta > 0.0 && beta.is_finite());
+
+        Beta {
+            alpha: alpha,
+            beta: beta,
+        }
+    }
+}
+
+impl ContinuousDistribution for Beta {
+    fn cdf(&self, x: f64) -> f64 {
+        beta_inc(self.alpha, self.beta, x.clamp(0.0, 1.0))
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        if !(0.0..=1.0).contains(&x) {
+            return 0.0;
+        }
+
+        (ln_power(x, self.alpha - 1.0) + ln_power(1.0 - x, self.beta - 1.0) -
+         ln_beta(self.alpha, self.beta))
+            .exp()
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p));
+
+        match p {
+            0.0 => 0.0,
+            1.0 => 1.0,
+            _ => invert_cdf(|x| self.cdf(x), p, 0.0, 1.0),
+        }
+    }
+
+    fn sample(&self, mut rng: &mut dyn Rng) -> f64 {
+        // Ratio of Gamma variates with a common scale.
+        let x = distributions::Gamma::new(self.alpha, 1.0).ind_sample(&mut rng);
+        let y = distributions::Gamma::new(self.beta, 1.0).ind_sample(&mut rng);
+        x / (x + y)
+    }
+}
+
+impl fmt::Display for Beta {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "beta:{},{}", self.alpha, self.beta)
+    }
+}
+
+/// Weibull distribution with a scale and shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weibull {
+    scale: f64,
+    shape: f64,
+}
+
+impl Weibull {
+    /// Create a Weibull distribution.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic unless the scale and shape are positive
+    /// and finite.
+    pub fn new(scale: f64, shape: f64) -> Weibull {
+        assert!(scale > 0.0 && scale.is_finite() && shape > 0.0 && shape.is_finite());
+
+        Weibull {
+            scale: scale,
+            shape: shape,
+        }
+    }
+}
+
+impl ContinuousDistribution for Weibull {
+    fn cdf(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            0.0
+        } else {
+            -(-(x / self.scale).powf(self.shape)).exp_m1()
+        }
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            return 0.0;
+        }
+
+        let x = x / self.scale;
+        self.shape / self.scale * (ln_power(x, self.shape - 1.0) - x.powf(self.shape)).exp()
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p));
+        self.scale * (-(-p).ln_1p()).powf(1.0 / self.shape)
+    }
+}
+
+impl fmt::Display for Weibull {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "weibull:{},{}", self.scale, self.shape)
+    }
+}
+
+/// Pareto distribution with a scale, the minimum value, and a shape, the tail
+/// index.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pareto {
+    scale: f64,
+    shape: f64,
+}
+
+impl Pareto {
+    /// Create a Pareto distribution.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic unless the scale and shape are positive
+    /// and finite.
+    pub fn new(scale: f64, shape: f64) -> Pareto {
+        assert!(scale > 0.0 && scale.is_finite() && shape > 0.0 && shape.is_finite());
+
+        Pareto {
+            scale: scale,
+            shape: shape,
+        }
+    }
+}
+
+impl ContinuousDistribution for Pareto {
+    fn cdf(&self, x: f64) -> f64 {
+        if x <= self.scale {
+            0.0
+        } else {
+            1.0 - (self.scale / x).powf(self.shape)
+        }
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        if x < self.scale {
+            0.0
+        } else {
+            self.shape / x * (self.scale / x).powf(self.shape)
+        }
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p));
+        self.scale / (1.0 - p).powf(1.0 / self.shape)
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        self.scale / open_uniform(rng).powf(1.0 / self.shape)
+    }
+}
+
+impl fmt::Display for Pareto {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "pareto:{},{}", self.scale, self.shape)
+    }
+}
+
+/// Cauchy distribution with a location, the median, and a scale, the half
+/// interquartile range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cauchy {
+    location: f64,
+    scale: f64,
+}
+
+impl Cauchy {
+    /// Create a Cauchy distribution.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic unless the location is finite and the
+    /// scale is positive and finite.
+    pub fn new(location: f64, scale: f64) -> Cauchy {
+        assert!(location.is_finite() && scale > 0.0 && scale.is_finite());
+
+        Cauchy {
+            location: location,
+            scale: scale,
+        }
+    }
+}
+
+impl ContinuousDistribution for Cauchy {
+    fn cdf(&self, x: f64) -> f64 {
+        0.5 + ((x - self.location) / self.scale).atan() / PI
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        let z = (x - self.location) / self.scale;
+        1.0 / (PI * self.scale * (1.0 + z * z))
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p));
+
+        match p {
+            0.0 => f64::NEG_INFINITY,
+            1.0 => f64::INFINITY,
+            _ => self.location + self.scale * (PI * (p - 0.5)).tan(),
+        }
+    }
+}
+
+impl fmt::Display for Cauchy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cauchy:{},{}", self.location, self.scale)
+    }
+}
+
+/// Student's t-distribution with a number of degrees of freedom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StudentT {
+    degrees_of_freedom: f64,
+}
+
+impl StudentT {
+    /// Create a Student's t-distribution.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic unless the degrees of freedom are positive
+    /// and finite.
+    pub fn new(degrees_of_freedom: f64) -> StudentT {
+        assert!(degrees_of_freedom > 0.0 && degrees_of_freedom.is_finite());
+
+        StudentT { degrees_of_freedom: degrees_of_freedom }
+    }
+}
+
+impl ContinuousDistribution for StudentT {
+    fn cdf(&self, x: f64) -> f64 {
+        let nu = self.degrees_of_freedom;
+
+        // Probability of the tail beyond |x|.
+        let tail = 0.5 * beta_inc(0.5 * nu, 0.5, nu / (nu + x * x));
+
+        if x > 0.0 { 1.0 - tail } else { tail }
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        let nu = self.degrees_of_freedom;
+
+        (-0.5 * (nu + 1.0) * (x * x / nu).ln_1p() - 0.5 * nu.ln() - ln_beta(0.5 * nu, 0.5)).exp()
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p));
+
+        match p {
+            0.0 => f64::NEG_INFINITY,
+            1.0 => f64::INFINITY,
+            _ => invert_cdf(|x| self.cdf(x), p, -1.0, 1.0),
+        }
+    }
+
+    fn sample(&self, mut rng: &mut dyn Rng) -> f64 {
+        distributions::StudentT::new(self.degrees_of_freedom).ind_sample(&mut rng)
+    }
+}
+
+impl fmt::Display for StudentT {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "student-t:{}", self.degrees_of_freedom)
+    }
+}
+
+/// Poisson distribution with mean lambda.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Poisson {
+    lambda: f64,
+}
+
+impl Poisson {
+    /// Create a Poisson distribution.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic unless lambda is non-negative and finite.
+    pub fn new(lambda: f64) -> Poisson {
+        assert!(lambda >= 0.0 && lambda.is_finite());
+
+        Poisson { lambda: lambda }
+    }
+}
+
+impl DiscreteDistribution for Poisson {
+    fn cdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            0.0
+        } else if self.lambda == 0.0 {
+            1.0
+        } else {
+            gamma_q(x.floor() + 1.0, self.lambda)
+        }
+    }
+
+    fn pmf(&self, k: u64) -> f64 {
+        if self.lambda == 0.0 {
+            return if k == 0 { 1.0 } else { 0.0 };
+        }
+
+        let k = k as f64;
+        (k * self.lambda.ln() - self.lambda - ln_gamma(k + 1.0)).exp()
+    }
+
+    /// Generate a Poisson variate as the number of arrivals of a Poisson
+    /// process with unit rate in time lambda.
+    ///
+    /// The time is split into intervals with mean arrivals at most 16, which
+    /// are counted by multiplying uniform variates to avoid underflow. The
+    /// expected running time is linear in lambda.
+    fn sample(&self, rng: &mut dyn Rng) -> u64 {
+        const INTERVAL: f64 = 16.0;
+
+        let mut count = 0;
+        let mut remaining = self.lambda;
+
+        while remaining > 0.0 {
+            let interval = remaining.min(INTERVAL);
+            remaining -= interval;
+
+            let limit = (-interval).exp();
+            let mut product = rng.next_f64();
+            while product > limit {
+                count += 1;
+                product *= rng.next_f64();
+            }
+        }
+
+        count
+    }
+}
+
+impl fmt::Display for Poisson {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "poisson:{}", self.lambda)
+    }
+}
+
+/// Binomial distribution of the number of successes in a number of trials
+/// with probability of success p.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Binomial {
+    trials: u64,
+    p: f64,
+}
+
+impl Binomial {
+    /// Create a binomial distribution.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic unless p is in [0, 1].
+    pub fn new(trials: u64, p: f64) -> Binomial {
+        assert!((0.0..=1.0).contains(&p));
+
+        Binomial {
+            trials: trials,
+            p: p,
+        }
+    }
+}
+
+impl DiscreteDistribution for Binomial {
+    fn cdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            return 0.0;
+        }
+
+        let k = x.floor();
+        let n = self.trials as f64;
+        if k >= n {
+            1.0
+        } else {
+            beta_inc(n - k, k + 1.0, 1.0 - self.p)
+        }
+    }
+
+    fn pmf(&self, k: u64) -> f64 {
+        if k > self.trials {
+            return 0.0;
+        }
+
+        let (n, k) = (self.trials as f64, k as f64);
+        let ln_choose = ln_gamma(n + 1.0) - ln_gamma(k + 1.0) - ln_gamma(n - k + 1.0);
+
+        (ln_choose + ln_power(self.p, k) + ln_power(1.0 - self.p, n - k)).exp()
+    }
+
+    /// Generate a binomial variate by skipping over the failures between
+    /// successes with geometric variates. The expected running time is linear
+    /// in trials * min(p, 1 - p).
+    fn sample(&self, rng: &mut dyn Rng) -> u64 {
+        if self.p > 0.5 {
+            return self.trials - Binomial::new(self.trials, 1.0 - self.p).sample(rng);
+        }
+        if self.p == 0.0 {
+            return 0;
+        }
+
+        let geometric = Geometric::new(self.p);
+        let mut successes = 0;
+        let mut position: u64 = 0;
+
+        loop {
+            // Number of trials up to and including the next success.
+            position = position.saturating_add(geometric.sample(rng)).saturating_add(1);
+            if position > self.trials {
+                return successes;
+            }
+            successes += 1;
+        }
+    }
+}
+
+impl fmt::Display for Binomial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "binomial:{},{}", self.trials, self.p)
+    }
+}
+
+/// Geometric distribution of the number of failures before the first success
+/// in trials with probability of success p.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geometric {
+    p: f64,
+}
+
+impl Geometric {
+    /// Create a geometric distribution.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic unless p is in (0, 1].
+    pub fn new(p: f64) -> Geometric {
+        assert!(0.0 < p && p <= 1.0);
+
+        Geometric { p: p }
+    }
+}
+
+impl DiscreteDistribution for Geometric {
+    fn cdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            0.0
+        } else {
+            -((x.floor() + 1.0) * (-self.p).ln_1p()).exp_m1()
+        }
+    }
+
+    fn pmf(&self, k: u64) -> f64 {
+        self.p * (k as f64 * (-self.p).ln_1p()).exp()
+    }
+
+    fn quantile(&self, p: f64) -> u64 {
+        assert!((0.0..=1.0).contains(&p));
+
+        if self.p == 1.0 {
+            return 0;
+        }
+
+        // Smallest k with 1 - (1 - self.p)^(k + 1) >= p, stepping down in
+        // case of rounding in the logarithms.
+        let k = ((-p).ln_1p() / (-self.p).ln_1p() - 1.0).ceil().max(0.0);
+        if k >= u64::MAX as f64 {
+            return u64::MAX;
+        }
+
+        let mut k = k as u64;
+        while k > 0 && self.cdf((k - 1) as f64) >= p {
+            k -= 1;
+        }
+        k
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> u64 {
+        if self.p == 1.0 {
+            return 0;
+        }
+
+        let k = (open_uniform(rng).ln() / (-self.p).ln_1p()).floor();
+        if k >= u64::MAX as f64 { u64::MAX } else { k as u64 }
+    }
+}
+
+impl fmt::Display for Geometric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "geometric:{}", self.p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use self::rand::{SeedableRng, StdRng};
+
+    use super::{Beta, Binomial, Cauchy, ContinuousDistribution, DiscreteDistribution,
+                Exponential, Gamma, Geometric, LogNormal, Normal, Pareto, Poisson, StudentT,
+                Uniform, Weibull};
+
+    const EPSILON: f64 = 1e-9;
+
+    fn continuous() -> Vec<Box<dyn ContinuousDistribution>> {
+        vec![Box::new(Uniform::new(-1.0, 3.0)),
+             Box::new(Normal::new(1.0, 2.0)),
+             Box::new(LogNormal::new(0.5, 0.75)),
+             Box::new(Exponential::new(2.0)),
+             Box::new(Gamma::new(0.5, 2.0)),
+             Box::new(Gamma::new(4.0, 0.5)),
+             Box::new(Beta::new(2.0, 5.0)),
+             Box::new(Beta::new(0.5, 0.5)),
+             Box::new(Weibull::new(2.0, 1.5)),
+             Box::new(Pareto::new(1.0, 3.0)),
+             Box::new(Cauchy::new(-1.0, 0.5)),
+             Box::new(StudentT::new(3.0))]
+    }
+
+    fn discrete() -> Vec<Box<dyn DiscreteDistribution>> {
+        vec![Box::new(Poisson::new(3.5)),
+             Box::new(Poisson::new(40.0)),
+             Box::new(Binomial::new(20, 0.25)),
+             Box::new(Binomial::new(20, 0.75)),
+             Box::new(Geometric::new(0.2))]
+    }
+
+    /// Integrate the pdf from the 0.1 quantile to the 0.9 quantile with
+    /// Simpson's rule.
+    fn central_probability(distribution: &dyn ContinuousDistribution) -> f64 {
+        let (a, b) = (distribution.quantile(0.1), distribution.quantile(0.9));
+        let n = 2000;
+        let h = (b - a) / n as f64;
+
+        let sum: f64 = (0..n + 1)
+            .map(|i| {
+                let weight = if i == 0 || i == n {
+                    1.0
+                } else if i % 2 == 1 {
+                    4.0
+                } else {
+                    2.0
+                };
+                weight * distribution.pdf(a + i as f64 * h)
+            })
+            .sum();
+
+        sum * h / 3.0
+    }
+
+    #[test]
+    fn continuous_quantile_inverts_cdf() {
+        for distribution in continuous().iter() {
+            for &p in [0.001, 0.1, 0.25, 0.5, 0.75, 0.9, 0.999].iter() {
+                let x = distribution.quantile(p);
+                assert!((distribution.cdf(x) - p).abs() < EPSILON, "{} {}", p, x);
+            }
+        }
+    }
+
+    #[test]
+    fn continuous_pdf_integrates_to_cdf() {
+        for distribution in continuous().iter() {
+            assert!((central_probability(&**distribution) - 0.8).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn continuous_cdf_known_values() {
+        assert!((Normal::new(0.0, 1.0).cdf(1.959_963_984_540_054) - 0.975).abs() < EPSILON);
+        assert!((Gamma::new(1.0, 2.0).cdf(2.0) - (1.0 - (-1.0f64).exp())).abs() < EPSILON);
+        assert!((StudentT::new(1.0).cdf(1.0) - Cauchy::new(0.0, 1.0).cdf(1.0)).abs() < EPSILON);
+        assert!((StudentT::new(10.0).quantile(0.975) - 2.228_138_851_986_273_7).abs() < 1e-8);
+        assert!((Beta::new(2.0, 2.0).cdf(0.25) - 0.156_25).abs() < EPSILON);
+        assert_eq!(Pareto::new(2.0, 1.0).cdf(4.0), 0.5);
+        assert_eq!(Weibull::new(1.0, 1.0).cdf(1.0), Exponential::new(1.0).cdf(1.0));
+    }
+
+    #[test]
+    fn normal_cdf_of_large_and_infinite_values() {
+        let normal = Normal::new(0.0, 1.0);
+
+        assert_eq!(normal.cdf(1e200), 1.0);
+        assert_eq!(normal.cdf(-1e200), 0.0);
+        assert_eq!(normal.cdf(f64::INFINITY), 1.0);
+        assert_eq!(normal.cdf(f64::NEG_INFINITY), 0.0);
+        assert!(normal.cdf(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn continuous_samples_have_distribution_median() {
+        let mut rng = StdRng::from_seed(&[42]);
+
+        for distribution in continuous().iter() {
+            let n = 10000;
+            let median = distribution.quantile(0.5);
+            let below = (0..n).filter(|_| distribution.sample(&mut rng) <= median).count();
+
+            assert!((below as f64 / n as f64 - 0.5).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn discrete_cdf_is_sum_of_pmf() {
+        for distribution in discrete().iter() {
+            let mut sum = 0.0;
+
+            for k in 0..100 {
+                sum += distribution.pmf(k);
+                assert!((distribution.cdf(k as f64) - sum).abs() < EPSILON);
+                assert_eq!(distribution.cdf(k as f64 + 0.5), distribution.cdf(k as f64));
+            }
+
+            assert_eq!(distribution.cdf(-0.5), 0.0);
+        }
+    }
+
+    #[test]
+    fn discrete_quantile_is_smallest_value_reaching_probability() {
+        for distribution in discrete().iter() {
+            for &p in [0.0, 0.001, 0.1, 0.5, 0.9, 0.999].iter() {
+                let k = distribution.quantile(p);
+
+                assert!(distribution.cdf(k as f64) >= p);
+                assert!(k == 0 || distribution.cdf((k - 1) as f64) < p);
+            }
+        }
+
+        assert_eq!(Binomial::new(10, 0.5).quantile(1.0), 10);
+        assert_eq!(Geometric::new(0.5).quantile(1.0), u64::MAX);
+    }
+
+    #[test]
+    fn discrete_samples_have_distribution_mean_and_variance() {
+        let mut rng = StdRng::from_seed(&[42]);
+
+        // Expected means and variances.
+        let distributions: [(Box<dyn DiscreteDistribution>, f64, f64); 5] =
+            [(Box::new(Poisson::new(3.5)), 3.5, 3.5),
+             (Box::new(Poisson::new(40.0)), 40.0, 40.0),
+             (Box::new(Binomial::new(20, 0.25)), 5.0, 3.75),
+             (Box::new(Binomial::new(20, 0.75)), 15.0, 3.75),
+             (Box::new(Geometric::new(0.2)), 4.0, 20.0)];
+
+        for &(ref distribution, expected_mean, expected_variance) in distributions.iter() {
+            let n = 100000;
+            let xs: Vec<f64> = (0..n).map(|_| distribution.sample(&mut rng) as f64).collect();
+
+            let mean = xs.iter().sum::<f64>() / n as f64;
+            let variance = xs.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() /
+                           (n - 1) as f64;
+
+            assert!((mean - expected_mean).abs() < 0.02 * expected_mean);
+            assert!((variance - expected_variance).abs() < 0.05 * expected_variance);
+        }
+    }
+
+    #[test]
+    fn distributions_display_as_specifications() {
+        assert_eq!(Normal::new(0.0, 1.0).to_string(), "normal:0,1");
+        assert_eq!(StudentT::new(2.5).to_string(), "student-t:2.5");
+        assert_eq!(Binomial::new(10, 0.25).to_string(), "binomial:10,0.25");
+    }
+}
@@ -1,8 +1,22 @@
 //! Empirical cumulative distribution function.
 
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Allowance for rounding in the cumulative weights of a weighted ECDF.
+pub(crate) const EPSILON: f64 = 1e-12;
+
 pub struct Ecdf<T: Ord> {
     samples: Vec<T>,
     length: usize,
+
+    // Cumulative probability up to and including each sorted sample for ECDFs
+    // with unequal sample weights, e.g. mixtures. None if every sample has
+    // equal weight, in which case probabilities are calculated from ranks.
+    cumulative: Option<Vec<f64>>,
 }
 
 impl<T: Ord + Clone> Ecdf<T> {
@@ -37,6 +51,178 @@ impl<T: Ord + Clone> Ecdf<T> {
         Ecdf {
             samples: sorted,
             length: length,
+            cumulative: None,
+        }
+    }
+
+    /// Construct the empirical cumulative distribution function of the pooled
+    /// samples of several ECDFs.
+    ///
+    /// The already sorted samples are merged in O(n log k) time for n samples
+    /// in k ECDFs, so this is cheaper than constructing a new ECDF from the
+    /// concatenated samples, which sorts them again. The
+    /// result is equivalent to an ECDF of the pooled samples, i.e. a mixture
+    /// with weights proportional to the sample lengths.
+    ///
+    /// # Panics
+    ///
+    /// There must be at least one ECDF to merge.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let xs = ks::Ecdf::new(&vec!(9, 8, 7, 6, 5));
+    /// let ys = ks::Ecdf::new(&vec!(4, 3, 2, 1, 0));
+    /// let ecdf = ks::Ecdf::merge(&[&xs, &ys]);
+    /// assert_eq!(ecdf.value(4), 0.5);
+    /// ```
+    pub fn merge(ecdfs: &[&Ecdf<T>]) -> Ecdf<T> {
+        assert!(!ecdfs.is_empty());
+
+        if ecdfs.iter().all(|ecdf| ecdf.cumulative.is_none()) {
+            // Merge the sorted samples with a heap of the least remaining
+            // sample of each ECDF.
+            let length = ecdfs.iter().map(|e| e.length).sum();
+            let mut samples = Vec::with_capacity(length);
+            let mut heap: BinaryHeap<Reverse<(&T, usize, usize)>> = ecdfs.iter()
+                .enumerate()
+                .map(|(i, ecdf)| Reverse((&ecdf.samples[0], i, 0)))
+                .collect();
+
+            while let Some(Reverse((sample, i, index))) = heap.pop() {
+                samples.push(sample.clone());
+
+                if let Some(next) = ecdfs[i].samples.get(index + 1) {
+                    heap.push(Reverse((next, i, index + 1)));
+                }
+            }
+
+            Ecdf {
+                samples: samples,
+                length: length,
+                cumulative: None,
+            }
+        } else {
+            let components: Vec<(&Ecdf<T>, f64)> = ecdfs.iter()
+                                                         .map(|&e| (e, e.length as f64))
+                                                         .collect();
+            Ecdf::mixture(&components)
+        }
+    }
+
+    /// Construct a weighted mixture of empirical cumulative distribution
+    /// functions.
+    ///
+    /// The value of the mixture at t is the weighted average of the component
+    /// values at t. Weights are normalised so need not sum to one, and
+    /// components with zero weight are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Weights must be non-negative and at least one weight must be positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let xs = ks::Ecdf::new(&vec!(0, 1, 2, 3));
+    /// let ys = ks::Ecdf::new(&vec!(4, 5, 6, 7, 8, 9, 10, 11));
+    /// let ecdf = ks::Ecdf::mixture(&[(&xs, 3.0), (&ys, 1.0)]);
+    /// assert_eq!(ecdf.value(3), 0.75);
+    /// assert_eq!(ecdf.percentile(80), 5);
+    /// ```
+    pub fn mixture(components: &[(&Ecdf<T>, f64)]) -> Ecdf<T> {
+        assert!(components.iter().all(|&(_, weight)| weight >= 0.0));
+
+        let total: f64 = components.iter().map(|&(_, weight)| weight).sum();
+        assert!(total > 0.0);
+
+        // Pair each sample with its probability mass in the mixture.
+        let mut weighted: Vec<(T, f64)> = Vec::new();
+
+        for &(ecdf, weight) in components.iter().filter(|&&(_, w)| w > 0.0) {
+            let weight = weight / total;
+
+            for (index, sample) in ecdf.samples.iter().enumerate() {
+                weighted.push((sample.clone(), weight * ecdf.mass(index)));
+            }
+        }
+
+        // Stable sort merges the presorted component runs.
+        weighted.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let length = weighted.len();
+        let mut samples = Vec::with_capacity(length);
+        let mut cumulative = Vec::with_capacity(length);
+        let mut sum = 0.0;
+
+        for (sample, mass) in weighted {
+            sum += mass;
+            samples.push(sample);
+            cumulative.push(sum);
+        }
+
+        // Trim rounding so the final sample has cumulative probability one.
+        cumulative[length - 1] = 1.0;
+
+        Ecdf {
+            samples: samples,
+            length: length,
+            cumulative: Some(cumulative),
+        }
+    }
+
+    /// Return the number of samples.
+    pub fn num_samples(&self) -> usize {
+        self.length
+    }
+
+    /// Probability mass of the sample at an index in the sorted samples.
+    fn mass(&self, index: usize) -> f64 {
+        match self.cumulative {
+            None => 1.0 / self.length as f64,
+            Some(ref cumulative) if index == 0 => cumulative[0],
+            Some(ref cumulative) => cumulative[index] - cumulative[index - 1],
+        }
+    }
+
+    /// Cumulative probability of the samples with rank less than or equal to
+    /// the given number of samples.
+    fn probability(&self, num_samples: usize) -> f64 {
+        match self.cumulative {
+            None => num_samples as f64 / self.length as f64,
+            Some(_) if num_samples == 0 => 0.0,
+            Some(ref cumulative) => cumulative[num_samples - 1],
+        }
+    }
+
+    /// Probability mass of the samples with rank greater than low and less than
+    /// or equal to high.
+    fn probability_between(&self, low: usize, high: usize) -> f64 {
+        match self.cumulative {
+            None => (high - low) as f64 / self.length as f64,
+            Some(_) => (self.probability(high) - self.probability(low)).max(0.0),
+        }
+    }
+
+    /// Rank of the first sample with cumulative probability at least p / scale.
+    /// This is the Nearest Rank method generalised for unequal sample weights.
+    fn nearest_rank(&self, p: f64, scale: f64) -> usize {
+        match self.cumulative {
+            None => {
+                // Allow for rounding in p, e.g. 0.07 * 100 is not exactly 7.
+                let rank = p * self.length as f64 / scale;
+                (rank - rank * EPSILON).ceil() as usize
+            }
+            Some(ref cumulative) => {
+                let p = p / scale;
+                let index = cumulative.partition_point(|&c| c < p - EPSILON);
+
+                index.min(self.length - 1) + 1
+            }
         }
     }
 
@@ -53,28 +239,12 @@ impl<T: Ord + Clone> Ecdf<T> {
     /// assert_eq!(ecdf.value(4), 0.5);
     /// ```
     pub fn value(&self, t: T) -> f64 {
-        let num_samples_leq_t = match self.samples.binary_search(&t) {
-            Ok(mut index) => {
-                // At least one sample is a t and we have the index of it. Need
-                // to walk down the sorted samples until at last that == t.
-                while index + 1 < self.length && self.samples[index + 1] == t {
-                    index += 1;
-                }
+        // Binary search for the index of the first sample greater than t. All
+        // samples to the left are <= t and counted in the cdf proportion. This
+        // avoids walking through runs of samples equal to t.
+        let num_samples_leq_t = self.samples.partition_point(|sample| *sample <= t);
 
-                // Compensate for 0-based indexing.
-                index + 1
-            }
-            Err(index) => {
-                // No sample is a t but if we had to put one in it would go at
-                // index. This means all indices to the left have samples < t
-                // and should be counted in the cdf proportion. We must take one
-                // from index to get the last included sample but then we just
-                // have to add one again to account for 0-based indexing.
-                index
-            }
-        };
-
-        num_samples_leq_t as f64 / self.length as f64
+        self.probability(num_samples_leq_t)
     }
 
     /// Calculate a percentile for the sample using the Nearest Rank method.
@@ -96,7 +266,7 @@ impl<T: Ord + Clone> Ecdf<T> {
     pub fn percentile(&self, p: u8) -> T {
         assert!(0 < p && p <= 100);
 
-        let rank = (p as f64 * self.length as f64 / 100.0).ceil() as usize;
+        let rank = self.nearest_rank(p as f64, 100.0);
         self.samples[rank - 1].clone()
     }
 
@@ -119,7 +289,33 @@ impl<T: Ord + Clone> Ecdf<T> {
     pub fn permille(&self, p: u16) -> T {
         assert!(0 < p && p <= 1000);
 
-        let rank = (p as f64 * self.length as f64 / 1000.0).ceil() as usize;
+        let rank = self.nearest_rank(p as f64, 1000.0);
+        self.samples[rank - 1].clone()
+    }
+
+    /// Calculate a quantile for the sample using the Nearest Rank method.
+    ///
+    /// This generalises percentiles and permilles to any probability, the
+    /// quantile is the minimal sample value with ECDF value at least p.
+    ///
+    /// # Panics
+    ///
+    /// The probability requested must be greater than 0 and less than or
+    /// equal to 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    /// let ecdf = ks::Ecdf::new(&samples);
+    /// assert_eq!(ecdf.quantile(0.25), 2);
+    /// ```
+    pub fn quantile(&self, p: f64) -> T {
+        assert!(0.0 < p && p <= 1.0);
+
+        let rank = self.nearest_rank(p, 1.0);
         self.samples[rank - 1].clone()
     }
 
@@ -174,6 +370,394 @@ impl<T: Ord + Clone> Ecdf<T> {
     pub fn max(&self) -> T {
         self.samples[self.samples.len() - 1].clone()
     }
+
+    /// Calculate a value of the empirical survival function for a given
+    /// sample, i.e. the proportion of samples strictly greater than it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    /// let ecdf = ks::Ecdf::new(&samples);
+    /// assert_eq!(ecdf.survival(6), 0.3);
+    /// ```
+    pub fn survival(&self, t: T) -> f64 {
+        let num_samples_leq_t = self.samples.partition_point(|sample| *sample <= t);
+        self.probability_between(num_samples_leq_t, self.length)
+    }
+
+    /// Calculate the proportion of samples strictly less than a given sample.
+    ///
+    /// This is the left limit of the empirical cumulative distribution function
+    /// and differs from the value only at sample values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 4, 2, 1, 0);
+    /// let ecdf = ks::Ecdf::new(&samples);
+    /// assert_eq!(ecdf.value_lt(4), 0.3);
+    /// assert_eq!(ecdf.value(4), 0.5);
+    /// ```
+    pub fn value_lt(&self, t: T) -> f64 {
+        let num_samples_lt_t = self.samples.partition_point(|sample| *sample < t);
+        self.probability(num_samples_lt_t)
+    }
+
+    /// Calculate the proportion of samples in the interval (a, b], i.e.
+    /// greater than a and less than or equal to b.
+    ///
+    /// The interval is empty, with proportion zero, unless a < b.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    /// let ecdf = ks::Ecdf::new(&samples);
+    /// assert_eq!(ecdf.interval(2, 6), 0.4);
+    /// ```
+    pub fn interval(&self, a: T, b: T) -> f64 {
+        if a >= b {
+            return 0.0;
+        }
+
+        let num_samples_leq_a = self.samples.partition_point(|sample| *sample <= a);
+        let num_samples_leq_b = self.samples.partition_point(|sample| *sample <= b);
+
+        self.probability_between(num_samples_leq_a, num_samples_leq_b)
+    }
+
+    /// Return an iterator over the steps of the empirical cumulative
+    /// distribution function in increasing order of sample value.
+    ///
+    /// There is one step for each distinct sample value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(2, 1, 1, 0);
+    /// let ecdf = ks::Ecdf::new(&samples);
+    ///
+    /// for step in ecdf.steps() {
+    ///     println!("{}\t{}\t{}", step.value, step.probability, step.count);
+    /// }
+    ///
+    /// let counts: Vec<usize> = ecdf.steps().map(|step| step.count).collect();
+    /// assert_eq!(counts, vec!(1, 2, 1));
+    /// ```
+    pub fn steps<'a>(&'a self) -> Steps<'a, T> {
+        Steps {
+            ecdf: self,
+            index: 0,
+        }
+    }
+}
+
+/// Step in an empirical cumulative distribution function.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Step<T> {
+    /// Sample value at which the step occurs.
+    pub value: T,
+    /// Value of the ECDF at the sample value, i.e. after the step.
+    pub probability: f64,
+    /// Number of samples equal to the sample value.
+    pub count: usize,
+}
+
+/// Iterator over the steps of an Ecdf<T>.
+///
+/// Constructed using Ecdf::steps.
+pub struct Steps<'a, T: 'a + Ord> {
+    ecdf: &'a Ecdf<T>,
+    index: usize,
+}
+
+impl<'a, T: Ord + Clone> Iterator for Steps<'a, T> {
+    type Item = Step<T>;
+
+    fn next(&mut self) -> Option<Step<T>> {
+        let samples = &self.ecdf.samples;
+
+        if self.index >= samples.len() {
+            return None;
+        }
+
+        // Advance through duplicate samples to the end of the step.
+        let start = self.index;
+        let value = &samples[start];
+        while self.index < samples.len() && samples[self.index] == *value {
+            self.index += 1;
+        }
+
+        Some(Step {
+            value: value.clone(),
+            probability: self.ecdf.probability(self.index),
+            count: self.index - start,
+        })
+    }
+}
+
+/// Empirical cumulative distribution function compressed by sample value.
+///
+/// Only the distinct sample values are stored, along with the cumulative count
+/// of samples up to and including each value. For heavily tied samples this
+/// uses far less memory than Ecdf<T> and calculates values, ranks, and
+/// percentiles in O(log k) time where k is the number of distinct values,
+/// regardless of the number of ties.
+pub struct CompressedEcdf<T: Ord> {
+    values: Vec<T>,
+    counts: Vec<usize>,
+
+    // Cumulative probability up to and including each distinct value for
+    // ECDFs with unequal sample weights. None if every sample has equal
+    // weight, in which case probabilities are calculated from counts.
+    cumulative: Option<Vec<f64>>,
+}
+
+impl<T: Ord + Clone> CompressedEcdf<T> {
+    /// Construct a new compressed representation of a cumulative distribution
+    /// function for a given sample.
+    ///
+    /// Samples are tallied by value without taking a sorted copy of the sample,
+    /// so memory use is proportional to the number of distinct values.
+    ///
+    /// # Panics
+    ///
+    /// The sample set must be non-empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(3, 3, 3, 2, 2, 1, 1, 1, 1, 0);
+    /// let ecdf = ks::CompressedEcdf::new(&samples);
+    /// ```
+    pub fn new(samples: &[T]) -> CompressedEcdf<T> {
+        assert!(!samples.is_empty());
+
+        let mut tally: BTreeMap<T, usize> = BTreeMap::new();
+        for sample in samples.iter() {
+            *tally.entry(sample.clone()).or_insert(0) += 1;
+        }
+
+        let mut values = Vec::with_capacity(tally.len());
+        let mut counts = Vec::with_capacity(tally.len());
+        let mut total = 0;
+
+        for (value, count) in tally {
+            total += count;
+            values.push(value);
+            counts.push(total);
+        }
+
+        CompressedEcdf {
+            values: values,
+            counts: counts,
+            cumulative: None,
+        }
+    }
+
+    /// Return the number of samples.
+    pub fn num_samples(&self) -> usize {
+        self.counts[self.counts.len() - 1]
+    }
+
+    /// Return the number of distinct sample values.
+    pub fn num_values(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Calculate a value of the empirical cumulative distribution function for
+    /// a given sample.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(3, 3, 3, 2, 2, 1, 1, 1, 1, 0);
+    /// let ecdf = ks::CompressedEcdf::new(&samples);
+    /// assert_eq!(ecdf.value(1), 0.5);
+    /// ```
+    pub fn value(&self, t: T) -> f64 {
+        // Number of distinct values <= t.
+        let index = self.values.partition_point(|value| *value <= t);
+
+        if index == 0 {
+            return 0.0;
+        }
+
+        match self.cumulative {
+            None => self.counts[index - 1] as f64 / self.num_samples() as f64,
+            Some(ref cumulative) => cumulative[index - 1],
+        }
+    }
+
+    /// Calculate a percentile for the sample using the Nearest Rank method.
+    ///
+    /// # Panics
+    ///
+    /// The percentile requested must be between 1 and 100 inclusive. In
+    /// particular, there is no 0-percentile.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(3, 3, 3, 2, 2, 1, 1, 1, 1, 0);
+    /// let ecdf = ks::CompressedEcdf::new(&samples);
+    /// assert_eq!(ecdf.percentile(50), 1);
+    /// ```
+    pub fn percentile(&self, p: u8) -> T {
+        assert!(0 < p && p <= 100);
+        self.nearest_rank_value(p as f64, 100.0)
+    }
+
+    /// Calculate a permille for the sample using the Nearest Rank method.
+    ///
+    /// # Panics
+    ///
+    /// The permille requested must be between 1 and 1000 inclusive. In
+    /// particular, there is no 0-permille.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(3, 3, 3, 2, 2, 1, 1, 1, 1, 0);
+    /// let ecdf = ks::CompressedEcdf::new(&samples);
+    /// assert_eq!(ecdf.permille(500), 1);
+    /// ```
+    pub fn permille(&self, p: u16) -> T {
+        assert!(0 < p && p <= 1000);
+        self.nearest_rank_value(p as f64, 1000.0)
+    }
+
+    /// Value of the first sample with cumulative probability at least
+    /// p / scale.
+    fn nearest_rank_value(&self, p: f64, scale: f64) -> T {
+        match self.cumulative {
+            None => {
+                let rank = (p * self.num_samples() as f64 / scale).ceil() as usize;
+                self.rank(rank)
+            }
+            Some(ref cumulative) => {
+                let p = p / scale;
+                let index = cumulative.partition_point(|&c| c < p - EPSILON);
+
+                self.values[index.min(self.values.len() - 1)].clone()
+            }
+        }
+    }
+
+    /// Calculate a rank element for the sample.
+    ///
+    /// # Panics
+    ///
+    /// The rank requested must be between 1 and the sample length inclusive. In
+    /// particular, there is no 0-rank.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(3, 3, 3, 2, 2, 1, 1, 1, 1, 0);
+    /// let ecdf = ks::CompressedEcdf::new(&samples);
+    /// assert_eq!(ecdf.rank(5), 1);
+    /// ```
+    pub fn rank(&self, rank: usize) -> T {
+        assert!(0 < rank && rank <= self.num_samples());
+
+        // The first distinct value whose cumulative count reaches rank.
+        let index = self.counts.partition_point(|&count| count < rank);
+        self.values[index].clone()
+    }
+
+    /// Return the minimal element of the samples.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(3, 3, 3, 2, 2, 1, 1, 1, 1, 0);
+    /// let ecdf = ks::CompressedEcdf::new(&samples);
+    /// assert_eq!(ecdf.min(), 0);
+    /// ```
+    pub fn min(&self) -> T {
+        self.values[0].clone()
+    }
+
+    /// Return the maximal element of the samples.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(3, 3, 3, 2, 2, 1, 1, 1, 1, 0);
+    /// let ecdf = ks::CompressedEcdf::new(&samples);
+    /// assert_eq!(ecdf.max(), 3);
+    /// ```
+    pub fn max(&self) -> T {
+        self.values[self.values.len() - 1].clone()
+    }
+}
+
+/// Compress an existing ECDF, preserving any sample weights.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// let samples = vec!(3, 3, 3, 2, 2, 1, 1, 1, 1, 0);
+/// let ecdf = ks::Ecdf::new(&samples);
+/// let compressed = ks::CompressedEcdf::from(&ecdf);
+/// assert_eq!(compressed.value(1), ecdf.value(1));
+/// ```
+impl<'a, T: Ord + Clone> From<&'a Ecdf<T>> for CompressedEcdf<T> {
+    fn from(ecdf: &'a Ecdf<T>) -> CompressedEcdf<T> {
+        let mut values: Vec<T> = Vec::new();
+        let mut counts = Vec::new();
+        let mut cumulative = ecdf.cumulative.as_ref().map(|_| Vec::new());
+
+        for (index, sample) in ecdf.samples.iter().enumerate() {
+            // Only the last of a run of equal samples is retained.
+            if values.last() == Some(sample) {
+                values.pop();
+                counts.pop();
+                if let Some(ref mut cumulative) = cumulative {
+                    cumulative.pop();
+                }
+            }
+
+            values.push(sample.clone());
+            counts.push(index + 1);
+            if let Some(ref mut cumulative) = cumulative {
+                cumulative.push(ecdf.probability(index + 1));
+            }
+        }
+
+        CompressedEcdf {
+            values: values,
+            counts: counts,
+            cumulative: cumulative,
+        }
+    }
 }
 
 /// Calculate a one-time value of the empirical cumulative distribution function
@@ -308,6 +892,7 @@ pub fn permille<T: Ord + Clone>(samples: &[T], p: u16) -> T {
 /// let rank = ks::rank(&samples, 5);
 /// assert_eq!(rank, 4);
 /// ```
+#[allow(clippy::almost_swapped)]
 pub fn rank<T: Ord + Clone>(samples: &[T], rank: usize) -> T {
     let length = samples.len();
     assert!(length > 0);
@@ -399,8 +984,7 @@ mod tests {
 
     use self::quickcheck::{Arbitrary, Gen, QuickCheck, Testable, TestResult, StdGen};
     use std::cmp;
-    use std::usize;
-    use super::{Ecdf, ecdf, percentile, permille, rank};
+    use super::{Ecdf, CompressedEcdf, Step, ecdf, percentile, permille, rank};
 
     fn check<A: Testable>(f: A) {
         let g = StdGen::new(rand::thread_rng(), usize::MAX);
@@ -426,9 +1010,10 @@ mod tests {
             Samples { vec: vec }
         }
 
-        fn shrink(&self) -> Box<Iterator<Item = Samples>> {
+        #[allow(clippy::len_zero)]
+        fn shrink(&self) -> Box<dyn Iterator<Item = Samples>> {
             let vec: Vec<u64> = self.vec.clone();
-            let shrunk: Box<Iterator<Item = Vec<u64>>> = vec.shrink();
+            let shrunk: Box<dyn Iterator<Item = Vec<u64>>> = vec.shrink();
 
             Box::new(shrunk.filter(|v| v.len() > 0).map(|v| Samples { vec: v }))
         }
@@ -449,8 +1034,8 @@ mod tests {
             Percentile { val: val }
         }
 
-        fn shrink(&self) -> Box<Iterator<Item = Percentile>> {
-            let shrunk: Box<Iterator<Item = u8>> = self.val.shrink();
+        fn shrink(&self) -> Box<dyn Iterator<Item = Percentile>> {
+            let shrunk: Box<dyn Iterator<Item = u8>> = self.val.shrink();
 
             Box::new(shrunk.filter(|&v| 0u8 < v && v <= 100u8).map(|v| Percentile { val: v }))
         }
@@ -471,8 +1056,8 @@ mod tests {
             Permille { val: val }
         }
 
-        fn shrink(&self) -> Box<Iterator<Item = Permille>> {
-            let shrunk: Box<Iterator<Item = u16>> = self.val.shrink();
+        fn shrink(&self) -> Box<dyn Iterator<Item = Permille>> {
+            let shrunk: Box<dyn Iterator<Item = u16>> = self.val.shrink();
 
             Box::new(shrunk.filter(|&v| 0u16 < v && v <= 1000u16).map(|v| Permille { val: v }))
         }
@@ -494,6 +1079,7 @@ mod tests {
 
     #[test]
     fn single_use_ecdf_between_zero_and_one() {
+        #[allow(clippy::manual_range_contains)]
         fn prop(xs: Samples, val: u64) -> bool {
             let actual = ecdf(&xs.vec, val);
 
@@ -505,6 +1091,7 @@ mod tests {
 
     #[test]
     fn multiple_use_ecdf_between_zero_and_one() {
+        #[allow(clippy::manual_range_contains)]
         fn prop(xs: Samples, val: u64) -> bool {
             let ecdf = Ecdf::new(&xs.vec);
             let actual = ecdf.value(val);
@@ -623,7 +1210,7 @@ mod tests {
         fn prop(xs: Samples, val: u64) -> TestResult {
             let length = xs.vec.len();
 
-            if xs.vec.iter().any(|&x| x == val) {
+            if xs.vec.contains(&val) {
                 // Discard Vec containing val.
                 return TestResult::discard();
             }
@@ -647,7 +1234,7 @@ mod tests {
         fn prop(xs: Samples, val: u64) -> TestResult {
             let length = xs.vec.len();
 
-            if xs.vec.iter().any(|&x| x == val) {
+            if xs.vec.contains(&val) {
                 // Discard Vec containing val.
                 return TestResult::discard();
             }
@@ -1647,4 +2234,318 @@ mod tests {
 
         check(prop as fn(Samples) -> bool);
     }
+
+    #[test]
+    fn merged_ecdf_agrees_with_ecdf_of_pooled_samples() {
+        fn prop(xs: Samples, ys: Samples, val: u64) -> bool {
+            let mut pooled = xs.vec.clone();
+            pooled.extend(ys.vec.iter().cloned());
+
+            let expected = Ecdf::new(&pooled);
+            let actual = Ecdf::merge(&[&Ecdf::new(&xs.vec), &Ecdf::new(&ys.vec)]);
+
+            actual.value(val) == expected.value(val) &&
+            (1..pooled.len() + 1).all(|r| actual.rank(r) == expected.rank(r)) &&
+            (1..101).all(|p| actual.percentile(p) == expected.percentile(p))
+        }
+
+        check(prop as fn(Samples, Samples, u64) -> bool);
+    }
+
+    #[test]
+    fn merge_of_several_ecdfs_has_sorted_pooled_samples() {
+        fn prop(xs: Samples, ys: Samples, zs: Samples) -> bool {
+            let mut pooled = xs.vec.clone();
+            pooled.extend(ys.vec.iter().cloned());
+            pooled.extend(zs.vec.iter().cloned());
+            pooled.sort();
+
+            let (ecdf_xs, ecdf_ys, ecdf_zs) = (Ecdf::new(&xs.vec), Ecdf::new(&ys.vec),
+                                               Ecdf::new(&zs.vec));
+            let merged = Ecdf::merge(&[&ecdf_xs, &ecdf_ys, &ecdf_zs]);
+
+            merged.samples == pooled && merged.length == pooled.len()
+        }
+
+        check(prop as fn(Samples, Samples, Samples) -> bool);
+    }
+
+    #[test]
+    fn mixture_is_weighted_average_of_components() {
+        fn prop(xs: Samples, ys: Samples, val: u64, w: u8) -> bool {
+            let weight = (w as f64 + 1.0) / 257.0;
+
+            let ecdf_xs = Ecdf::new(&xs.vec);
+            let ecdf_ys = Ecdf::new(&ys.vec);
+            let mixture = Ecdf::mixture(&[(&ecdf_xs, weight), (&ecdf_ys, 1.0 - weight)]);
+
+            let expected = weight * ecdf_xs.value(val) + (1.0 - weight) * ecdf_ys.value(val);
+
+            (mixture.value(val) - expected).abs() < 1e-10
+        }
+
+        check(prop as fn(Samples, Samples, u64, u8) -> bool);
+    }
+
+    #[test]
+    fn mixture_with_length_weights_agrees_with_merge() {
+        fn prop(xs: Samples, ys: Samples) -> bool {
+            let ecdf_xs = Ecdf::new(&xs.vec);
+            let ecdf_ys = Ecdf::new(&ys.vec);
+
+            let merged = Ecdf::merge(&[&ecdf_xs, &ecdf_ys]);
+            let mixture = Ecdf::mixture(&[(&ecdf_xs, xs.vec.len() as f64),
+                                          (&ecdf_ys, ys.vec.len() as f64)]);
+
+            (1..101).all(|p| mixture.percentile(p) == merged.percentile(p)) &&
+            (1..1001).all(|p| mixture.permille(p) == merged.permille(p)) &&
+            mixture.min() == merged.min() && mixture.max() == merged.max()
+        }
+
+        check(prop as fn(Samples, Samples) -> bool);
+    }
+
+    #[test]
+    fn mixture_ignores_zero_weight_components() {
+        fn prop(xs: Samples, ys: Samples, val: u64) -> bool {
+            let ecdf_xs = Ecdf::new(&xs.vec);
+            let ecdf_ys = Ecdf::new(&ys.vec);
+            let mixture = Ecdf::mixture(&[(&ecdf_xs, 2.0), (&ecdf_ys, 0.0)]);
+
+            (mixture.value(val) - ecdf_xs.value(val)).abs() < 1e-10 &&
+            mixture.max() == ecdf_xs.max()
+        }
+
+        check(prop as fn(Samples, Samples, u64) -> bool);
+    }
+
+    #[test]
+    fn merge_of_mixtures_weights_by_sample_length() {
+        fn prop(xs: Samples, ys: Samples, val: u64) -> bool {
+            let ecdf_xs = Ecdf::new(&xs.vec);
+            let ecdf_ys = Ecdf::new(&ys.vec);
+
+            let mixture = Ecdf::mixture(&[(&ecdf_xs, 1.0), (&ecdf_ys, 1.0)]);
+            let merged = Ecdf::merge(&[&mixture, &ecdf_xs]);
+
+            let n = (xs.vec.len() + ys.vec.len()) as f64;
+            let m = xs.vec.len() as f64;
+            let expected = (n * mixture.value(val) + m * ecdf_xs.value(val)) / (n + m);
+
+            (merged.value(val) - expected).abs() < 1e-10
+        }
+
+        check(prop as fn(Samples, Samples, u64) -> bool);
+    }
+
+    #[test]
+    #[should_panic(expected="assertion failed: total > 0.0")]
+    fn mixture_panics_on_zero_total_weight() {
+        let ecdf = Ecdf::new(&[0, 1, 2]);
+        Ecdf::mixture(&[(&ecdf, 0.0)]);
+    }
+
+    #[test]
+    #[should_panic(expected="assertion failed: components.iter().all(|&(_, weight)| weight >= 0.0)")]
+    fn mixture_panics_on_negative_weight() {
+        let ecdf = Ecdf::new(&[0, 1, 2]);
+        Ecdf::mixture(&[(&ecdf, 2.0), (&ecdf, -1.0)]);
+    }
+
+    #[test]
+    #[should_panic(expected="assertion failed: !samples.is_empty()")]
+    fn compressed_ecdf_panics_on_empty_samples_set() {
+        let xs: Vec<u64> = vec![];
+        CompressedEcdf::new(&xs);
+    }
+
+    #[test]
+    fn compressed_ecdf_agrees_with_ecdf() {
+        fn prop(xs: Samples, val: u64) -> bool {
+            // Reduce the sample values to force plenty of ties.
+            let xs: Vec<u64> = xs.vec.iter().map(|x| x % 16).collect();
+            let val = val % 18;
+
+            let ecdf = Ecdf::new(&xs);
+            let compressed = CompressedEcdf::new(&xs);
+
+            compressed.num_samples() == xs.len() && compressed.value(val) == ecdf.value(val) &&
+            compressed.min() == ecdf.min() && compressed.max() == ecdf.max()
+        }
+
+        check(prop as fn(Samples, u64) -> bool);
+    }
+
+    #[test]
+    fn compressed_ecdf_ranks_and_percentiles_agree_with_ecdf() {
+        fn prop(xs: Samples) -> bool {
+            let xs: Vec<u64> = xs.vec.iter().map(|x| x % 16).collect();
+
+            let ecdf = Ecdf::new(&xs);
+            let compressed = CompressedEcdf::new(&xs);
+
+            (1..xs.len() + 1).all(|r| compressed.rank(r) == ecdf.rank(r)) &&
+            (1..101).all(|p| compressed.percentile(p) == ecdf.percentile(p)) &&
+            (1..1001).all(|p| compressed.permille(p) == ecdf.permille(p))
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn compressed_ecdf_stores_distinct_values() {
+        fn prop(xs: Samples) -> bool {
+            let xs: Vec<u64> = xs.vec.iter().map(|x| x % 16).collect();
+
+            let mut distinct = xs.clone();
+            distinct.sort();
+            distinct.dedup();
+
+            CompressedEcdf::new(&xs).num_values() == distinct.len()
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn compressed_ecdf_from_ecdf_agrees_with_new() {
+        fn prop(xs: Samples, val: u64) -> bool {
+            let xs: Vec<u64> = xs.vec.iter().map(|x| x % 16).collect();
+            let val = val % 18;
+
+            let from = CompressedEcdf::from(&Ecdf::new(&xs));
+            let new = CompressedEcdf::new(&xs);
+
+            from.value(val) == new.value(val) && from.num_values() == new.num_values() &&
+            (1..xs.len() + 1).all(|r| from.rank(r) == new.rank(r))
+        }
+
+        check(prop as fn(Samples, u64) -> bool);
+    }
+
+    #[test]
+    fn compressed_ecdf_from_mixture_agrees_with_mixture() {
+        fn prop(xs: Samples, ys: Samples, val: u64) -> bool {
+            let xs: Vec<u64> = xs.vec.iter().map(|x| x % 16).collect();
+            let ys: Vec<u64> = ys.vec.iter().map(|y| y % 16).collect();
+            let val = val % 18;
+
+            let ecdf_xs = Ecdf::new(&xs);
+            let ecdf_ys = Ecdf::new(&ys);
+            let mixture = Ecdf::mixture(&[(&ecdf_xs, 0.3), (&ecdf_ys, 0.7)]);
+            let compressed = CompressedEcdf::from(&mixture);
+
+            compressed.value(val) == mixture.value(val) &&
+            (1..101).all(|p| compressed.percentile(p) == mixture.percentile(p))
+        }
+
+        check(prop as fn(Samples, Samples, u64) -> bool);
+    }
+
+    #[test]
+    fn steps_have_distinct_increasing_values() {
+        fn prop(xs: Samples) -> bool {
+            let steps: Vec<Step<u64>> = Ecdf::new(&xs.vec).steps().collect();
+
+            steps.windows(2).all(|w| w[0].value < w[1].value)
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn steps_counts_sum_to_sample_length() {
+        fn prop(xs: Samples) -> bool {
+            let total: usize = Ecdf::new(&xs.vec).steps().map(|step| step.count).sum();
+
+            total == xs.vec.len()
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn steps_probability_is_ecdf_value_and_count_is_number_of_equal_samples() {
+        fn prop(xs: Samples) -> bool {
+            let ecdf = Ecdf::new(&xs.vec);
+
+            ecdf.steps().all(|step| {
+                let count = xs.vec.iter().filter(|&&x| x == step.value).count();
+                step.probability == ecdf.value(step.value) && step.count == count
+            })
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn last_step_probability_is_one() {
+        fn prop(xs: Samples) -> bool {
+            Ecdf::new(&xs.vec).steps().last().unwrap().probability == 1.0
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn survival_is_one_minus_value() {
+        fn prop(xs: Samples, val: u64) -> bool {
+            let ecdf = Ecdf::new(&xs.vec);
+
+            (ecdf.survival(val) - (1.0 - ecdf.value(val))).abs() < 1e-10
+        }
+
+        check(prop as fn(Samples, u64) -> bool);
+    }
+
+    #[test]
+    fn value_lt_is_num_samples_lt_val_div_length() {
+        fn prop(xs: Samples, val: u64) -> bool {
+            let num_samples = xs.vec.iter().filter(|&&x| x < val).count();
+            let expected = num_samples as f64 / xs.vec.len() as f64;
+
+            Ecdf::new(&xs.vec).value_lt(val) == expected
+        }
+
+        check(prop as fn(Samples, u64) -> bool);
+    }
+
+    #[test]
+    fn value_lt_of_sample_value_is_lt_value() {
+        fn prop(xs: Samples) -> bool {
+            let ecdf = Ecdf::new(&xs.vec);
+
+            xs.vec.iter().all(|&x| ecdf.value_lt(x) < ecdf.value(x))
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn interval_is_num_samples_in_interval_div_length() {
+        fn prop(xs: Samples, a: u64, b: u64) -> bool {
+            let num_samples = xs.vec.iter().filter(|&&x| a < x && x <= b).count();
+            let expected = num_samples as f64 / xs.vec.len() as f64;
+            Ecdf::new(&xs.vec).interval(a, b) == expected
+        }
+
+        check(prop as fn(Samples, u64, u64) -> bool);
+    }
+
+    #[test]
+    #[should_panic(expected="assertion failed: 0.0 < p && p <= 1.0")]
+    fn quantile_panics_on_zero_probability() {
+        Ecdf::new(&[0]).quantile(0.0);
+    }
+
+    #[test]
+    fn quantile_agrees_with_percentile() {
+        fn prop(xs: Samples) -> bool {
+            let ecdf = Ecdf::new(&xs.vec);
+
+            (1..101).all(|p| ecdf.quantile(p as f64 / 100.0) == ecdf.percentile(p))
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
 }
@@ -0,0 +1,635 @@
+//! Fitting distributions to samples.
+//!
+//! Estimates the parameters of candidate families of continuous distributions
+//! by maximum likelihood or the method of moments, and ranks the fitted
+//! distributions by the Kolmogorov-Smirnov distance between their distribution
+//! functions and the sample ECDF.
+//!
+//! The one sample test is too lenient for a distribution whose parameters were
+//! estimated from the same sample, so p-values are instead calibrated by a
+//! parametric bootstrap, refitting the family to samples generated from the
+//! fitted distribution.
+
+use std::fmt;
+use std::str::FromStr;
+
+use rand::Rng;
+
+use distributions::{ContinuousDistribution, Exponential, Gamma, LogNormal, Normal, Pareto,
+                    Uniform, Weibull};
+use ecdf::Ecdf;
+use numeric::OrderableF64;
+use special::{digamma, ln_gamma, trigamma};
+use test::distance_to_distribution;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
+
+/// Family of continuous distributions with parameters to estimate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "kebab-case"))]
+pub enum Family {
+    Normal,
+    LogNormal,
+    Exponential,
+    Gamma,
+    Weibull,
+    Pareto,
+    Uniform,
+}
+
+impl Family {
+    /// All the families, in order of display.
+    pub const ALL: [Family; 7] = [Family::Normal,
+                                  Family::LogNormal,
+                                  Family::Exponential,
+                                  Family::Gamma,
+                                  Family::Weibull,
+                                  Family::Pareto,
+                                  Family::Uniform];
+}
+
+impl fmt::Display for Family {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Family::Normal => "normal".fmt(f),
+            Family::LogNormal => "lognormal".fmt(f),
+            Family::Exponential => "exponential".fmt(f),
+            Family::Gamma => "gamma".fmt(f),
+            Family::Weibull => "weibull".fmt(f),
+            Family::Pareto => "pareto".fmt(f),
+            Family::Uniform => "uniform".fmt(f),
+        }
+    }
+}
+
+impl FromStr for Family {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Family, String> {
+        Family::ALL
+            .iter()
+            .find(|family| family.to_string() == s)
+            .cloned()
+            .ok_or_else(|| {
+                "expected normal, lognormal, exponential, gamma, weibull, pareto or uniform"
+                    .to_string()
+            })
+    }
+}
+
+/// Method of estimating the parameters of a family.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "kebab-case"))]
+pub enum Estimator {
+    /// Maximum likelihood estimation.
+    #[cfg_attr(feature = "serde", serde(rename = "mle"))]
+    MaximumLikelihood,
+    /// Method of moments, matching the mean and variance of the sample.
+    Moments,
+}
+
+impl fmt::Display for Estimator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Estimator::MaximumLikelihood => "mle".fmt(f),
+            Estimator::Moments => "moments".fmt(f),
+        }
+    }
+}
+
+impl FromStr for Estimator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Estimator, String> {
+        match s {
+            "mle" => Ok(Estimator::MaximumLikelihood),
+            "moments" => Ok(Estimator::Moments),
+            _ => Err("expected mle or moments".to_string()),
+        }
+    }
+}
+
+/// Distribution of a family with estimated parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FittedDistribution {
+    Normal(Normal),
+    LogNormal(LogNormal),
+    Exponential(Exponential),
+    Gamma(Gamma),
+    Weibull(Weibull),
+    Pareto(Pareto),
+    Uniform(Uniform),
+}
+
+/// Apply an expression to the distribution of any variant.
+macro_rules! with_distribution {
+    ($fitted:expr, $d:ident => $e:expr) => {
+        match *$fitted {
+            FittedDistribution::Normal(ref $d) => $e,
+            FittedDistribution::LogNormal(ref $d) => $e,
+            FittedDistribution::Exponential(ref $d) => $e,
+            FittedDistribution::Gamma(ref $d) => $e,
+            FittedDistribution::Weibull(ref $d) => $e,
+            FittedDistribution::Pareto(ref $d) => $e,
+            FittedDistribution::Uniform(ref $d) => $e,
+        }
+    }
+}
+
+impl FittedDistribution {
+    /// Return the family of the distribution.
+    pub fn family(&self) -> Family {
+        match *self {
+            FittedDistribution::Normal(_) => Family::Normal,
+            FittedDistribution::LogNormal(_) => Family::LogNormal,
+            FittedDistribution::Exponential(_) => Family::Exponential,
+            FittedDistribution::Gamma(_) => Family::Gamma,
+            FittedDistribution::Weibull(_) => Family::Weibull,
+            FittedDistribution::Pareto(_) => Family::Pareto,
+            FittedDistribution::Uniform(_) => Family::Uniform,
+        }
+    }
+}
+
+impl ContinuousDistribution for FittedDistribution {
+    fn cdf(&self, x: f64) -> f64 {
+        with_distribution!(self, d => d.cdf(x))
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        with_distribution!(self, d => d.pdf(x))
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        with_distribution!(self, d => d.quantile(p))
+    }
+
+    fn sample(&self, rng: &mut dyn Rng) -> f64 {
+        with_distribution!(self, d => d.sample(rng))
+    }
+}
+
+/// Displays as the specification of the distribution, e.g. `gamma:2,1.5`.
+impl fmt::Display for FittedDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        with_distribution!(self, d => d.fmt(f))
+    }
+}
+
+/// Serializes as the specification of the distribution.
+#[cfg(feature = "serde")]
+impl Serialize for FittedDistribution {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Distribution fitted to a sample, with its goodness-of-fit.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Fit {
+    pub family: Family,
+    pub distribution: FittedDistribution,
+    /// Kolmogorov-Smirnov distance between the sample ECDF and the fitted
+    /// distribution function.
+    pub statistic: f64,
+    /// Parametric bootstrap p-value of the statistic, if calculated.
+    pub p_value: Option<f64>,
+}
+
+/// Sample mean, variance, minimum and maximum.
+struct Moments {
+    mean: f64,
+    variance: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Moments {
+    fn new(xs: &[f64]) -> Moments {
+        let n = xs.len() as f64;
+        let mean = xs.iter().sum::<f64>() / n;
+
+        Moments {
+            mean: mean,
+            variance: xs.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n,
+            min: xs.iter().cloned().fold(f64::INFINITY, f64::min),
+            max: xs.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+/// Estimate the parameters of a family of distributions from a sample.
+///
+/// The variance of the method of moments is the sample variance with divisor
+/// n, as for the maximum likelihood estimate of the Normal distribution.
+///
+/// Returns None if the family cannot be fitted to the sample, i.e. if the
+/// sample values are all equal, or if the family only has positive values and
+/// the sample does not.
+///
+/// # Panics
+///
+/// There is an assertion panic if the sample is empty.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::fit::{fit, Estimator, Family};
+///
+/// let xs = vec!(1.0, 2.0, 3.0, 4.0);
+///
+/// let normal = fit(&xs, Family::Normal, Estimator::MaximumLikelihood).unwrap();
+/// assert_eq!(normal.to_string(), "normal:2.5,1.118033988749895");
+///
+/// let uniform = fit(&xs, Family::Uniform, Estimator::MaximumLikelihood).unwrap();
+/// assert_eq!(uniform.to_string(), "uniform:1,4");
+///
+/// let ys = vec!(-1.0, 1.0);
+/// assert!(fit(&ys, Family::Exponential, Estimator::MaximumLikelihood).is_none());
+/// ```
+pub fn fit(xs: &[f64], family: Family, estimator: Estimator) -> Option<FittedDistribution> {
+    assert!(!xs.is_empty());
+
+    let moments = Moments::new(xs);
+    let (mean, variance) = (moments.mean, moments.variance);
+
+    if !(variance > 0.0 && variance.is_finite()) {
+        return None;
+    }
+
+    if family != Family::Normal && family != Family::Uniform && moments.min <= 0.0 {
+        return None;
+    }
+
+    let n = xs.len() as f64;
+    let finite_positive = |x: f64| x > 0.0 && x.is_finite();
+
+    let fitted = match (family, estimator) {
+        (Family::Normal, _) => FittedDistribution::Normal(Normal::new(mean, variance.sqrt())),
+        (Family::Uniform, Estimator::MaximumLikelihood) => {
+            FittedDistribution::Uniform(Uniform::new(moments.min, moments.max))
+        }
+        (Family::Uniform, Estimator::Moments) => {
+            let half_width = (3.0 * variance).sqrt();
+            FittedDistribution::Uniform(Uniform::new(mean - half_width, mean + half_width))
+        }
+        (Family::LogNormal, Estimator::MaximumLikelihood) => {
+            let ln_xs: Vec<f64> = xs.iter().map(|x| x.ln()).collect();
+            let ln_moments = Moments::new(&ln_xs);
+            if ln_moments.variance <= 0.0 {
+                return None;
+            }
+            FittedDistribution::LogNormal(LogNormal::new(ln_moments.mean,
+                                                         ln_moments.variance.sqrt()))
+        }
+        (Family::LogNormal, Estimator::Moments) => {
+            let sigma_squared = (variance / (mean * mean)).ln_1p();
+            FittedDistribution::LogNormal(LogNormal::new(mean.ln() - 0.5 * sigma_squared,
+                                                         sigma_squared.sqrt()))
+        }
+        (Family::Exponential, _) => FittedDistribution::Exponential(Exponential::new(1.0 / mean)),
+        (Family::Gamma, Estimator::MaximumLikelihood) => {
+            let mean_ln = xs.iter().map(|x| x.ln()).sum::<f64>() / n;
+            let shape = gamma_shape(mean.ln() - mean_ln)?;
+            FittedDistribution::Gamma(Gamma::new(shape, mean / shape))
+        }
+        (Family::Gamma, Estimator::Moments) => {
+            FittedDistribution::Gamma(Gamma::new(mean * mean / variance, variance / mean))
+        }
+        (Family::Weibull, Estimator::MaximumLikelihood) => {
+            let (scale, shape) = weibull_mle(xs, moments.max)?;
+            FittedDistribution::Weibull(Weibull::new(scale, shape))
+        }
+        (Family::Weibull, Estimator::Moments) => {
+            // The squared coefficient of variation decreases with the shape.
+            let cv_squared = variance / (mean * mean);
+            let shape = solve_increasing(|k| {
+                cv_squared - (ln_gamma(1.0 + 2.0 / k) - 2.0 * ln_gamma(1.0 + 1.0 / k)).exp_m1()
+            })?;
+            let scale = mean / ln_gamma(1.0 + 1.0 / shape).exp();
+            FittedDistribution::Weibull(Weibull::new(scale, shape))
+        }
+        (Family::Pareto, Estimator::MaximumLikelihood) => {
+            let sum_ln = xs.iter().map(|x| (x / moments.min).ln()).sum::<f64>();
+            FittedDistribution::Pareto(Pareto::new(moments.min, n / sum_ln))
+        }
+        (Family::Pareto, Estimator::Moments) => {
+            let shape = 1.0 + (1.0 + mean * mean / variance).sqrt();
+            FittedDistribution::Pareto(Pareto::new(mean * (shape - 1.0) / shape, shape))
+        }
+    };
+
+    // Guard against parameters overflowing for extreme samples.
+    let check = match fitted {
+        FittedDistribution::Normal(_) | FittedDistribution::Uniform(_) => 1.0,
+        _ => fitted.quantile(0.5),
+    };
+
+    if finite_positive(check) { Some(fitted) } else { None }
+}
+
+/// Estimate the shape of a Gamma distribution by maximum likelihood given s,
+/// the log of the mean less the mean of the logs of the sample, solving
+/// ln(k) - digamma(k) = s by Newton's method.
+fn gamma_shape(s: f64) -> Option<f64> {
+    if s <= 0.0 || !s.is_finite() {
+        return None;
+    }
+
+    // Approximation of Minka, accurate to within 1.5% as a starting point.
+    let mut shape = (3.0 - s + ((s - 3.0) * (s - 3.0) + 24.0 * s).sqrt()) / (12.0 * s);
+
+    for _ in 0..100 {
+        let step = (shape.ln() - digamma(shape) - s) / (1.0 / shape - trigamma(shape));
+        let next = if shape - step > 0.0 { shape - step } else { shape / 2.0 };
+
+        if (next - shape).abs() <= 1e-12 * shape {
+            return Some(next);
+        }
+        shape = next;
+    }
+
+    Some(shape)
+}
+
+/// Estimate the scale and shape of a Weibull distribution by maximum
+/// likelihood, solving the profile likelihood equation for the shape.
+fn weibull_mle(xs: &[f64], max: f64) -> Option<(f64, f64)> {
+    // Scale by the maximum so the powers of the sample cannot overflow.
+    let ys: Vec<f64> = xs.iter().map(|x| x / max).collect();
+    let mean_ln = ys.iter().map(|y| y.ln()).sum::<f64>() / ys.len() as f64;
+
+    let shape = solve_increasing(|k| {
+        let (sum, weighted_ln) = ys.iter().fold((0.0, 0.0), |(sum, weighted_ln), y| {
+            let power = y.powf(k);
+            (sum + power, weighted_ln + power * y.ln())
+        });
+        weighted_ln / sum - 1.0 / k - mean_ln
+    })?;
+
+    let mean_power = ys.iter().map(|y| y.powf(shape)).sum::<f64>() / ys.len() as f64;
+    Some((max * mean_power.powf(1.0 / shape), shape))
+}
+
+/// Find the positive root of an increasing function by bisection, doubling or
+/// halving a bracket starting from one to locate it.
+fn solve_increasing<F: Fn(f64) -> f64>(f: F) -> Option<f64> {
+    let (mut low, mut high) = (1.0, 1.0);
+
+    while f(low) > 0.0 {
+        low /= 2.0;
+        if low < 1e-6 {
+            return None;
+        }
+    }
+    while f(high) < 0.0 {
+        high *= 2.0;
+        if high > 1e6 {
+            return None;
+        }
+    }
+
+    for _ in 0..200 {
+        let mid = low + (high - low) / 2.0;
+        if mid <= low || mid >= high {
+            break;
+        }
+
+        if f(mid) < 0.0 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    Some(low + (high - low) / 2.0)
+}
+
+/// Calculate the Kolmogorov-Smirnov distance between a sample and a
+/// distribution.
+fn statistic<D: ContinuousDistribution>(xs: &[f64], distribution: &D) -> f64 {
+    let xs: Vec<OrderableF64> = xs.iter().map(|&x| OrderableF64::new(x)).collect();
+    distance_to_distribution(&Ecdf::new(&xs), distribution).statistic
+}
+
+/// Calculate the parametric bootstrap p-value of the distance between a sample
+/// of size n and a fitted distribution, i.e. the proportion of samples from the
+/// fitted distribution at least as distant from their own fit.
+///
+/// Bootstrap samples that cannot be fitted are discarded.
+fn bootstrap_p_value(fitted: &FittedDistribution,
+                     estimator: Estimator,
+                     n: usize,
+                     statistic_xs: f64,
+                     bootstraps: usize,
+                     rng: &mut dyn Rng)
+                     -> f64 {
+    let mut count = 0;
+    let mut extreme = 0;
+
+    for _ in 0..bootstraps {
+        let ys: Vec<f64> = (0..n).map(|_| fitted.sample(rng)).collect();
+
+        if let Some(refitted) = fit(&ys, fitted.family(), estimator) {
+            count += 1;
+            if statistic(&ys, &refitted) >= statistic_xs {
+                extreme += 1;
+            }
+        }
+    }
+
+    // Count the sample itself so the p-value is never zero.
+    (extreme + 1) as f64 / (count + 1) as f64
+}
+
+/// Fit families of distributions to a sample and rank the fits in order of
+/// increasing Kolmogorov-Smirnov distance to the sample ECDF, i.e. best first.
+///
+/// Families that cannot be fitted to the sample are left out. If bootstraps
+/// is positive, the p-value of each fit is calculated from that many
+/// parametric bootstrap samples generated with rng.
+///
+/// The sample must have length > 7 elements for the fit to be meaningful.
+///
+/// # Panics
+///
+/// There is an assertion panic if the sample has <= 7 elements.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+/// extern crate rand;
+///
+/// use ks::fit::{rank, Estimator, Family};
+///
+/// // Exponential quantiles.
+/// let xs: Vec<f64> = (0..100).map(|i| -(1.0 - (i as f64 + 0.5) / 100.0).ln()).collect();
+///
+/// let fits = rank(&xs, &Family::ALL, Estimator::MaximumLikelihood, 19, &mut rand::thread_rng());
+///
+/// assert!(fits[0].family == Family::Exponential || fits[0].family == Family::Gamma);
+/// assert!(fits.iter().all(|fit| fit.p_value.is_some()));
+/// ```
+pub fn rank(xs: &[f64],
+            families: &[Family],
+            estimator: Estimator,
+            bootstraps: usize,
+            rng: &mut dyn Rng)
+            -> Vec<Fit> {
+    // Only supports samples of size > 7.
+    assert!(xs.len() > 7);
+
+    let mut fits: Vec<Fit> = families.iter()
+        .filter_map(|&family| fit(xs, family, estimator))
+        .map(|distribution| {
+            Fit {
+                family: distribution.family(),
+                distribution: distribution,
+                statistic: statistic(xs, &distribution),
+                p_value: None,
+            }
+        })
+        .collect();
+
+    if bootstraps > 0 {
+        for fit in fits.iter_mut() {
+            fit.p_value = Some(bootstrap_p_value(&fit.distribution,
+                                                 estimator,
+                                                 xs.len(),
+                                                 fit.statistic,
+                                                 bootstraps,
+                                                 rng));
+        }
+    }
+
+    fits.sort_by(|a, b| a.statistic.partial_cmp(&b.statistic).unwrap());
+    fits
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use self::rand::{SeedableRng, StdRng};
+
+    use super::{fit, rank, Estimator, Family, FittedDistribution};
+    use distributions::{ContinuousDistribution, Gamma, LogNormal, Normal, Pareto, Weibull};
+
+    /// Generate a sample from a distribution with a fixed seed.
+    fn sample<D: ContinuousDistribution>(distribution: &D, n: usize) -> Vec<f64> {
+        let mut rng = StdRng::from_seed(&[42]);
+        (0..n).map(|_| distribution.sample(&mut rng)).collect()
+    }
+
+    /// Maximum difference of the quantiles of two distributions at the
+    /// deciles, relative to the interdecile range of the first.
+    fn quantile_difference<D: ContinuousDistribution + ?Sized>(expected: &D,
+                                                      actual: &FittedDistribution)
+                                                      -> f64 {
+        let range = expected.quantile(0.9) - expected.quantile(0.1);
+
+        (1..10)
+            .map(|i| (expected.quantile(i as f64 / 10.0) - actual.quantile(i as f64 / 10.0)).abs())
+            .fold(0.0, f64::max) / range
+    }
+
+    #[test]
+    fn estimators_recover_distribution_of_large_sample() {
+        let n = 20000;
+        let distributions: Vec<(Family, Box<dyn ContinuousDistribution>)> =
+            vec![(Family::Normal, Box::new(Normal::new(5.0, 2.0))),
+                 (Family::LogNormal, Box::new(LogNormal::new(1.0, 0.5))),
+                 (Family::Gamma, Box::new(Gamma::new(0.5, 3.0))),
+                 (Family::Gamma, Box::new(Gamma::new(7.0, 0.5))),
+                 (Family::Weibull, Box::new(Weibull::new(2.0, 0.8))),
+                 (Family::Weibull, Box::new(Weibull::new(1.0, 3.5))),
+                 (Family::Pareto, Box::new(Pareto::new(1.0, 4.0)))];
+
+        for &(family, ref distribution) in distributions.iter() {
+            let mut rng = StdRng::from_seed(&[42]);
+            let xs: Vec<f64> = (0..n).map(|_| distribution.sample(&mut rng)).collect();
+
+            for &estimator in [Estimator::MaximumLikelihood, Estimator::Moments].iter() {
+                let fitted = fit(&xs, family, estimator).unwrap();
+
+                assert_eq!(fitted.family(), family);
+                assert!(quantile_difference(&**distribution, &fitted) < 0.05,
+                        "{} {} {}",
+                        family,
+                        estimator,
+                        fitted);
+            }
+        }
+    }
+
+    #[test]
+    fn moments_estimates_match_sample_mean_and_variance() {
+        let xs = sample(&Gamma::new(2.0, 1.0), 1000);
+
+        let n = xs.len() as f64;
+        let mean = xs.iter().sum::<f64>() / n;
+        let variance = xs.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n;
+
+        for &family in [Family::Gamma, Family::LogNormal, Family::Uniform].iter() {
+            let fitted = fit(&xs, family, Estimator::Moments).unwrap();
+            let ys = sample(&fitted, 200000);
+
+            let fitted_mean = ys.iter().sum::<f64>() / ys.len() as f64;
+            assert!((fitted_mean - mean).abs() < 0.01 * mean, "{}", family);
+
+            let fitted_variance = ys.iter()
+                .map(|y| (y - fitted_mean) * (y - fitted_mean))
+                .sum::<f64>() / ys.len() as f64;
+            assert!((fitted_variance - variance).abs() < 0.03 * variance, "{}", family);
+        }
+    }
+
+    #[test]
+    fn fit_rejects_samples_outside_family_support() {
+        let xs = vec![-1.0, 0.5, 2.0, 3.0];
+        let constant = vec![2.0; 10];
+
+        for &family in Family::ALL.iter() {
+            for &estimator in [Estimator::MaximumLikelihood, Estimator::Moments].iter() {
+                let positive = family != Family::Normal && family != Family::Uniform;
+                assert_eq!(fit(&xs, family, estimator).is_none(), positive);
+                assert!(fit(&constant, family, estimator).is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn rank_orders_fits_by_statistic_with_true_family_first() {
+        let xs = sample(&LogNormal::new(0.0, 1.0), 2000);
+        let mut rng = StdRng::from_seed(&[7]);
+
+        let fits = rank(&xs, &Family::ALL, Estimator::MaximumLikelihood, 19, &mut rng);
+
+        assert_eq!(fits.len(), Family::ALL.len());
+        assert_eq!(fits[0].family, Family::LogNormal);
+        assert!(fits.windows(2).all(|w| w[0].statistic <= w[1].statistic));
+
+        // The bootstrap finds the log-normal plausible and the Normal not.
+        let p_value = |family| fits.iter().find(|fit| fit.family == family).unwrap().p_value.unwrap();
+        assert!(p_value(Family::LogNormal) > 0.05);
+        assert_eq!(p_value(Family::Normal), 0.05);
+    }
+
+    #[test]
+    fn family_and_estimator_round_trip_through_strings() {
+        for &family in Family::ALL.iter() {
+            assert_eq!(family.to_string().parse::<Family>(), Ok(family));
+        }
+        for &estimator in [Estimator::MaximumLikelihood, Estimator::Moments].iter() {
+            assert_eq!(estimator.to_string().parse::<Estimator>(), Ok(estimator));
+        }
+
+        assert!("cauchy".parse::<Family>().is_err());
+        assert!("bayes".parse::<Estimator>().is_err());
+    }
+}
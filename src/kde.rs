@@ -0,0 +1,382 @@
+//! Kernel density and smoothed cumulative distribution function estimation.
+
+use ecdf::Ecdf;
+use numeric::Numeric;
+use special::{normal_cdf, normal_pdf};
+use summary::Summary;
+
+/// Smoothing kernel for density estimation.
+///
+/// Kernels are scaled to have standard deviation equal to the bandwidth so
+/// that bandwidths are comparable between kernels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kernel {
+    Gaussian,
+    Epanechnikov,
+}
+
+/// Half width of the support of the unit variance Epanechnikov kernel.
+const EPANECHNIKOV_SUPPORT: f64 = 2.236_067_977_499_79;
+
+impl Kernel {
+    /// Probability density of the unit variance kernel.
+    fn density(&self, u: f64) -> f64 {
+        match *self {
+            Kernel::Gaussian => normal_pdf(u),
+            Kernel::Epanechnikov => {
+                let u = u / EPANECHNIKOV_SUPPORT;
+                if u.abs() < 1.0 {
+                    0.75 * (1.0 - u * u) / EPANECHNIKOV_SUPPORT
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    /// Cumulative distribution function of the unit variance kernel.
+    fn cdf(&self, u: f64) -> f64 {
+        match *self {
+            Kernel::Gaussian => normal_cdf(u),
+            Kernel::Epanechnikov => {
+                let u = u / EPANECHNIKOV_SUPPORT;
+                if u <= -1.0 {
+                    0.0
+                } else if u >= 1.0 {
+                    1.0
+                } else {
+                    (2.0 + 3.0 * u - u * u * u) / 4.0
+                }
+            }
+        }
+    }
+}
+
+/// Kernel bandwidth selection method.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bandwidth {
+    /// Silverman's rule of thumb, 0.9 min(sd, iqr / 1.34) n^(-1/5).
+    Silverman,
+    /// Scott's rule of thumb, 1.06 sd n^(-1/5).
+    Scott,
+    /// Specified bandwidth, which must be positive.
+    Fixed(f64),
+}
+
+impl Bandwidth {
+    /// Select a bandwidth for a sample from its descriptive statistics.
+    fn select(&self, summary: &Summary) -> f64 {
+        let sd = summary.variance.sqrt();
+        let factor = (summary.count as f64).powf(-0.2);
+
+        let bandwidth = match *self {
+            Bandwidth::Fixed(bandwidth) => {
+                assert!(bandwidth > 0.0);
+                return bandwidth;
+            }
+            Bandwidth::Silverman => {
+                let iqr = summary.iqr / 1.34;
+                let spread = if sd > 0.0 && iqr > 0.0 {
+                    sd.min(iqr)
+                } else {
+                    sd.max(iqr)
+                };
+                0.9 * spread * factor
+            }
+            Bandwidth::Scott => 1.06 * sd * factor,
+        };
+
+        if bandwidth > 0.0 {
+            bandwidth
+        } else if summary.mean != 0.0 {
+            // Degenerate sample with no spread. Fall back to a bandwidth
+            // proportional to the sample value.
+            0.1 * summary.mean.abs()
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Point in a grid evaluation of a kernel density estimate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridPoint {
+    pub x: f64,
+    pub density: f64,
+    pub cdf: f64,
+}
+
+/// Kernel density estimate of a sample.
+///
+/// The estimate also provides a smoothed cumulative distribution function,
+/// the integral of the density estimate, as an alternative to the step
+/// function of an ECDF.
+pub struct Kde {
+    // Distinct sample values with their probability mass in the sample.
+    masses: Vec<(f64, f64)>,
+    kernel: Kernel,
+    bandwidth: f64,
+}
+
+impl Kde {
+    /// Construct a kernel density estimate for the sample of an ECDF.
+    ///
+    /// # Panics
+    ///
+    /// Fixed bandwidths must be positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// use ks::kde::{Bandwidth, Kde, Kernel};
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    /// let ecdf = ks::Ecdf::new(&samples);
+    /// let kde = Kde::new(&ecdf, Kernel::Gaussian, Bandwidth::Silverman);
+    ///
+    /// assert!(kde.density(4.5) > kde.density(9.0));
+    /// assert!((kde.cdf(4.5) - 0.5).abs() < 1e-10);
+    /// ```
+    pub fn new<T: Numeric>(ecdf: &Ecdf<T>, kernel: Kernel, bandwidth: Bandwidth) -> Kde {
+        let mut previous = 0.0;
+        let mut masses = Vec::new();
+
+        for step in ecdf.steps() {
+            masses.push((step.value.to_f64(), step.probability - previous));
+            previous = step.probability;
+        }
+
+        Kde {
+            masses: masses,
+            kernel: kernel,
+            bandwidth: bandwidth.select(&Summary::new(ecdf)),
+        }
+    }
+
+    /// Return the kernel bandwidth used in the estimate.
+    pub fn bandwidth(&self) -> f64 {
+        self.bandwidth
+    }
+
+    /// Calculate the density estimate at x.
+    pub fn density(&self, x: f64) -> f64 {
+        let h = self.bandwidth;
+
+        self.masses
+            .iter()
+            .map(|&(sample, mass)| mass * self.kernel.density((x - sample) / h))
+            .sum::<f64>() / h
+    }
+
+    /// Calculate the smoothed cumulative distribution function at x.
+    pub fn cdf(&self, x: f64) -> f64 {
+        let h = self.bandwidth;
+
+        let cdf: f64 = self.masses
+                           .iter()
+                           .map(|&(sample, mass)| mass * self.kernel.cdf((x - sample) / h))
+                           .sum();
+
+        cdf.clamp(0.0, 1.0)
+    }
+
+    /// Evaluate the density estimate and smoothed cumulative distribution
+    /// function at equally spaced points between low and high inclusive.
+    ///
+    /// # Panics
+    ///
+    /// There must be at least two grid points and low must be less than high.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// use ks::kde::{Bandwidth, Kde, Kernel};
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    /// let ecdf = ks::Ecdf::new(&samples);
+    /// let kde = Kde::new(&ecdf, Kernel::Epanechnikov, Bandwidth::Scott);
+    ///
+    /// for point in kde.grid(-5.0, 15.0, 201) {
+    ///     println!("{}\t{}\t{}", point.x, point.density, point.cdf);
+    /// }
+    /// ```
+    pub fn grid(&self, low: f64, high: f64, num_points: usize) -> Vec<GridPoint> {
+        assert!(num_points > 1);
+        assert!(low < high);
+
+        let step = (high - low) / (num_points - 1) as f64;
+
+        (0..num_points)
+            .map(|i| {
+                let x = low + i as f64 * step;
+                GridPoint {
+                    x: x,
+                    density: self.density(x),
+                    cdf: self.cdf(x),
+                }
+            })
+            .collect()
+    }
+
+    /// Evaluate the estimate on a grid extending three bandwidths either side
+    /// of the sample range, which covers all but a negligible fraction of the
+    /// estimated probability mass.
+    pub fn default_grid(&self, num_points: usize) -> Vec<GridPoint> {
+        let min = self.masses[0].0;
+        let max = self.masses[self.masses.len() - 1].0;
+        let margin = 3.0 * self.bandwidth;
+
+        self.grid(min - margin, max + margin, num_points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate quickcheck;
+    extern crate rand;
+
+    use self::quickcheck::{Arbitrary, Gen, QuickCheck, Testable, StdGen};
+    use std::cmp;
+    use super::{Bandwidth, Kde, Kernel};
+    use ecdf::Ecdf;
+
+    fn check<A: Testable>(f: A) {
+        let g = StdGen::new(rand::thread_rng(), usize::MAX);
+        QuickCheck::new().gen(g).quickcheck(f);
+    }
+
+    /// Wrapper for generating sample data with QuickCheck.
+    ///
+    /// Samples must be non-empty sequences of i64 values. Values are bounded
+    /// so density grids remain a manageable size.
+    #[derive(Debug, Clone)]
+    struct Samples {
+        vec: Vec<i64>,
+    }
+
+    impl Arbitrary for Samples {
+        fn arbitrary<G: Gen>(g: &mut G) -> Samples {
+            // Limit size of generated sample set to 256
+            let max = cmp::min(g.size(), 256);
+
+            let size = g.gen_range(1, max);
+            let vec = (0..size).map(|_| g.gen_range(-100, 100)).collect();
+
+            Samples { vec: vec }
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Samples>> {
+            let vec: Vec<i64> = self.vec.clone();
+            let shrunk: Box<dyn Iterator<Item = Vec<i64>>> = vec.shrink();
+
+            Box::new(shrunk.filter(|v| !v.is_empty()).map(|v| Samples { vec: v }))
+        }
+    }
+
+    fn kernels() -> Vec<Kernel> {
+        vec![Kernel::Gaussian, Kernel::Epanechnikov]
+    }
+
+    #[test]
+    #[should_panic(expected="assertion failed: bandwidth > 0.0")]
+    fn kde_panics_on_non_positive_fixed_bandwidth() {
+        let ecdf = Ecdf::new(&[0, 1, 2]);
+        Kde::new(&ecdf, Kernel::Gaussian, Bandwidth::Fixed(0.0));
+    }
+
+    #[test]
+    fn bandwidth_is_positive() {
+        fn prop(xs: Samples) -> bool {
+            let ecdf = Ecdf::new(&xs.vec);
+
+            Kde::new(&ecdf, Kernel::Gaussian, Bandwidth::Silverman).bandwidth() > 0.0 &&
+            Kde::new(&ecdf, Kernel::Gaussian, Bandwidth::Scott).bandwidth() > 0.0
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn density_integrates_to_one() {
+        fn prop(xs: Samples) -> bool {
+            let ecdf = Ecdf::new(&xs.vec);
+
+            kernels().into_iter().all(|kernel| {
+                let kde = Kde::new(&ecdf, kernel, Bandwidth::Fixed(2.0));
+                let grid = kde.grid(-120.0, 120.0, 1201);
+
+                // Trapezoidal rule.
+                let integral: f64 = grid.windows(2)
+                                        .map(|w| (w[1].x - w[0].x) * (w[0].density + w[1].density) / 2.0)
+                                        .sum();
+
+                (integral - 1.0).abs() < 1e-3
+            })
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn cdf_is_increasing_between_zero_and_one() {
+        fn prop(xs: Samples) -> bool {
+            let ecdf = Ecdf::new(&xs.vec);
+
+            kernels().into_iter().all(|kernel| {
+                let kde = Kde::new(&ecdf, kernel, Bandwidth::Silverman);
+                let grid = kde.default_grid(101);
+
+                grid.windows(2).all(|w| w[0].cdf <= w[1].cdf) && grid[0].cdf < 0.01 &&
+                grid[grid.len() - 1].cdf > 0.99
+            })
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn cdf_approaches_ecdf_for_small_bandwidth() {
+        fn prop(xs: Samples, val: u64) -> bool {
+            let ecdf = Ecdf::new(&xs.vec);
+            let x = (val % 240) as f64 - 119.5;
+
+            kernels().into_iter().all(|kernel| {
+                let kde = Kde::new(&ecdf, kernel, Bandwidth::Fixed(1e-3));
+                (kde.cdf(x) - ecdf.value(x.floor() as i64)).abs() < 1e-10
+            })
+        }
+
+        check(prop as fn(Samples, u64) -> bool);
+    }
+
+    #[test]
+    fn density_is_zero_beyond_epanechnikov_support() {
+        fn prop(xs: Samples) -> bool {
+            let ecdf = Ecdf::new(&xs.vec);
+            let kde = Kde::new(&ecdf, Kernel::Epanechnikov, Bandwidth::Fixed(1.0));
+
+            kde.density(103.0) == 0.0 && kde.density(-103.0) == 0.0
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn cdf_of_nan_and_distant_points() {
+        let ecdf = Ecdf::new(&[1, 2, 3, 4, 5, 6, 7, 8]);
+
+        for kernel in kernels() {
+            let kde = Kde::new(&ecdf, kernel, Bandwidth::Silverman);
+
+            assert!(kde.cdf(f64::NAN).is_nan());
+            assert_eq!(kde.cdf(1e200), 1.0);
+            assert_eq!(kde.cdf(-1e200), 0.0);
+            assert_eq!(kde.cdf(f64::INFINITY), 1.0);
+            assert_eq!(kde.cdf(f64::NEG_INFINITY), 0.0);
+        }
+    }
+}
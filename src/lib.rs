@@ -1,5 +1,32 @@
+// Struct fields are initialised as `field: field` throughout the crate.
+#![allow(clippy::redundant_field_names)]
+
+extern crate rand;
+#[cfg(feature = "serde")]
+extern crate serde;
+
+pub mod bootstrap;
+pub mod change_point;
+pub mod correction;
+pub mod distributions;
 pub mod ecdf;
+pub mod fit;
+pub mod kde;
+pub mod mann_whitney;
+pub mod mutable_ecdf;
+pub mod numeric;
+pub mod pairwise;
+#[cfg(feature = "plot")]
+pub mod plot;
+pub mod power_law;
+pub mod qq;
+mod special;
+pub mod summary;
 pub mod test;
 
-pub use test::{test, test_f64, calculate_critical_value};
-pub use ecdf::{Ecdf, ecdf, percentile, permille, rank};
+pub use test::{test, test_f64, test_with, calculate_critical_value, Alternative, KsTest, Method,
+               OneSampleTest, TestOutcome, TestResult, TwoSampleTest};
+pub use ecdf::{Ecdf, CompressedEcdf, Step, Steps, ecdf, percentile, permille, rank};
+pub use mutable_ecdf::MutableEcdf;
+pub use numeric::{Numeric, OrderableF64};
+pub use summary::Summary;
@@ -0,0 +1,471 @@
+//! Mann-Whitney U test, also known as the Wilcoxon rank-sum test.
+//!
+//! A companion to the two sample Kolmogorov-Smirnov test that is sensitive to
+//! one sample tending to be larger than the other, e.g. a shift in location,
+//! rather than to any difference in distribution. The ranks of the samples
+//! are calculated in a single sweep over the merged ECDFs of the samples, as
+//! for the Kolmogorov-Smirnov statistic.
+
+use ecdf::Ecdf;
+use special::normal_cdf;
+use test::{merged_steps, Alternative, Method, TestOutcome, TwoSampleTest};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Mann-Whitney U test result.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct MannWhitneyResult {
+    pub is_rejected: bool,
+    /// U statistic of xs, the number of pairs of a value of xs and a value of
+    /// ys where the value of xs is larger, counting ties as one half.
+    pub statistic: f64,
+    /// Standardised statistic of the normal approximation, with the tie and
+    /// continuity corrections.
+    pub z: f64,
+    /// Probability of a test statistic at least as extreme under the null
+    /// hypothesis.
+    pub p_value: f64,
+    /// Common-language effect size, the probability that a random value of xs
+    /// is larger than a random value of ys, counting ties as one half, i.e. U
+    /// divided by the product of the sample sizes.
+    pub effect_size: f64,
+    /// Method used to calculate the p-value.
+    pub method: Method,
+    pub confidence: f64,
+}
+
+impl TestOutcome for MannWhitneyResult {
+    fn statistic(&self) -> f64 {
+        self.statistic
+    }
+
+    fn p_value(&self) -> f64 {
+        self.p_value
+    }
+
+    fn is_rejected(&self) -> bool {
+        self.is_rejected
+    }
+}
+
+/// Samples with fewer values than this each and no ties use the exact
+/// distribution of U by default, as for R's `wilcox.test`.
+const EXACT_LIMIT: usize = 50;
+
+/// Mann-Whitney U test configured by chaining options, e.g.
+/// `MannWhitneyTest::new().confidence(0.99).run(&xs, &ys)`.
+///
+/// The options default to a confidence level of 0.95 and the two-sided
+/// alternative. The p-value is calculated from the exact distribution of U if
+/// the samples have no ties and fewer than 50 values each, and otherwise from
+/// the normal approximation with tie and continuity corrections, unless the
+/// method is set.
+///
+/// The alternatives refer to the distribution functions of the samples as for
+/// the Kolmogorov-Smirnov test, e.g. `Alternative::Less` is that the
+/// distribution function of xs lies below that of ys, i.e. xs tends to be
+/// larger and U is large.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::mann_whitney::MannWhitneyTest;
+/// use ks::test::{Alternative, Method};
+///
+/// let xs = vec!(80, 83, 189, 104, 145, 138, 191, 164, 73, 146);
+/// let ys = vec!(115, 88, 90, 74, 121);
+///
+/// let result = MannWhitneyTest::new().alternative(Alternative::Less).run(&xs, &ys);
+///
+/// assert_eq!(result.statistic, 35.0);
+/// assert_eq!(result.effect_size, 0.7);
+/// assert_eq!(result.method, Method::Exact);
+/// assert!((result.p_value - 0.1272).abs() < 1e-4);
+/// assert!(!result.is_rejected);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MannWhitneyTest {
+    confidence: f64,
+    alternative: Alternative,
+    method: Option<Method>,
+}
+
+impl MannWhitneyTest {
+    /// Configure a test with the default options.
+    pub fn new() -> MannWhitneyTest {
+        MannWhitneyTest {
+            confidence: 0.95,
+            alternative: Alternative::TwoSided,
+            method: None,
+        }
+    }
+
+    /// Set the confidence level at which the test rejects.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic if the confidence level is not strictly
+    /// between zero and one.
+    pub fn confidence(mut self, confidence: f64) -> MannWhitneyTest {
+        assert!(0.0 < confidence && confidence < 1.0);
+        self.confidence = confidence;
+        self
+    }
+
+    /// Set the alternative hypothesis.
+    pub fn alternative(mut self, alternative: Alternative) -> MannWhitneyTest {
+        self.alternative = alternative;
+        self
+    }
+
+    /// Set the method of calculating the p-value.
+    ///
+    /// The exact distribution assumes the samples have no ties, so the normal
+    /// approximation is used for samples with ties regardless. It takes time
+    /// proportional to the square of the product of the sample sizes so is
+    /// intended for smaller samples.
+    pub fn method(mut self, method: Method) -> MannWhitneyTest {
+        self.method = Some(method);
+        self
+    }
+
+    /// Perform the test on given samples.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic if either sample is empty.
+    pub fn run<T: Ord + Clone>(&self, xs: &[T], ys: &[T]) -> MannWhitneyResult {
+        assert!(!xs.is_empty() && !ys.is_empty());
+
+        let (n1, n2) = (xs.len(), ys.len());
+        let (m, n) = (n1 as f64, n2 as f64);
+        let total = m + n;
+
+        let ecdf_xs = Ecdf::new(xs);
+        let ecdf_ys = Ecdf::new(ys);
+
+        // Sum the ranks of xs, giving tied values the mean of their ranks, and
+        // the tie correction sum of t^3 - t over groups of t tied values.
+        let mut rank_sum = 0.0;
+        let mut tie_sum = 0.0;
+        let mut below = 0.0;
+
+        for step in merged_steps(&ecdf_xs, &ecdf_ys) {
+            let ties = (step.count_xs + step.count_ys) as f64;
+            let mid_rank = below + (ties + 1.0) / 2.0;
+
+            rank_sum += step.count_xs as f64 * mid_rank;
+            tie_sum += ties * ties * ties - ties;
+            below += ties;
+        }
+
+        let statistic = rank_sum - m * (m + 1.0) / 2.0;
+
+        let mean = m * n / 2.0;
+        let variance = m * n / 12.0 * (total + 1.0 - tie_sum / (total * (total - 1.0)));
+        let z = normal_z(statistic - mean, variance.sqrt(), self.alternative);
+
+        let has_ties = tie_sum > 0.0;
+        let method = match self.method {
+            _ if has_ties => Method::Asymptotic,
+            Some(method) => method,
+            None if n1 < EXACT_LIMIT && n2 < EXACT_LIMIT => Method::Exact,
+            None => Method::Asymptotic,
+        };
+
+        let p_value = match method {
+            Method::Exact => exact_p_value(statistic as usize, n1, n2, self.alternative),
+            Method::Asymptotic => normal_p_value(z, self.alternative),
+        };
+
+        MannWhitneyResult {
+            is_rejected: p_value < 1.0 - self.confidence,
+            statistic: statistic,
+            z: z,
+            p_value: p_value,
+            effect_size: statistic / (m * n),
+            method: method,
+            confidence: self.confidence,
+        }
+    }
+}
+
+impl Default for MannWhitneyTest {
+    fn default() -> MannWhitneyTest {
+        MannWhitneyTest::new()
+    }
+}
+
+impl TwoSampleTest for MannWhitneyTest {
+    type Outcome = MannWhitneyResult;
+
+    fn name(&self) -> &'static str {
+        "mann-whitney"
+    }
+
+    fn test<T: Ord + Clone>(&self, xs: &[T], ys: &[T]) -> MannWhitneyResult {
+        self.run(xs, ys)
+    }
+}
+
+/// Perform a two-sided Mann-Whitney U test on given samples with the default
+/// method.
+///
+/// # Panics
+///
+/// There are assertion panics if either sample is empty or if the confidence
+/// level is not strictly between zero and one.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// let xs: Vec<u32> = (0..60).collect();
+/// let ys: Vec<u32> = (20..80).collect();
+///
+/// let result = ks::mann_whitney::test(&xs, &ys, 0.95);
+///
+/// assert!(result.is_rejected);
+/// assert!(result.effect_size < 0.5);
+/// ```
+pub fn test<T: Ord + Clone>(xs: &[T], ys: &[T], confidence: f64) -> MannWhitneyResult {
+    MannWhitneyTest::new().confidence(confidence).run(xs, ys)
+}
+
+/// Standardise the difference of U from its mean, moving it half a unit
+/// towards zero as a continuity correction in the direction of the
+/// alternative.
+fn normal_z(difference: f64, std_dev: f64, alternative: Alternative) -> f64 {
+    if std_dev == 0.0 {
+        // Every value is tied so U is constant.
+        return 0.0;
+    }
+
+    let correction = match alternative {
+        Alternative::TwoSided if difference == 0.0 => 0.0,
+        Alternative::TwoSided => 0.5 * difference.signum(),
+        Alternative::Less => 0.5,
+        Alternative::Greater => -0.5,
+    };
+
+    (difference - correction) / std_dev
+}
+
+/// Calculate the p-value of the standardised statistic from the standard
+/// normal distribution.
+fn normal_p_value(z: f64, alternative: Alternative) -> f64 {
+    match alternative {
+        Alternative::TwoSided => (2.0 * normal_cdf(-z.abs())).min(1.0),
+        Alternative::Less => normal_cdf(-z),
+        Alternative::Greater => normal_cdf(z),
+    }
+}
+
+/// Calculate the p-value of U from its exact distribution for samples of
+/// sizes n1 and n2 without ties.
+fn exact_p_value(statistic: usize, n1: usize, n2: usize, alternative: Alternative) -> f64 {
+    let probabilities = exact_distribution(n1, n2);
+
+    let at_most: f64 = probabilities[..statistic + 1].iter().sum();
+    let at_least: f64 = probabilities[statistic..].iter().sum();
+
+    let p_value = match alternative {
+        Alternative::TwoSided => 2.0 * at_most.min(at_least),
+        Alternative::Less => at_least,
+        Alternative::Greater => at_most,
+    };
+
+    p_value.clamp(0.0, 1.0)
+}
+
+/// Calculate the exact distribution of U for samples of sizes n1 and n2
+/// without ties, returning the probability of each value from zero to n1 n2.
+///
+/// Under the null hypothesis every ordering of the merged samples is equally
+/// likely, and the largest value is from xs with probability n1 / (n1 + n2),
+/// when it is larger than every value of ys. So the probability P(u; i, j)
+/// of U = u for samples of sizes i and j satisfies
+///
+/// P(u; i, j) = i / (i + j) P(u - j; i - 1, j) + j / (i + j) P(u; i, j - 1),
+///
+/// which is accumulated row by row in i.
+fn exact_distribution(n1: usize, n2: usize) -> Vec<f64> {
+    // Row i = 0, where U is always zero.
+    let mut rows: Vec<Vec<f64>> = (0..n2 + 1).map(|_| vec![1.0]).collect();
+
+    for i in 1..n1 + 1 {
+        let mut next: Vec<Vec<f64>> = Vec::with_capacity(n2 + 1);
+        next.push(vec![1.0]);
+
+        for j in 1..n2 + 1 {
+            let (x_largest, y_largest) = (i as f64 / (i + j) as f64, j as f64 / (i + j) as f64);
+
+            let probabilities = (0..i * j + 1)
+                .map(|u| {
+                    let from_x = if u >= j { rows[j].get(u - j).map_or(0.0, |&p| p) } else { 0.0 };
+                    let from_y = next[j - 1].get(u).map_or(0.0, |&p| p);
+
+                    x_largest * from_x + y_largest * from_y
+                })
+                .collect();
+            next.push(probabilities);
+        }
+
+        rows = next;
+    }
+
+    rows.pop().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate quickcheck;
+
+    use self::quickcheck::{quickcheck, TestResult};
+
+    use super::{exact_distribution, test, MannWhitneyTest};
+    use correction::Correction;
+    use pairwise::pairwise_with;
+    use test::{Alternative, Method};
+
+    const EPSILON: f64 = 1e-12;
+
+    /// Calculate the exact distribution of U by enumerating the subsets of
+    /// ranks of xs, as bit masks of the ranks of the merged samples.
+    fn exact_distribution_alt(n1: usize, n2: usize) -> Vec<f64> {
+        let mut counts = vec![0.0; n1 * n2 + 1];
+        let mut total = 0.0;
+
+        for mask in 0u32..(1 << (n1 + n2)) {
+            if mask.count_ones() as usize != n1 {
+                continue;
+            }
+
+            // Each value of xs is larger than the values of ys below it.
+            let mut u = 0;
+            let mut ys_below = 0;
+            for rank in 0..n1 + n2 {
+                if mask & (1 << rank) != 0 {
+                    u += ys_below;
+                } else {
+                    ys_below += 1;
+                }
+            }
+
+            counts[u] += 1.0;
+            total += 1.0;
+        }
+
+        counts.iter().map(|count| count / total).collect()
+    }
+
+    #[test]
+    fn exact_distribution_matches_enumeration() {
+        for n1 in 1..7 {
+            for n2 in 1..7 {
+                let actual = exact_distribution(n1, n2);
+                let expected = exact_distribution_alt(n1, n2);
+
+                assert_eq!(actual.len(), n1 * n2 + 1);
+                for (a, e) in actual.iter().zip(expected.iter()) {
+                    assert!((a - e).abs() < EPSILON, "{} {} {:?} {:?}", n1, n2, actual, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn statistic_and_effect_size_count_pairs() {
+        fn prop(xs: Vec<u8>, ys: Vec<u8>) -> TestResult {
+            if xs.is_empty() || ys.is_empty() {
+                return TestResult::discard();
+            }
+
+            let pairs: f64 = xs.iter()
+                .flat_map(|x| ys.iter().map(move |y| (x, y)))
+                .map(|(x, y)| if x > y { 1.0 } else if x == y { 0.5 } else { 0.0 })
+                .sum();
+            let product = (xs.len() * ys.len()) as f64;
+
+            let result = test(&xs, &ys, 0.95);
+            let reversed = test(&ys, &xs, 0.95);
+
+            TestResult::from_bool((result.statistic - pairs).abs() < 1e-9 &&
+                                  (result.effect_size - pairs / product).abs() < 1e-9 &&
+                                  (result.statistic + reversed.statistic - product).abs() < 1e-9 &&
+                                  (result.p_value - reversed.p_value).abs() < 1e-9)
+        }
+
+        quickcheck(prop as fn(Vec<u8>, Vec<u8>) -> TestResult);
+    }
+
+    #[test]
+    fn normal_approximation_has_continuity_and_tie_corrections() {
+        let xs = vec![80, 83, 189, 104, 145, 138, 191, 164, 73, 146];
+        let ys = vec![115, 88, 90, 74, 121];
+
+        // As R's wilcox.test(x, y, alternative = "greater", exact = FALSE).
+        let result = MannWhitneyTest::new()
+            .alternative(Alternative::Less)
+            .method(Method::Asymptotic)
+            .run(&xs, &ys);
+        assert_eq!(result.method, Method::Asymptotic);
+        assert!((result.z - 9.5 / (50.0 * 16.0 / 12.0f64).sqrt()).abs() < EPSILON);
+        assert!((result.p_value - 0.1223).abs() < 1e-4, "{:?}", result);
+
+        // Samples with ties use the normal approximation, with the variance
+        // reduced by the sum of t^3 - t over the groups of t tied values.
+        let xs = vec![1, 1, 2, 2, 3, 3, 4, 4];
+        let ys = vec![3, 3, 4, 4, 5, 5, 6, 6];
+        let result = MannWhitneyTest::new().method(Method::Exact).run(&xs, &ys);
+
+        let variance: f64 = 64.0 / 12.0 * (17.0 - (4.0 * 6.0 + 2.0 * 60.0) / (16.0 * 15.0));
+        assert_eq!(result.method, Method::Asymptotic);
+        assert_eq!(result.statistic, 8.0);
+        assert!((result.z - (8.0 - 32.0 + 0.5) / variance.sqrt()).abs() < EPSILON);
+        assert!(result.is_rejected);
+    }
+
+    #[test]
+    fn exact_and_normal_p_values_agree_for_larger_samples() {
+        let xs: Vec<u32> = (0..40).map(|i| 3 * i).collect();
+        let ys: Vec<u32> = (0..40).map(|i| 3 * i + 10).collect();
+
+        for &alternative in [Alternative::TwoSided, Alternative::Less, Alternative::Greater].iter() {
+            let exact = MannWhitneyTest::new()
+                .alternative(alternative)
+                .method(Method::Exact)
+                .run(&xs, &ys);
+            let normal = MannWhitneyTest::new()
+                .alternative(alternative)
+                .method(Method::Asymptotic)
+                .run(&xs, &ys);
+
+            assert_eq!(exact.method, Method::Exact);
+            assert!((exact.p_value - normal.p_value).abs() < 0.01, "{:?} {:?}", exact, normal);
+        }
+    }
+
+    #[test]
+    fn pairwise_with_mann_whitney_test() {
+        let xs: Vec<u32> = (0..40).collect();
+        let ys: Vec<u32> = (0..40).map(|i| 40 - i).collect();
+        let zs: Vec<u32> = (30..70).collect();
+
+        let result = pairwise_with(&[&xs, &ys, &zs],
+                                   &MannWhitneyTest::new(),
+                                   0.95,
+                                   Correction::Bonferroni);
+
+        let significant: Vec<_> = result.significant().iter().map(|c| (c.i, c.j)).collect();
+        assert_eq!(significant, vec![(0, 2), (1, 2)]);
+
+        // The values 31 to 39 of xs exceed 45 values of zs and tie with 10.
+        assert_eq!(result.comparison(0, 2).unwrap().result.effect_size, 50.0 / 1600.0);
+    }
+}
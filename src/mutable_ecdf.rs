@@ -0,0 +1,625 @@
+//! Mutable empirical cumulative distribution function.
+
+use std::cmp::{max, Ordering};
+use std::iter::FromIterator;
+
+/// Node in the order statistic tree backing a MutableEcdf.
+///
+/// Each node holds a distinct sample value together with the number of times
+/// it occurs. Nodes are augmented with the total number of samples in their
+/// subtree so ECDF values and ranks can be located in a single descent.
+struct Node<T> {
+    value: T,
+    count: usize,
+    size: usize,
+    height: usize,
+    left: Option<Box<Node<T>>>,
+    right: Option<Box<Node<T>>>,
+}
+
+fn size<T>(node: &Option<Box<Node<T>>>) -> usize {
+    node.as_ref().map_or(0, |n| n.size)
+}
+
+fn height<T>(node: &Option<Box<Node<T>>>) -> usize {
+    node.as_ref().map_or(0, |n| n.height)
+}
+
+impl<T: Ord> Node<T> {
+    fn new(value: T) -> Box<Node<T>> {
+        Box::new(Node {
+            value: value,
+            count: 1,
+            size: 1,
+            height: 1,
+            left: None,
+            right: None,
+        })
+    }
+
+    /// Recalculate the size and height of the node from its children.
+    fn update(&mut self) {
+        self.size = size(&self.left) + self.count + size(&self.right);
+        self.height = max(height(&self.left), height(&self.right)) + 1;
+    }
+
+    fn balance_factor(&self) -> isize {
+        height(&self.left) as isize - height(&self.right) as isize
+    }
+}
+
+fn rotate_left<T: Ord>(mut node: Box<Node<T>>) -> Box<Node<T>> {
+    let mut pivot = node.right.take().expect("Rotating left requires a right child.");
+    node.right = pivot.left.take();
+    node.update();
+    pivot.left = Some(node);
+    pivot.update();
+    pivot
+}
+
+fn rotate_right<T: Ord>(mut node: Box<Node<T>>) -> Box<Node<T>> {
+    let mut pivot = node.left.take().expect("Rotating right requires a left child.");
+    node.left = pivot.right.take();
+    node.update();
+    pivot.right = Some(node);
+    pivot.update();
+    pivot
+}
+
+/// Restore the AVL height invariant at a node whose subtrees differ in height
+/// by at most two.
+fn rebalance<T: Ord>(mut node: Box<Node<T>>) -> Box<Node<T>> {
+    node.update();
+
+    let balance = node.balance_factor();
+
+    if balance > 1 {
+        if node.left.as_ref().map_or(0, |n| n.balance_factor()) < 0 {
+            node.left = node.left.take().map(rotate_left);
+        }
+        return rotate_right(node);
+    }
+
+    if balance < -1 {
+        if node.right.as_ref().map_or(0, |n| n.balance_factor()) > 0 {
+            node.right = node.right.take().map(rotate_right);
+        }
+        return rotate_left(node);
+    }
+
+    node
+}
+
+fn insert<T: Ord>(node: Option<Box<Node<T>>>, value: T) -> Box<Node<T>> {
+    let mut node = match node {
+        Some(node) => node,
+        None => return Node::new(value),
+    };
+
+    match value.cmp(&node.value) {
+        Ordering::Less => node.left = Some(insert(node.left.take(), value)),
+        Ordering::Greater => node.right = Some(insert(node.right.take(), value)),
+        Ordering::Equal => node.count += 1,
+    }
+
+    rebalance(node)
+}
+
+/// Detach the minimal node of a subtree, returning it along with the
+/// remaining rebalanced subtree.
+fn take_min<T: Ord>(mut node: Box<Node<T>>) -> (Box<Node<T>>, Option<Box<Node<T>>>) {
+    match node.left.take() {
+        None => {
+            let rest = node.right.take();
+            (node, rest)
+        }
+        Some(left) => {
+            let (min, rest) = take_min(left);
+            node.left = rest;
+            (min, Some(rebalance(node)))
+        }
+    }
+}
+
+fn remove<T: Ord>(node: Option<Box<Node<T>>>, value: &T) -> (Option<Box<Node<T>>>, bool) {
+    let mut node = match node {
+        Some(node) => node,
+        None => return (None, false),
+    };
+
+    let removed = match value.cmp(&node.value) {
+        Ordering::Less => {
+            let (left, removed) = remove(node.left.take(), value);
+            node.left = left;
+            removed
+        }
+        Ordering::Greater => {
+            let (right, removed) = remove(node.right.take(), value);
+            node.right = right;
+            removed
+        }
+        Ordering::Equal => {
+            if node.count > 1 {
+                node.count -= 1;
+            } else {
+                // Last occurrence of the value, so splice out the node.
+                let left = node.left.take();
+                let right = node.right.take();
+
+                return match (left, right) {
+                    (None, right) => (right, true),
+                    (left, None) => (left, true),
+                    (left, Some(right)) => {
+                        let (mut successor, rest) = take_min(right);
+                        successor.left = left;
+                        successor.right = rest;
+                        (Some(rebalance(successor)), true)
+                    }
+                };
+            }
+            true
+        }
+    };
+
+    (Some(rebalance(node)), removed)
+}
+
+/// Empirical cumulative distribution function supporting insertion and removal
+/// of samples.
+///
+/// Unlike Ecdf<T>, which sorts its samples once on construction, the samples
+/// are held in an order statistic tree. Inserting or removing a sample and
+/// calculating values, ranks, and percentiles all take O(log k) time where k is
+/// the number of distinct sample values, making this suitable for maintaining
+/// a live ECDF over a changing sample.
+pub struct MutableEcdf<T: Ord> {
+    root: Option<Box<Node<T>>>,
+}
+
+impl<T: Ord + Clone> MutableEcdf<T> {
+    /// Construct a new mutable representation of a cumulative distribution
+    /// function for a given sample.
+    ///
+    /// The sample may be empty, in which case samples must be inserted before
+    /// the ECDF can be queried.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    /// let ecdf = ks::MutableEcdf::new(&samples);
+    /// ```
+    pub fn new(samples: &[T]) -> MutableEcdf<T> {
+        let mut ecdf = MutableEcdf { root: None };
+        ecdf.extend(samples.iter().cloned());
+        ecdf
+    }
+
+    /// Add a sample to the ECDF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1);
+    /// let mut ecdf = ks::MutableEcdf::new(&samples);
+    /// ecdf.insert(0);
+    /// assert_eq!(ecdf.value(4), 0.5);
+    /// ```
+    pub fn insert(&mut self, t: T) {
+        self.root = Some(insert(self.root.take(), t));
+    }
+
+    /// Remove a single occurrence of a sample from the ECDF.
+    ///
+    /// Returns whether the sample was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 0);
+    /// let mut ecdf = ks::MutableEcdf::new(&samples);
+    /// assert!(ecdf.remove(&0));
+    /// assert!(ecdf.remove(&0));
+    /// assert!(!ecdf.remove(&10));
+    /// assert_eq!(ecdf.value(4), 0.5);
+    /// ```
+    pub fn remove(&mut self, t: &T) -> bool {
+        let (root, removed) = remove(self.root.take(), t);
+        self.root = root;
+        removed
+    }
+
+    /// Return the number of samples in the ECDF.
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    /// Return whether the ECDF has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Calculate a value of the empirical cumulative distribution function for
+    /// a given sample.
+    ///
+    /// # Panics
+    ///
+    /// The ECDF must be non-empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    /// let ecdf = ks::MutableEcdf::new(&samples);
+    /// assert_eq!(ecdf.value(4), 0.5);
+    /// ```
+    pub fn value(&self, t: T) -> f64 {
+        let length = self.len();
+        assert!(length > 0);
+
+        let mut num_samples_leq_t = 0;
+        let mut node = &self.root;
+
+        while let Some(ref n) = *node {
+            match t.cmp(&n.value) {
+                Ordering::Less => node = &n.left,
+                Ordering::Equal => {
+                    num_samples_leq_t += size(&n.left) + n.count;
+                    break;
+                }
+                Ordering::Greater => {
+                    num_samples_leq_t += size(&n.left) + n.count;
+                    node = &n.right;
+                }
+            }
+        }
+
+        num_samples_leq_t as f64 / length as f64
+    }
+
+    /// Calculate a percentile for the sample using the Nearest Rank method.
+    ///
+    /// # Panics
+    ///
+    /// The ECDF must be non-empty.
+    ///
+    /// The percentile requested must be between 1 and 100 inclusive. In
+    /// particular, there is no 0-percentile.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    /// let ecdf = ks::MutableEcdf::new(&samples);
+    /// assert_eq!(ecdf.percentile(50), 4);
+    /// ```
+    pub fn percentile(&self, p: u8) -> T {
+        assert!(0 < p && p <= 100);
+
+        let length = self.len();
+        assert!(length > 0);
+
+        let rank = (p as f64 * length as f64 / 100.0).ceil() as usize;
+        self.rank(rank)
+    }
+
+    /// Calculate a permille for the sample using the Nearest Rank method.
+    ///
+    /// # Panics
+    ///
+    /// The ECDF must be non-empty.
+    ///
+    /// The permille requested must be between 1 and 1000 inclusive. In
+    /// particular, there is no 0-permille.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    /// let ecdf = ks::MutableEcdf::new(&samples);
+    /// assert_eq!(ecdf.permille(500), 4);
+    /// ```
+    pub fn permille(&self, p: u16) -> T {
+        assert!(0 < p && p <= 1000);
+
+        let length = self.len();
+        assert!(length > 0);
+
+        let rank = (p as f64 * length as f64 / 1000.0).ceil() as usize;
+        self.rank(rank)
+    }
+
+    /// Calculate a rank element for the sample.
+    ///
+    /// # Panics
+    ///
+    /// The rank requested must be between 1 and the sample length inclusive. In
+    /// particular, there is no 0-rank.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    /// let ecdf = ks::MutableEcdf::new(&samples);
+    /// assert_eq!(ecdf.rank(5), 4);
+    /// ```
+    pub fn rank(&self, rank: usize) -> T {
+        let length = self.len();
+        assert!(0 < rank && rank <= length);
+
+        // Descend the tree, discarding the samples to the left of any subtree
+        // we step right into.
+        let mut rank = rank;
+        let mut node = &self.root;
+
+        while let Some(ref n) = *node {
+            let left = size(&n.left);
+
+            if rank <= left {
+                node = &n.left;
+            } else if rank <= left + n.count {
+                return n.value.clone();
+            } else {
+                rank -= left + n.count;
+                node = &n.right;
+            }
+        }
+
+        unreachable!("Rank {} not located in tree of size {}.", rank, length);
+    }
+
+    /// Return the minimal element of the samples.
+    ///
+    /// # Panics
+    ///
+    /// The ECDF must be non-empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    /// let ecdf = ks::MutableEcdf::new(&samples);
+    /// assert_eq!(ecdf.min(), 0);
+    /// ```
+    pub fn min(&self) -> T {
+        let mut node = self.root.as_ref().expect("ECDF has no samples.");
+        while let Some(ref left) = node.left {
+            node = left;
+        }
+        node.value.clone()
+    }
+
+    /// Return the maximal element of the samples.
+    ///
+    /// # Panics
+    ///
+    /// The ECDF must be non-empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    /// let ecdf = ks::MutableEcdf::new(&samples);
+    /// assert_eq!(ecdf.max(), 9);
+    /// ```
+    pub fn max(&self) -> T {
+        let mut node = self.root.as_ref().expect("ECDF has no samples.");
+        while let Some(ref right) = node.right {
+            node = right;
+        }
+        node.value.clone()
+    }
+}
+
+/// Bulk insertion of samples.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// let mut ecdf = ks::MutableEcdf::new(&[9, 8, 7, 6, 5]);
+/// ecdf.extend(vec!(4, 3, 2, 1, 0));
+/// assert_eq!(ecdf.value(4), 0.5);
+/// ```
+impl<T: Ord + Clone> Extend<T> for MutableEcdf<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for t in iter {
+            self.insert(t);
+        }
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for MutableEcdf<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> MutableEcdf<T> {
+        let mut ecdf = MutableEcdf { root: None };
+        ecdf.extend(iter);
+        ecdf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate quickcheck;
+    extern crate rand;
+
+    use self::quickcheck::{Arbitrary, Gen, QuickCheck, Testable, StdGen};
+    use std::cmp;
+    use super::{MutableEcdf, height, Node};
+    use ecdf::Ecdf;
+
+    fn check<A: Testable>(f: A) {
+        let g = StdGen::new(rand::thread_rng(), usize::MAX);
+        QuickCheck::new().gen(g).quickcheck(f);
+    }
+
+    /// Wrapper for generating sample data with QuickCheck.
+    ///
+    /// Samples must be non-empty sequences of u64 values. Values are drawn
+    /// from a small range so that samples contain plenty of ties.
+    #[derive(Debug, Clone)]
+    struct Samples {
+        vec: Vec<u64>,
+    }
+
+    impl Arbitrary for Samples {
+        fn arbitrary<G: Gen>(g: &mut G) -> Samples {
+            // Limit size of generated sample set to 1024
+            let max = cmp::min(g.size(), 1024);
+
+            let size = g.gen_range(1, max);
+            let vec = (0..size).map(|_| g.gen_range(0, 64)).collect();
+
+            Samples { vec: vec }
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Samples>> {
+            let vec: Vec<u64> = self.vec.clone();
+            let shrunk: Box<dyn Iterator<Item = Vec<u64>>> = vec.shrink();
+
+            Box::new(shrunk.filter(|v| !v.is_empty()).map(|v| Samples { vec: v }))
+        }
+    }
+
+    /// Check the AVL and size invariants for every node of a tree.
+    fn is_balanced(node: &Option<Box<Node<u64>>>) -> bool {
+        match *node {
+            None => true,
+            Some(ref n) => {
+                let left = n.left.as_ref().map_or(0, |l| l.size);
+                let right = n.right.as_ref().map_or(0, |r| r.size);
+                let balance = height(&n.left) as isize - height(&n.right) as isize;
+
+                n.size == left + n.count + right && balance.abs() <= 1 &&
+                is_balanced(&n.left) && is_balanced(&n.right)
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected="assertion failed: length > 0")]
+    fn mutable_ecdf_panics_on_value_of_empty_samples_set() {
+        let xs: Vec<u64> = vec![];
+        MutableEcdf::new(&xs).value(0);
+    }
+
+    #[test]
+    fn mutable_ecdf_is_empty_after_removing_all_samples() {
+        fn prop(xs: Samples) -> bool {
+            let mut ecdf = MutableEcdf::new(&xs.vec);
+            for x in xs.vec.iter() {
+                ecdf.remove(x);
+            }
+
+            ecdf.is_empty() && !ecdf.remove(&xs.vec[0])
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn mutable_ecdf_remove_of_missing_value_is_false() {
+        fn prop(xs: Samples) -> bool {
+            let mut ecdf = MutableEcdf::new(&xs.vec);
+
+            !ecdf.remove(&64) && ecdf.len() == xs.vec.len()
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn mutable_ecdf_tree_is_balanced() {
+        fn prop(xs: Samples, ys: Samples) -> bool {
+            let mut ecdf = MutableEcdf::new(&xs.vec);
+            ecdf.extend(ys.vec.iter().cloned());
+
+            for x in xs.vec.iter().step_by(2) {
+                ecdf.remove(x);
+            }
+
+            is_balanced(&ecdf.root)
+        }
+
+        check(prop as fn(Samples, Samples) -> bool);
+    }
+
+    #[test]
+    fn mutable_ecdf_agrees_with_ecdf() {
+        fn prop(xs: Samples, val: u64) -> bool {
+            let mutable = MutableEcdf::new(&xs.vec);
+            let ecdf = Ecdf::new(&xs.vec);
+            let val = val % 66;
+
+            mutable.value(val) == ecdf.value(val) && mutable.min() == ecdf.min() &&
+            mutable.max() == ecdf.max()
+        }
+
+        check(prop as fn(Samples, u64) -> bool);
+    }
+
+    #[test]
+    fn mutable_ecdf_ranks_agree_with_ecdf() {
+        fn prop(xs: Samples) -> bool {
+            let mutable = MutableEcdf::new(&xs.vec);
+            let ecdf = Ecdf::new(&xs.vec);
+
+            (1..xs.vec.len() + 1).all(|r| mutable.rank(r) == ecdf.rank(r))
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn mutable_ecdf_percentiles_agree_with_ecdf() {
+        fn prop(xs: Samples) -> bool {
+            let mutable = MutableEcdf::new(&xs.vec);
+            let ecdf = Ecdf::new(&xs.vec);
+
+            (1..101).all(|p| mutable.percentile(p) == ecdf.percentile(p)) &&
+            (1..1001).all(|p| mutable.permille(p) == ecdf.permille(p))
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn mutable_ecdf_after_removal_agrees_with_ecdf_of_remaining_samples() {
+        fn prop(xs: Samples, ys: Samples, val: u64) -> bool {
+            let mut all = xs.vec.clone();
+            all.extend(ys.vec.iter().cloned());
+
+            let mut mutable = MutableEcdf::new(&all);
+            for y in ys.vec.iter() {
+                assert!(mutable.remove(y));
+            }
+
+            let ecdf = Ecdf::new(&xs.vec);
+            let val = val % 66;
+
+            mutable.len() == xs.vec.len() && mutable.value(val) == ecdf.value(val) &&
+            mutable.min() == ecdf.min() && mutable.max() == ecdf.max() &&
+            (1..xs.vec.len() + 1).all(|r| mutable.rank(r) == ecdf.rank(r))
+        }
+
+        check(prop as fn(Samples, Samples, u64) -> bool);
+    }
+}
@@ -0,0 +1,92 @@
+//! Numeric sample types.
+
+use std::cmp::{Ord, Ordering};
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
+
+/// Sample types with a numeric value.
+///
+/// Implemented for the primitive integer types and OrderableF64 so that
+/// statistics such as the mean and variance can be calculated from an Ecdf.
+pub trait Numeric: Ord + Clone {
+    /// Return the sample value as a floating point number.
+    fn to_f64(&self) -> f64;
+}
+
+macro_rules! numeric_impl {
+    ($($t:ty)*) => ($(
+        impl Numeric for $t {
+            fn to_f64(&self) -> f64 {
+                *self as f64
+            }
+        }
+    )*)
+}
+
+numeric_impl! { i8 i16 i32 i64 isize u8 u16 u32 u64 usize }
+
+/// Wrapper type for f64 to implement Ord and make usable with test and Ecdf.
+///
+/// This is necessary because f64 does not implement Ord in Rust as some
+/// elements are incomparable, e.g. NaN. Comparison of wrapped NaN values
+/// panics.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::{Numeric, OrderableF64};
+///
+/// let xs: Vec<OrderableF64> = vec!(0.5, 1.5, 0.0).into_iter().map(OrderableF64::new).collect();
+/// let ecdf = ks::Ecdf::new(&xs);
+/// assert_eq!(ecdf.max().to_f64(), 1.5);
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct OrderableF64 {
+    val: f64,
+}
+
+impl OrderableF64 {
+    pub fn new(val: f64) -> OrderableF64 {
+        OrderableF64 { val: val }
+    }
+}
+
+impl Eq for OrderableF64 {}
+
+// Comparisons with NaN are None rather than panicking as for cmp.
+#[allow(clippy::non_canonical_partial_ord_impl)]
+impl PartialOrd for OrderableF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.val.partial_cmp(&other.val)
+    }
+}
+
+impl Ord for OrderableF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.val.partial_cmp(&other.val).unwrap()
+    }
+}
+
+impl fmt::Display for OrderableF64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.val.fmt(f)
+    }
+}
+
+/// Serializes as the wrapped f64.
+#[cfg(feature = "serde")]
+impl Serialize for OrderableF64 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.val)
+    }
+}
+
+impl Numeric for OrderableF64 {
+    fn to_f64(&self) -> f64 {
+        self.val
+    }
+}
@@ -0,0 +1,315 @@
+//! Pairwise two sample tests across many samples.
+//!
+//! Compares every pair of a set of samples, e.g. of builds or hosts, with the
+//! two sample Kolmogorov-Smirnov test, or any other two sample test. The ECDF
+//! of each sample is built once for the Kolmogorov-Smirnov test and the pairs
+//! are tested in parallel.
+
+use std::cmp::max;
+use std::thread;
+
+use correction::{correct_results, Correction};
+use ecdf::Ecdf;
+use test::{test_ecdfs, Alternative, Method, TestOutcome, TestResult, TwoSampleTest};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Two sample test of a pair of samples.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Comparison<R = TestResult> {
+    /// Index of the first sample of the pair.
+    pub i: usize,
+    /// Index of the second sample of the pair, greater than i.
+    pub j: usize,
+    pub result: R,
+    /// The p-value of the test adjusted for the number of comparisons with a
+    /// multiple testing correction.
+    pub adjusted_p_value: f64,
+    /// Whether the adjusted p-value is below the significance level, i.e. one
+    /// minus the confidence level.
+    pub is_significant: bool,
+}
+
+/// Pairwise test results, with a comparison for each pair of samples.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PairwiseResult<R = TestResult> {
+    pub num_samples: usize,
+    /// Comparisons ordered by the first and then second sample index.
+    pub comparisons: Vec<Comparison<R>>,
+}
+
+impl<R: TestOutcome> PairwiseResult<R> {
+    /// Return the comparison of samples i and j in either order, or None if
+    /// they are the same sample.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic if either index is out of range.
+    pub fn comparison(&self, i: usize, j: usize) -> Option<&Comparison<R>> {
+        let n = self.num_samples;
+        assert!(i < n && j < n);
+
+        let (i, j) = if i < j { (i, j) } else { (j, i) };
+        if i == j {
+            return None;
+        }
+
+        // Pairs before row i, then the offset of column j in row i.
+        Some(&self.comparisons[i * (2 * n - i - 1) / 2 + (j - i - 1)])
+    }
+
+    /// Return the test statistic of samples i and j, which is zero when they
+    /// are the same sample.
+    pub fn statistic(&self, i: usize, j: usize) -> f64 {
+        self.comparison(i, j).map_or(0.0, |comparison| comparison.result.statistic())
+    }
+
+    /// Return the matrix of test statistics, e.g. ECDF distances, of every pair
+    /// of samples.
+    pub fn statistics(&self) -> Vec<Vec<f64>> {
+        (0..self.num_samples)
+            .map(|i| (0..self.num_samples).map(|j| self.statistic(i, j)).collect())
+            .collect()
+    }
+
+    /// Return the comparisons with significant differences in order of
+    /// increasing adjusted p-value.
+    pub fn significant(&self) -> Vec<&Comparison<R>> {
+        let mut significant: Vec<&Comparison<R>> = self.comparisons
+                                                    .iter()
+                                                    .filter(|comparison| comparison.is_significant)
+                                                    .collect();
+        significant.sort_by(|a, b| a.adjusted_p_value.partial_cmp(&b.adjusted_p_value).unwrap());
+        significant
+    }
+}
+
+/// Perform two sided two sample Kolmogorov-Smirnov tests of every pair of
+/// samples in parallel, adjusting the p-values for the number of comparisons
+/// with a multiple testing correction.
+///
+/// The confidence level applies to the comparisons as a whole, e.g. to the
+/// family-wise error rate for the Bonferroni correction.
+///
+/// The samples must each have length > 7 elements for the tests to be valid.
+///
+/// # Panics
+///
+/// There are assertion panics if any sample has <= 7 elements or if the
+/// confidence level is not strictly between zero and one.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::correction::Correction;
+/// use ks::test::Method;
+///
+/// let xs: Vec<u32> = (0..40).collect();
+/// let ys: Vec<u32> = (1..41).collect();
+/// let zs: Vec<u32> = (30..70).collect();
+///
+/// let result = ks::pairwise::pairwise(&[&xs, &ys, &zs],
+///                                     0.95,
+///                                     Method::Asymptotic,
+///                                     Correction::Holm);
+///
+/// assert_eq!(result.comparisons.len(), 3);
+/// assert_eq!(result.statistic(2, 0), 0.75);
+///
+/// let significant: Vec<_> = result.significant().iter().map(|c| (c.i, c.j)).collect();
+/// assert_eq!(significant, vec![(0, 2), (1, 2)]);
+/// ```
+pub fn pairwise<T: Ord + Clone + Sync>(samples: &[&[T]],
+                                       confidence: f64,
+                                       method: Method,
+                                       correction: Correction)
+                                       -> PairwiseResult {
+    assert!(0.0 < confidence && confidence < 1.0);
+
+    // Only supports samples of size > 7.
+    assert!(samples.iter().all(|xs| xs.len() > 7));
+
+    let ecdfs: Vec<Ecdf<T>> = samples.iter().map(|xs| Ecdf::new(xs)).collect();
+
+    compare(samples.len(), confidence, correction, |i, j| {
+        test_ecdfs(&ecdfs[i], &ecdfs[j], confidence, Alternative::TwoSided, method)
+    })
+}
+
+/// Perform a two sample test of every pair of samples in parallel, adjusting
+/// the p-values for the number of comparisons with a multiple testing
+/// correction.
+///
+/// The confidence level of the correction applies to the comparisons as a
+/// whole, independently of any confidence level of the test itself.
+///
+/// # Panics
+///
+/// There is an assertion panic if the confidence level is not strictly
+/// between zero and one, and the test panics as for a single pair of samples,
+/// e.g. if any sample is too small for the test.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::correction::Correction;
+/// use ks::test::Method;
+/// use ks::KsTest;
+///
+/// let xs: Vec<u32> = (0..40).collect();
+/// let ys: Vec<u32> = (1..41).collect();
+/// let zs: Vec<u32> = (30..70).collect();
+///
+/// let ks_test = KsTest::two_sample().method(Method::Exact);
+/// let result = ks::pairwise::pairwise_with(&[&xs, &ys, &zs], &ks_test, 0.95, Correction::Holm);
+///
+/// let significant: Vec<_> = result.significant().iter().map(|c| (c.i, c.j)).collect();
+/// assert_eq!(significant, vec![(0, 2), (1, 2)]);
+/// ```
+pub fn pairwise_with<T, S>(samples: &[&[T]],
+                           test: &S,
+                           confidence: f64,
+                           correction: Correction)
+                           -> PairwiseResult<S::Outcome>
+    where T: Ord + Clone + Sync,
+          S: TwoSampleTest + Sync,
+          S::Outcome: Send
+{
+    assert!(0.0 < confidence && confidence < 1.0);
+
+    compare(samples.len(), confidence, correction, |i, j| test.test(samples[i], samples[j]))
+}
+
+/// Compare every pair of n samples with a test of samples i and j in
+/// parallel, then correct the p-values of the comparisons.
+fn compare<R, F>(n: usize, confidence: f64, correction: Correction, test: F) -> PairwiseResult<R>
+    where R: TestOutcome + Send,
+          F: Fn(usize, usize) -> R + Sync
+{
+    let pairs: Vec<(usize, usize)> = (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j))).collect();
+
+    // Share the pairs evenly between a thread for each available CPU.
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let chunk_size = max(1, pairs.len().div_ceil(threads));
+
+    let results: Vec<R> = thread::scope(|scope| {
+        let test = &test;
+        let handles: Vec<_> = pairs.chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || chunk.iter().map(|&(i, j)| test(i, j)).collect::<Vec<_>>())
+            })
+            .collect();
+
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    });
+
+    let decisions = correct_results(&results, correction, confidence);
+
+    let comparisons = pairs.into_iter()
+        .zip(results)
+        .zip(decisions)
+        .map(|(((i, j), result), decision)| {
+            Comparison {
+                i: i,
+                j: j,
+                result: result,
+                adjusted_p_value: decision.adjusted_p_value,
+                is_significant: decision.is_rejected,
+            }
+        })
+        .collect();
+
+    PairwiseResult {
+        num_samples: n,
+        comparisons: comparisons,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pairwise, pairwise_with};
+    use correction::Correction;
+    use test::{test_with, Alternative, KsTest, Method};
+
+    fn samples() -> Vec<Vec<i64>> {
+        vec![(0..30).collect(), (5..35).collect(), (0..60).map(|x| x / 2).collect(), (25..55).collect()]
+    }
+
+    #[test]
+    fn pairwise_matches_test_of_each_pair() {
+        let samples = samples();
+        let slices: Vec<&[i64]> = samples.iter().map(|xs| &xs[..]).collect();
+
+        let result = pairwise(&slices, 0.95, Method::Asymptotic, Correction::Bonferroni);
+        assert_eq!(result.comparisons.len(), 6);
+
+        for i in 0..4 {
+            assert!(result.comparison(i, i).is_none());
+            assert_eq!(result.statistic(i, i), 0.0);
+
+            for j in (i + 1)..4 {
+                let comparison = result.comparison(j, i).unwrap();
+                let expected = test_with(&samples[i], &samples[j], 0.95, Alternative::TwoSided,
+                                         Method::Asymptotic);
+
+                assert_eq!((comparison.i, comparison.j), (i, j));
+                assert_eq!(comparison.result.statistic, expected.statistic);
+                assert_eq!(comparison.result.p_value, expected.p_value);
+                assert_eq!(result.statistics()[i][j], result.statistics()[j][i]);
+            }
+        }
+    }
+
+    #[test]
+    fn pairwise_adjusts_p_values_for_number_of_pairs() {
+        let samples = samples();
+        let slices: Vec<&[i64]> = samples.iter().map(|xs| &xs[..]).collect();
+
+        let result = pairwise(&slices, 0.95, Method::Exact, Correction::Bonferroni);
+
+        for comparison in result.comparisons.iter() {
+            assert_eq!(comparison.adjusted_p_value,
+                       (6.0 * comparison.result.p_value).min(1.0));
+            assert_eq!(comparison.is_significant, comparison.adjusted_p_value < 0.05);
+
+            // Adjusting never finds more differences than the unadjusted test.
+            assert!(!comparison.is_significant || comparison.result.is_rejected);
+        }
+
+        let significant = result.significant();
+        assert!(!significant.is_empty());
+        assert!(significant.windows(2).all(|w| w[0].adjusted_p_value <= w[1].adjusted_p_value));
+    }
+
+    #[test]
+    fn pairwise_with_ks_test_matches_pairwise() {
+        let samples = samples();
+        let slices: Vec<&[i64]> = samples.iter().map(|xs| &xs[..]).collect();
+
+        let expected = pairwise(&slices, 0.9, Method::Exact, Correction::Holm);
+        let ks_test = KsTest::two_sample().confidence(0.9).method(Method::Exact);
+        let result = pairwise_with(&slices, &ks_test, 0.9, Correction::Holm);
+
+        assert_eq!(result.statistics(), expected.statistics());
+        for (comparison, expected) in result.comparisons.iter().zip(expected.comparisons.iter()) {
+            assert_eq!(comparison.result.p_value, expected.result.p_value);
+            assert_eq!(comparison.adjusted_p_value, expected.adjusted_p_value);
+            assert_eq!(comparison.is_significant, expected.is_significant);
+        }
+    }
+
+    #[test]
+    fn pairwise_of_single_sample_has_no_comparisons() {
+        let xs: Vec<i64> = (0..10).collect();
+
+        let result = pairwise(&[&xs], 0.95, Method::Asymptotic, Correction::Holm);
+
+        assert!(result.comparisons.is_empty());
+        assert_eq!(result.statistics(), vec![vec![0.0]]);
+    }
+}
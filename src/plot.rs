@@ -0,0 +1,666 @@
+//! SVG plots of empirical cumulative distribution functions.
+//!
+//! Renders ECDFs as step functions in a standalone SVG document, optionally
+//! highlighting the maximum distance between two ECDFs, i.e. the two sample
+//! Kolmogorov-Smirnov test statistic, and drawing confidence bands.
+
+use std::fmt::Write;
+
+use ecdf::Ecdf;
+use numeric::Numeric;
+use test::distance;
+
+/// Colours used for successive ECDFs in a plot.
+const PALETTE: [&str; 6] = ["#1f77b4", "#ff7f0e", "#2ca02c", "#9467bd", "#8c564b", "#e377c2"];
+
+/// Colour used to highlight the maximum distance between two ECDFs.
+const HIGHLIGHT: &str = "#d62728";
+
+const MARGIN_LEFT: f64 = 60.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 50.0;
+
+/// Plot rendering options.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    /// Width of the plot in pixels.
+    pub width: u32,
+    /// Height of the plot in pixels.
+    pub height: u32,
+    pub title: Option<String>,
+    /// Highlight the location and size of the maximum distance when plotting
+    /// exactly two ECDFs.
+    pub highlight_distance: bool,
+    /// Draw Dvoretzky-Kiefer-Wolfowitz confidence bands around each ECDF at
+    /// the specified confidence level.
+    pub confidence_band: Option<f64>,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            width: 640,
+            height: 400,
+            title: None,
+            highlight_distance: true,
+            confidence_band: None,
+        }
+    }
+}
+
+/// Calculate the half width of the Dvoretzky-Kiefer-Wolfowitz confidence band
+/// for an ECDF of a sample with n elements.
+///
+/// The true cumulative distribution function lies within this distance of the
+/// ECDF everywhere with the specified confidence.
+///
+/// # Panics
+///
+/// The confidence must be strictly between zero and one.
+pub fn dkw_band(n: usize, confidence: f64) -> f64 {
+    assert!(0.0 < confidence && confidence < 1.0);
+
+    let alpha = 1.0 - confidence;
+    ((2.0 / alpha).ln() / (2.0 * n as f64)).sqrt()
+}
+
+/// Mapping from data coordinates to SVG coordinates.
+struct Frame {
+    x_min: f64,
+    x_max: f64,
+    width: f64,
+    height: f64,
+}
+
+impl Frame {
+    fn x(&self, x: f64) -> f64 {
+        let plot_width = self.width - MARGIN_LEFT - MARGIN_RIGHT;
+        MARGIN_LEFT + (x - self.x_min) / (self.x_max - self.x_min) * plot_width
+    }
+
+    fn y(&self, p: f64) -> f64 {
+        let plot_height = self.height - MARGIN_TOP - MARGIN_BOTTOM;
+        MARGIN_TOP + (1.0 - p) * plot_height
+    }
+}
+
+/// Escape text for inclusion in SVG markup.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Calculate evenly spaced axis ticks at a round step size covering the range
+/// from low to high, as (value, label) pairs.
+fn ticks(low: f64, high: f64) -> Vec<(f64, String)> {
+    let raw_step = (high - low) / 5.0;
+    let magnitude = 10f64.powf(raw_step.log10().floor());
+
+    let step = [1.0, 2.0, 5.0, 10.0]
+                   .iter()
+                   .map(|m| m * magnitude)
+                   .find(|&step| step >= raw_step)
+                   .unwrap_or(10.0 * magnitude);
+
+    // A degenerate range, e.g. of a single value too large to pad, has no
+    // step, so has a single tick at low. The tick indices must also fit in
+    // i64 for the range to be stepped over.
+    if step <= 0.0 || !step.is_finite() || (low / step).abs() >= i64::MAX as f64 ||
+       (high / step).abs() >= i64::MAX as f64 {
+        return vec![(low, low.to_string())];
+    }
+
+    let first = (low / step).ceil() as i64;
+    let last = (high / step).floor() as i64;
+
+    // Label with just enough decimal places to distinguish the ticks.
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+
+    (first..last + 1)
+        .map(|i| {
+            let tick = i as f64 * step;
+            (tick, format!("{:.*}", decimals, tick))
+        })
+        .collect()
+}
+
+/// Step function points of an ECDF shifted by an offset and clamped to the
+/// unit interval, as (value, probability) pairs.
+fn step_points<T: Numeric>(ecdf: &Ecdf<T>, offset: f64) -> Vec<(f64, f64)> {
+    ecdf.steps()
+        .map(|step| (step.value.to_f64(), (step.probability + offset).clamp(0.0, 1.0)))
+        .collect()
+}
+
+/// Range of values to plot for ECDFs, padded so the first and last steps are
+/// visible.
+fn plot_range<T: Numeric>(ecdfs: &[(&str, &Ecdf<T>)]) -> (f64, f64) {
+    let low = ecdfs.iter().map(|&(_, e)| e.min().to_f64()).fold(f64::INFINITY, f64::min);
+    let high = ecdfs.iter().map(|&(_, e)| e.max().to_f64()).fold(f64::NEG_INFINITY, f64::max);
+    let padding = if high > low {
+        0.05 * (high - low)
+    } else {
+        // Pad a single value in proportion to its size, so the padding is not
+        // lost to rounding for large values.
+        (0.05 * low.abs()).max(1.0)
+    };
+
+    (low - padding, high + padding)
+}
+
+/// SVG path data for a step function starting at probability p0 at x_min
+/// and extending to x_max.
+fn step_path(frame: &Frame, p0: f64, points: &[(f64, f64)]) -> String {
+    let mut path = String::new();
+
+    write!(path, "M{:.2},{:.2}", frame.x(frame.x_min), frame.y(p0)).unwrap();
+    for &(x, p) in points {
+        write!(path, " H{:.2} V{:.2}", frame.x(x), frame.y(p)).unwrap();
+    }
+    write!(path, " H{:.2}", frame.x(frame.x_max)).unwrap();
+
+    path
+}
+
+/// Render ECDFs as step functions in an SVG document.
+///
+/// Each ECDF is given with a label for the plot legend.
+///
+/// # Panics
+///
+/// There must be at least one ECDF to plot.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::plot::{Options, svg};
+///
+/// let xs = ks::Ecdf::new(&vec!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9));
+/// let ys = ks::Ecdf::new(&vec!(4, 5, 6, 7, 8, 9, 10, 11, 12, 13));
+///
+/// let options = Options { confidence_band: Some(0.95), ..Options::default() };
+/// let svg = svg(&[("xs", &xs), ("ys", &ys)], &options);
+/// assert!(svg.starts_with("<svg"));
+/// ```
+pub fn svg<T: Numeric>(ecdfs: &[(&str, &Ecdf<T>)], options: &Options) -> String {
+    assert!(!ecdfs.is_empty());
+
+    let width = options.width as f64;
+    let height = options.height as f64;
+
+    let (x_min, x_max) = plot_range(ecdfs);
+
+    let frame = Frame {
+        x_min: x_min,
+        x_max: x_max,
+        width: width,
+        height: height,
+    };
+
+    let mut svg = String::new();
+
+    writeln!(svg,
+             "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+              viewBox=\"0 0 {} {}\" font-family=\"sans-serif\" font-size=\"12\">",
+             options.width,
+             options.height,
+             options.width,
+             options.height)
+        .unwrap();
+    writeln!(svg,
+             "<rect width=\"{}\" height=\"{}\" fill=\"white\"/>",
+             options.width,
+             options.height)
+        .unwrap();
+
+    if let Some(ref title) = options.title {
+        writeln!(svg,
+                 "<text x=\"{:.2}\" y=\"24\" text-anchor=\"middle\" font-size=\"16\">{}</text>",
+                 width / 2.0,
+                 escape(title))
+            .unwrap();
+    }
+
+    // Axes with tick marks and grid lines.
+    let (left, right) = (frame.x(frame.x_min), frame.x(frame.x_max));
+    let (top, bottom) = (frame.y(1.0), frame.y(0.0));
+
+    for (tick, label) in ticks(frame.x_min, frame.x_max) {
+        let x = frame.x(tick);
+        writeln!(svg,
+                 "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"#e0e0e0\"/>",
+                 x,
+                 top,
+                 x,
+                 bottom)
+            .unwrap();
+        writeln!(svg,
+                 "<text x=\"{:.2}\" y=\"{:.2}\" text-anchor=\"middle\">{}</text>",
+                 x,
+                 bottom + 16.0,
+                 label)
+            .unwrap();
+    }
+
+    for i in 0..5 {
+        let p = i as f64 / 4.0;
+        let y = frame.y(p);
+        writeln!(svg,
+                 "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"#e0e0e0\"/>",
+                 left,
+                 y,
+                 right,
+                 y)
+            .unwrap();
+        writeln!(svg,
+                 "<text x=\"{:.2}\" y=\"{:.2}\" text-anchor=\"end\">{}</text>",
+                 left - 6.0,
+                 y + 4.0,
+                 p)
+            .unwrap();
+    }
+
+    writeln!(svg,
+             "<path d=\"M{:.2},{:.2} V{:.2} H{:.2}\" fill=\"none\" stroke=\"black\"/>",
+             left,
+             top,
+             bottom,
+             right)
+        .unwrap();
+    writeln!(svg,
+             "<text x=\"{:.2}\" y=\"{:.2}\" text-anchor=\"middle\">x</text>",
+             (left + right) / 2.0,
+             height - 12.0)
+        .unwrap();
+    writeln!(svg,
+             "<text x=\"16\" y=\"{:.2}\" text-anchor=\"middle\" \
+              transform=\"rotate(-90 16 {:.2})\">ECDF(x)</text>",
+             (top + bottom) / 2.0,
+             (top + bottom) / 2.0)
+        .unwrap();
+
+    // Confidence bands are drawn first so the ECDFs are drawn over them.
+    if let Some(confidence) = options.confidence_band {
+        for (i, &(_, ecdf)) in ecdfs.iter().enumerate() {
+            let band = dkw_band(ecdf.num_samples(), confidence);
+            let colour = PALETTE[i % PALETTE.len()];
+
+            for &offset in [band, -band].iter() {
+                let p0 = offset.clamp(0.0, 1.0);
+                writeln!(svg,
+                         "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-opacity=\"0.5\" \
+                          stroke-dasharray=\"4 3\"/>",
+                         step_path(&frame, p0, &step_points(ecdf, offset)),
+                         colour)
+                    .unwrap();
+            }
+        }
+    }
+
+    for (i, &(_, ecdf)) in ecdfs.iter().enumerate() {
+        writeln!(svg,
+                 "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\"/>",
+                 step_path(&frame, 0.0, &step_points(ecdf, 0.0)),
+                 PALETTE[i % PALETTE.len()])
+            .unwrap();
+    }
+
+    if options.highlight_distance && ecdfs.len() == 2 {
+        let (xs, ys) = (ecdfs[0].1, ecdfs[1].1);
+        let distance = distance(xs, ys);
+
+        let location = distance.location.clone();
+        let x = frame.x(distance.location.to_f64());
+        let (y1, y2) = (frame.y(xs.value(location.clone())), frame.y(ys.value(location)));
+
+        writeln!(svg,
+                 "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"{}\" \
+                  stroke-width=\"2\"/>",
+                 x,
+                 y1,
+                 x,
+                 y2,
+                 HIGHLIGHT)
+            .unwrap();
+        writeln!(svg,
+                 "<text x=\"{:.2}\" y=\"{:.2}\" fill=\"{}\">D = {:.4}</text>",
+                 x + 6.0,
+                 (y1 + y2) / 2.0 + 4.0,
+                 HIGHLIGHT,
+                 distance.statistic)
+            .unwrap();
+    }
+
+    // Legend in the lower right corner, clear of ECDFs that rise to the right.
+    for (i, &(label, _)) in ecdfs.iter().enumerate() {
+        let y = bottom - 12.0 - 18.0 * (ecdfs.len() - 1 - i) as f64;
+        writeln!(svg,
+                 "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"{}\" \
+                  stroke-width=\"3\"/>",
+                 right - 140.0,
+                 y - 4.0,
+                 right - 120.0,
+                 y - 4.0,
+                 PALETTE[i % PALETTE.len()])
+            .unwrap();
+        writeln!(svg,
+                 "<text x=\"{:.2}\" y=\"{:.2}\">{}</text>",
+                 right - 114.0,
+                 y,
+                 escape(label))
+            .unwrap();
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Minimum width of a text plot in terminal columns.
+pub const MIN_TEXT_COLUMNS: usize = 16;
+
+/// Text plot rendering options.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextOptions {
+    /// Width of the plot in terminal columns, including axis labels.
+    pub columns: usize,
+    /// Height of the plot area in terminal rows.
+    pub rows: usize,
+    /// Colour each ECDF using ANSI escape codes.
+    pub colour: bool,
+}
+
+impl Default for TextOptions {
+    fn default() -> TextOptions {
+        TextOptions {
+            columns: 80,
+            rows: 20,
+            colour: false,
+        }
+    }
+}
+
+/// ANSI colour codes used for successive ECDFs in a text plot.
+const ANSI_PALETTE: [&str; 6] = ["34", "33", "32", "35", "36", "37"];
+
+/// ANSI colour code used to highlight the maximum distance between two ECDFs.
+const ANSI_HIGHLIGHT: &str = "31";
+
+/// Width of the probability axis labels in a text plot, e.g. "0.50 ┤".
+const TEXT_MARGIN: usize = 6;
+
+/// Braille dot bits indexed by dot row and column within a character cell.
+const BRAILLE_DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Value of a step function at x, given its (value, probability) steps.
+fn step_value(points: &[(f64, f64)], x: f64) -> f64 {
+    match points.partition_point(|&(value, _)| value <= x) {
+        0 => 0.0,
+        i => points[i - 1].1,
+    }
+}
+
+/// Wrap text in an ANSI colour escape code if colour is enabled.
+fn paint(text: &str, code: &str, colour: bool) -> String {
+    if colour {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Render ECDFs as step functions in text using Unicode braille characters,
+/// for display in a terminal.
+///
+/// Each character cell holds a two by four grid of dots. When exactly two
+/// ECDFs are plotted, the location of the maximum distance between them is
+/// marked with a vertical line. A legend follows the plot.
+///
+/// # Panics
+///
+/// There must be at least one ECDF to plot, and the plot must be at least
+/// sixteen columns wide and four rows high.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::plot::{text, TextOptions};
+///
+/// let xs = ks::Ecdf::new(&vec!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9));
+/// let ys = ks::Ecdf::new(&vec!(4, 5, 6, 7, 8, 9, 10, 11, 12, 13));
+///
+/// print!("{}", text(&[("xs", &xs), ("ys", &ys)], &TextOptions::default()));
+/// ```
+pub fn text<T: Numeric>(ecdfs: &[(&str, &Ecdf<T>)], options: &TextOptions) -> String {
+    assert!(!ecdfs.is_empty());
+    assert!(options.columns >= MIN_TEXT_COLUMNS && options.rows >= 4);
+
+    let columns = options.columns - TEXT_MARGIN;
+    let rows = options.rows;
+    let (x_min, x_max) = plot_range(ecdfs);
+
+    // Data value at the centre of a dot column, and the dot row of a
+    // probability.
+    let dot_columns = 2 * columns;
+    let dot_rows = 4 * rows;
+    let x = |i: usize| x_min + (i as f64 + 0.5) / dot_columns as f64 * (x_max - x_min);
+    let row = |p: f64| ((1.0 - p) * (dot_rows - 1) as f64).round() as usize;
+
+    // Braille dot bits and the index of the last ECDF drawn in each cell.
+    let mut cells = vec![vec![(0u8, 0usize); columns]; rows];
+
+    for (k, &(_, ecdf)) in ecdfs.iter().enumerate() {
+        let points = step_points(ecdf, 0.0);
+        let mut previous = row(0.0);
+
+        for i in 0..dot_columns {
+            let current = row(step_value(&points, x(i)));
+
+            // Fill the rise from the previous dot column to draw the step.
+            for r in current.min(previous)..current.max(previous) + 1 {
+                let cell = &mut cells[r / 4][i / 2];
+                cell.0 |= BRAILLE_DOTS[r % 4][i % 2];
+                cell.1 = k;
+            }
+
+            previous = current;
+        }
+    }
+
+    let mut rendered: Vec<Vec<String>> = cells.iter()
+        .map(|line| {
+            line.iter()
+                .map(|&(bits, k)| {
+                    let c = ::std::char::from_u32(0x2800 + bits as u32).unwrap();
+                    if bits == 0 {
+                        c.to_string()
+                    } else {
+                        paint(&c.to_string(), ANSI_PALETTE[k % ANSI_PALETTE.len()], options.colour)
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut legend: Vec<String> = ecdfs.iter()
+        .enumerate()
+        .map(|(k, &(label, _))| {
+            format!("{} {}", paint("⣿", ANSI_PALETTE[k % ANSI_PALETTE.len()], options.colour), label)
+        })
+        .collect();
+
+    if ecdfs.len() == 2 {
+        let (xs, ys) = (ecdfs[0].1, ecdfs[1].1);
+        let distance = distance(xs, ys);
+
+        let location = distance.location.to_f64();
+        let column = ((location - x_min) / (x_max - x_min) * columns as f64) as usize;
+        let p1 = xs.value(distance.location.clone());
+        let p2 = ys.value(distance.location.clone());
+
+        // Mark the cells strictly between the two ECDFs.
+        let (top, bottom) = (row(p1.max(p2)) / 4, row(p1.min(p2)) / 4);
+        for line in rendered.iter_mut().take(bottom).skip(top + 1) {
+            line[column.min(columns - 1)] = paint("│", ANSI_HIGHLIGHT, options.colour);
+        }
+
+        legend.push(format!("{} D = {:.4} at {}",
+                            paint("│", ANSI_HIGHLIGHT, options.colour),
+                            distance.statistic,
+                            location));
+    }
+
+    let mut text = String::new();
+
+    for (r, line) in rendered.iter().enumerate() {
+        let label = if r == 0 {
+            "1.00 ┤"
+        } else if r == rows / 2 {
+            "0.50 ┤"
+        } else if r == rows - 1 {
+            "0.00 ┤"
+        } else {
+            "     │"
+        };
+
+        text.push_str(label);
+        text.push_str(&line.concat());
+        text.push('\n');
+    }
+
+    // Value axis with the range of the plot at either end.
+    let low = format!("{:.4}", x_min);
+    let high = format!("{:.4}", x_max);
+    let gap = columns.saturating_sub(low.chars().count() + high.chars().count());
+
+    writeln!(text, "     └{}", "─".repeat(columns)).unwrap();
+    writeln!(text, "      {}{}{}", low, " ".repeat(gap), high).unwrap();
+    writeln!(text, "      {}", legend.join("  ")).unwrap();
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dkw_band, escape, svg, text, ticks, Options, TextOptions};
+    use ecdf::Ecdf;
+
+    #[test]
+    fn dkw_band_decreases_with_sample_size() {
+        assert!(dkw_band(100, 0.95) > dkw_band(1000, 0.95));
+        assert!(dkw_band(100, 0.99) > dkw_band(100, 0.95));
+        assert!((dkw_band(100, 0.95) - 0.135_810_3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn ticks_are_round_and_cover_range() {
+        fn labels(low: f64, high: f64) -> Vec<String> {
+            ticks(low, high).into_iter().map(|(_, label)| label).collect()
+        }
+
+        assert_eq!(labels(0.0, 10.0), vec!["0", "2", "4", "6", "8", "10"]);
+        assert_eq!(labels(-0.3, 0.7), vec!["-0.2", "0.0", "0.2", "0.4", "0.6"]);
+        assert_eq!(labels(3.0, 3.5), vec!["3.0", "3.1", "3.2", "3.3", "3.4", "3.5"]);
+        assert_eq!(labels(-1050.0, 4200.0), vec!["0", "2000", "4000"]);
+    }
+
+    #[test]
+    fn ticks_of_degenerate_range_are_single_tick() {
+        assert_eq!(ticks(1e20, 1e20), vec![(1e20, 1e20.to_string())]);
+        assert_eq!(ticks(-3.0, -3.0), vec![(-3.0, "-3".to_string())]);
+
+        // Samples of a single large value plot with a padded range.
+        let xs = Ecdf::new(&[1e20 as i64 / 100; 10]);
+        let svg = svg(&[("xs", &xs), ("ys", &xs)], &Options::default());
+        assert!(svg.ends_with("</svg>\n") && !svg.contains("NaN"));
+    }
+
+    #[test]
+    fn labels_are_escaped() {
+        assert_eq!(escape("a < b & \"c\""), "a &lt; b &amp; &quot;c&quot;");
+
+        let ecdf = Ecdf::new(&[0, 1, 2]);
+        let svg = svg(&[("<xs>", &ecdf)], &Options::default());
+
+        assert!(svg.contains("&lt;xs&gt;"));
+        assert!(!svg.contains("<xs>"));
+    }
+
+    #[test]
+    fn svg_has_path_per_ecdf_and_distance_highlight() {
+        let xs = Ecdf::new(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let ys = Ecdf::new(&[4, 5, 6, 7, 8, 9, 10, 11, 12, 13]);
+
+        let svg = svg(&[("xs", &xs), ("ys", &ys)], &Options::default());
+
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("stroke-width=\"1.5\"").count(), 2);
+        assert!(svg.contains("D = 0.4000"));
+    }
+
+    #[test]
+    fn svg_draws_two_band_paths_per_ecdf() {
+        let xs = Ecdf::new(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        let options = Options {
+            confidence_band: Some(0.95),
+            highlight_distance: false,
+            title: Some("Bands".to_string()),
+            ..Options::default()
+        };
+        let svg = svg(&[("xs", &xs)], &options);
+
+        assert_eq!(svg.matches("stroke-dasharray").count(), 2);
+        assert!(svg.contains(">Bands</text>"));
+        assert!(!svg.contains("D = "));
+    }
+
+    #[test]
+    fn text_has_requested_size_and_distance_marker() {
+        let xs = Ecdf::new(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let ys = Ecdf::new(&[4, 5, 6, 7, 8, 9, 10, 11, 12, 13]);
+
+        let options = TextOptions {
+            columns: 40,
+            rows: 10,
+            colour: false,
+        };
+        let text = text(&[("xs", &xs), ("ys", &ys)], &options);
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 13);
+        assert!(lines[..10].iter().all(|line| line.chars().count() == 40));
+        assert!(lines[..10].iter().any(|line| line.contains('│')));
+        assert!(lines[12].contains("D = 0.4000 at 3"));
+        assert!(!text.contains('\x1b'));
+    }
+
+    #[test]
+    fn text_draws_ecdf_from_bottom_left_to_top_right() {
+        let xs = Ecdf::new(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        let text = text(&[("xs", &xs)], &TextOptions::default());
+        let lines: Vec<&str> = text.lines().collect();
+
+        // Lowest dot row of the first cell and highest dot row of the last.
+        assert_eq!(lines[19].chars().nth(6), Some('⣀'));
+        assert_eq!(lines[0].chars().last(), Some('⠉'));
+        assert!(!text.contains("D = "));
+    }
+
+    #[test]
+    fn text_colours_ecdfs_when_enabled() {
+        let xs = Ecdf::new(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        let options = TextOptions { colour: true, ..TextOptions::default() };
+        let text = text(&[("xs", &xs)], &options);
+
+        assert!(text.contains("\x1b[34m"));
+    }
+}
@@ -0,0 +1,313 @@
+//! Fitting power law tails to samples.
+//!
+//! Implements the procedure of Clauset, Shalizi and Newman, "Power-law
+//! distributions in empirical data", SIAM Review 51 (2009). The power law with
+//! density proportional to x^-alpha is fitted to the tail of the sample above
+//! xmin, choosing xmin to minimise the Kolmogorov-Smirnov distance between the
+//! ECDF of the tail and the fitted power law, and estimating alpha by maximum
+//! likelihood for each candidate xmin.
+//!
+//! The continuous power law is fitted, which approximates discrete data such
+//! as counts well for tails above a few units.
+
+use rand::Rng;
+
+use distributions::{ContinuousDistribution, Pareto};
+use numeric::{Numeric, OrderableF64};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Power law fitted to the tail of a sample.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PowerLaw {
+    /// Lower bound of the tail following the power law.
+    pub xmin: f64,
+    /// Exponent of the power law density, greater than one.
+    pub alpha: f64,
+    /// Standard error of the estimate of alpha.
+    pub alpha_error: f64,
+    /// Number of sample values in the tail, i.e. at least xmin.
+    pub tail_count: usize,
+    /// Kolmogorov-Smirnov distance between the ECDF of the tail and the power
+    /// law.
+    pub statistic: f64,
+    /// Bootstrap p-value of the statistic, if calculated. Small p-values, e.g.
+    /// below 0.1, rule out the power law.
+    pub p_value: Option<f64>,
+}
+
+impl PowerLaw {
+    /// Return the fitted power law of the tail, a Pareto distribution with
+    /// scale xmin and shape alpha - 1.
+    pub fn distribution(&self) -> Pareto {
+        Pareto::new(self.xmin, self.alpha - 1.0)
+    }
+}
+
+/// Minimum number of values in a tail to fit, as for the one sample test.
+const MIN_TAIL_COUNT: usize = 8;
+
+/// Fit a power law to the tail of a sample.
+///
+/// If bootstraps is positive, the goodness-of-fit p-value is calculated from
+/// that many synthetic samples generated with rng, each with the power law
+/// above xmin and values resampled from the sample below xmin, in proportion
+/// to the tail. The p-value is the proportion of synthetic samples whose own
+/// fitted power law is at least as distant as that of the sample.
+///
+/// Returns None if no tail of more than 7 positive, not all equal values can
+/// be fitted.
+///
+/// # Panics
+///
+/// There is an assertion panic if the sample has <= 7 elements, and a panic
+/// if a sample value is NaN.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+/// extern crate rand;
+///
+/// use ks::power_law;
+///
+/// // Power law quantiles with xmin = 1 and alpha = 2.5 above a uniform body.
+/// let mut xs: Vec<f64> = (0..1000).map(|i| (1.0 - (i as f64 + 0.5) / 1000.0).powf(-1.0 / 1.5)).collect();
+/// xs.extend((0..100).map(|i| i as f64 / 100.0));
+///
+/// let fit = power_law::fit(&xs, 0, &mut rand::thread_rng()).unwrap();
+///
+/// assert_eq!(fit.xmin, xs[0]);
+/// assert!((fit.alpha - 2.5).abs() < 0.01);
+/// assert!(fit.p_value.is_none());
+/// ```
+pub fn fit(xs: &[f64], bootstraps: usize, rng: &mut dyn Rng) -> Option<PowerLaw> {
+    // Only supports samples of size > 7.
+    assert!(xs.len() > 7);
+
+    let mut xs: Vec<OrderableF64> = xs.iter().map(|&x| OrderableF64::new(x)).collect();
+    xs.sort();
+
+    let mut power_law = fit_sorted(&xs)?;
+
+    if bootstraps > 0 {
+        power_law.p_value = Some(bootstrap_p_value(&xs, &power_law, bootstraps, rng));
+    }
+
+    Some(power_law)
+}
+
+/// Fit a power law to the tail of a sorted sample, trying each distinct
+/// positive value as xmin.
+///
+/// The maximum likelihood estimate of alpha only depends on the sum of the
+/// logarithms of the tail, which are accumulated from the top of the sample
+/// down, so each xmin takes time proportional to the size of its tail for the
+/// distance.
+fn fit_sorted(xs: &[OrderableF64]) -> Option<PowerLaw> {
+    let n = xs.len();
+    let mut best: Option<PowerLaw> = None;
+
+    // Element i is the sum of the logarithms of xs[i..], for positive values.
+    let mut suffix_ln = vec![0.0; n + 1];
+    for i in (0..n).rev() {
+        let x = xs[i].to_f64();
+        if x <= 0.0 {
+            break;
+        }
+        suffix_ln[i] = suffix_ln[i + 1] + x.ln();
+    }
+
+    for i in 0..n {
+        let tail_count = n - i;
+        if tail_count < MIN_TAIL_COUNT {
+            break;
+        }
+
+        let xmin = xs[i].to_f64();
+        if xmin <= 0.0 || (i > 0 && xs[i] == xs[i - 1]) {
+            continue;
+        }
+
+        if xs[n - 1] == xs[i] {
+            // The rest of the sample is equal to xmin.
+            break;
+        }
+
+        let sum_ln = suffix_ln[i] - tail_count as f64 * xmin.ln();
+        if sum_ln <= 0.0 {
+            // The tail is too close to xmin to resolve from the sums.
+            continue;
+        }
+
+        let alpha = 1.0 + tail_count as f64 / sum_ln;
+        let pareto = Pareto::new(xmin, alpha - 1.0);
+        let statistic = tail_distance(&xs[i..], &pareto);
+
+        if best.as_ref().is_none_or(|best| statistic < best.statistic) {
+            best = Some(PowerLaw {
+                xmin: xmin,
+                alpha: alpha,
+                alpha_error: (alpha - 1.0) / (tail_count as f64).sqrt(),
+                tail_count: tail_count,
+                statistic: statistic,
+                p_value: None,
+            });
+        }
+    }
+
+    best
+}
+
+/// Calculate the Kolmogorov-Smirnov distance between the ECDF of a sorted
+/// tail and a power law, as for distance_to_distribution, sweeping the steps
+/// of the tail in place.
+fn tail_distance(tail: &[OrderableF64], pareto: &Pareto) -> f64 {
+    let m = tail.len();
+    let mut statistic: f64 = 0.0;
+    let mut previous = 0.0;
+    let mut i = 0;
+
+    while i < m {
+        let value = tail[i];
+        while i < m && tail[i] == value {
+            i += 1;
+        }
+
+        let x = value.to_f64();
+        let probability = i as f64 / m as f64;
+        let cdf = pareto.cdf(x);
+
+        statistic = statistic.max(probability - cdf).max(cdf - previous);
+        previous = probability;
+    }
+
+    statistic
+}
+
+/// Calculate the goodness-of-fit p-value of a power law fitted to a sorted
+/// sample by a semi-parametric bootstrap.
+///
+/// Synthetic samples that cannot be fitted are discarded.
+fn bootstrap_p_value(xs: &[OrderableF64],
+                     power_law: &PowerLaw,
+                     bootstraps: usize,
+                     rng: &mut dyn Rng)
+                     -> f64 {
+    let n = xs.len();
+    let body = &xs[..n - power_law.tail_count];
+    let tail_probability = power_law.tail_count as f64 / n as f64;
+    let distribution = power_law.distribution();
+
+    let mut count = 0;
+    let mut extreme = 0;
+
+    for _ in 0..bootstraps {
+        let mut ys: Vec<OrderableF64> = (0..n)
+            .map(|_| {
+                if body.is_empty() || rng.next_f64() < tail_probability {
+                    OrderableF64::new(distribution.sample(rng))
+                } else {
+                    let i = (rng.next_f64() * body.len() as f64) as usize;
+                    body[i.min(body.len() - 1)]
+                }
+            })
+            .collect();
+        ys.sort();
+
+        if let Some(refitted) = fit_sorted(&ys) {
+            count += 1;
+            if refitted.statistic >= power_law.statistic {
+                extreme += 1;
+            }
+        }
+    }
+
+    // Count the sample itself so the p-value is never zero.
+    (extreme + 1) as f64 / (count + 1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rand;
+
+    use self::rand::{SeedableRng, StdRng};
+
+    use super::{fit, tail_distance};
+    use distributions::{ContinuousDistribution, Exponential, Pareto, Uniform};
+    use ecdf::Ecdf;
+    use numeric::OrderableF64;
+    use test::distance_to_distribution;
+
+    #[test]
+    fn fit_recovers_power_law_tail_above_body() {
+        let mut rng = StdRng::from_seed(&[42]);
+
+        // Two thirds of the sample follows a power law above 5 with alpha 2.5.
+        let tail = Pareto::new(5.0, 1.5);
+        let body = Uniform::new(0.0, 5.0);
+        let xs: Vec<f64> = (0..3000)
+            .map(|i| if i % 3 == 0 { body.sample(&mut rng) } else { tail.sample(&mut rng) })
+            .collect();
+
+        let power_law = fit(&xs, 0, &mut rng).unwrap();
+
+        assert!(4.5 < power_law.xmin && power_law.xmin < 6.0, "{:?}", power_law);
+        assert!((power_law.alpha - 2.5).abs() < 3.0 * power_law.alpha_error,
+                "{:?}",
+                power_law);
+        assert!((power_law.tail_count as f64 - 2000.0).abs() < 200.0);
+        assert_eq!(power_law.alpha_error,
+                   (power_law.alpha - 1.0) / (power_law.tail_count as f64).sqrt());
+    }
+
+    #[test]
+    fn bootstrap_rejects_exponential_but_not_power_law() {
+        let mut rng = StdRng::from_seed(&[7]);
+
+        let pareto = Pareto::new(1.0, 1.5);
+        let xs: Vec<f64> = (0..500).map(|_| pareto.sample(&mut rng)).collect();
+        let power_law = fit(&xs, 19, &mut rng).unwrap();
+        assert!(power_law.p_value.unwrap() > 0.1, "{:?}", power_law);
+
+        // Short tails of an exponential sample resemble a power law, so a
+        // larger sample is needed to rule it out.
+        let exponential = Exponential::new(1.0);
+        let ys: Vec<f64> = (0..2000).map(|_| exponential.sample(&mut rng)).collect();
+        let power_law = fit(&ys, 19, &mut rng).unwrap();
+        assert!(power_law.p_value.unwrap() <= 0.1, "{:?}", power_law);
+    }
+
+    #[test]
+    fn tail_distance_equals_distance_to_distribution() {
+        let mut rng = StdRng::from_seed(&[3]);
+        let pareto = Pareto::new(1.0, 1.5);
+
+        // Rounding leaves ties in the tail.
+        let mut tail: Vec<OrderableF64> = (0..200)
+            .map(|_| OrderableF64::new((pareto.sample(&mut rng) * 4.0).round() / 4.0))
+            .collect();
+        tail.sort();
+
+        let expected = distance_to_distribution(&Ecdf::new(&tail), &pareto).statistic;
+        assert_eq!(tail_distance(&tail, &pareto), expected);
+    }
+
+    #[test]
+    fn fit_requires_positive_tail_of_distinct_values() {
+        let mut rng = StdRng::from_seed(&[1]);
+
+        let negative: Vec<f64> = (0..20).map(|i| -i as f64).collect();
+        assert!(fit(&negative, 0, &mut rng).is_none());
+
+        let constant = vec![3.0; 20];
+        assert!(fit(&constant, 0, &mut rng).is_none());
+
+        // Only the smallest positive values leave a tail of more than 7.
+        let mixed: Vec<f64> = (-10..10).map(|i| i as f64).collect();
+        let power_law = fit(&mixed, 0, &mut rng).unwrap();
+        assert!(power_law.xmin <= 2.0);
+    }
+}
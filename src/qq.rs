@@ -0,0 +1,338 @@
+//! Quantile-quantile and probability-probability plot data.
+//!
+//! Q-Q and P-P plots are the usual diagnostics for how two distributions
+//! differ when a Kolmogorov-Smirnov test rejects. These functions generate the
+//! aligned pairs of points for plotting tools to consume.
+
+use std::cmp::min;
+
+use ecdf::Ecdf;
+use numeric::Numeric;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Point in a quantile-quantile plot.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct QqPoint<T> {
+    /// Probability at which the quantiles are calculated.
+    pub probability: f64,
+    pub x: T,
+    pub y: T,
+}
+
+/// Point in a probability-probability plot.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PpPoint<T> {
+    /// Sample value at which the cumulative probabilities are calculated.
+    pub value: T,
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Probability grid (i - 1/2) / n for i = 1, ..., n. These plotting positions
+/// avoid the extreme quantiles at probabilities zero and one.
+fn probability_grid(num_points: usize) -> Vec<f64> {
+    (0..num_points).map(|i| (i as f64 + 0.5) / num_points as f64).collect()
+}
+
+/// Generate two sample quantile-quantile plot data.
+///
+/// Quantiles of both samples are calculated at a common grid of probabilities
+/// using the Nearest Rank method. The number of points is the smaller of the
+/// two sample sizes.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// let xs = ks::Ecdf::new(&vec!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9));
+/// let ys = ks::Ecdf::new(&vec!(10, 11, 12, 13, 14, 15, 16, 17, 18, 19));
+///
+/// for point in ks::qq::qq(&xs, &ys) {
+///     assert_eq!(point.y, point.x + 10);
+/// }
+/// ```
+pub fn qq<T: Ord + Clone>(xs: &Ecdf<T>, ys: &Ecdf<T>) -> Vec<QqPoint<T>> {
+    qq_grid(xs, ys, min(xs.num_samples(), ys.num_samples()))
+}
+
+/// Generate two sample quantile-quantile plot data with a specified number of
+/// points.
+///
+/// # Panics
+///
+/// The number of points must be positive.
+pub fn qq_grid<T: Ord + Clone>(xs: &Ecdf<T>, ys: &Ecdf<T>, num_points: usize) -> Vec<QqPoint<T>> {
+    assert!(num_points > 0);
+
+    probability_grid(num_points)
+        .into_iter()
+        .map(|p| {
+            QqPoint {
+                probability: p,
+                x: xs.quantile(p),
+                y: ys.quantile(p),
+            }
+        })
+        .collect()
+}
+
+/// Generate quantile-quantile plot data for a sample against a theoretical
+/// distribution specified by its quantile function.
+///
+/// The x coordinates are the theoretical quantiles and the y coordinates are
+/// the sample quantiles, with one point per sample.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// let xs = ks::Ecdf::new(&vec!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9));
+///
+/// // Uniform distribution on [0, 10).
+/// let points = ks::qq::qq_theoretical(&xs, |p| 10.0 * p);
+/// assert_eq!(points[0].x, 0.5);
+/// assert_eq!(points[0].y, 0.0);
+/// ```
+pub fn qq_theoretical<T, F>(xs: &Ecdf<T>, quantile: F) -> Vec<QqPoint<f64>>
+    where T: Numeric,
+          F: Fn(f64) -> f64
+{
+    probability_grid(xs.num_samples())
+        .into_iter()
+        .map(|p| {
+            QqPoint {
+                probability: p,
+                x: quantile(p),
+                y: xs.quantile(p).to_f64(),
+            }
+        })
+        .collect()
+}
+
+/// Generate two sample probability-probability plot data.
+///
+/// There is one point for each distinct value in the pooled samples, with the
+/// ECDF values of the two samples at that value as coordinates.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// let xs = ks::Ecdf::new(&vec!(0, 1, 2, 3));
+/// let ys = ks::Ecdf::new(&vec!(2, 3, 4, 5));
+///
+/// let points = ks::qq::pp(&xs, &ys);
+/// assert_eq!(points.len(), 6);
+/// assert_eq!((points[2].x, points[2].y), (0.75, 0.25));
+/// ```
+pub fn pp<T: Ord + Clone>(xs: &Ecdf<T>, ys: &Ecdf<T>) -> Vec<PpPoint<T>> {
+    let mut steps_xs = xs.steps().peekable();
+    let mut steps_ys = ys.steps().peekable();
+
+    let mut value_xs = 0.0;
+    let mut value_ys = 0.0;
+    let mut points = Vec::new();
+
+    loop {
+        // Step to the next sample value in either ECDF, taking the step in
+        // both if they share the value.
+        let current = match (steps_xs.peek(), steps_ys.peek()) {
+            (Some(x), Some(y)) => min(&x.value, &y.value).clone(),
+            (Some(x), None) => x.value.clone(),
+            (None, Some(y)) => y.value.clone(),
+            (None, None) => break,
+        };
+
+        if steps_xs.peek().is_some_and(|x| x.value == current) {
+            value_xs = steps_xs.next().unwrap().probability;
+        }
+        if steps_ys.peek().is_some_and(|y| y.value == current) {
+            value_ys = steps_ys.next().unwrap().probability;
+        }
+
+        points.push(PpPoint {
+            value: current,
+            x: value_xs,
+            y: value_ys,
+        });
+    }
+
+    points
+}
+
+/// Generate probability-probability plot data for a sample against a
+/// theoretical distribution specified by its cumulative distribution function.
+///
+/// There is one point for each distinct sample value. The x coordinates are
+/// the theoretical cumulative probabilities and the y coordinates are the ECDF
+/// values.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// let xs = ks::Ecdf::new(&vec!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9));
+///
+/// // Uniform distribution on [0, 10).
+/// let points = ks::qq::pp_theoretical(&xs, |x| x / 10.0);
+/// assert_eq!((points[4].x, points[4].y), (0.4, 0.5));
+/// ```
+pub fn pp_theoretical<T, F>(xs: &Ecdf<T>, cdf: F) -> Vec<PpPoint<f64>>
+    where T: Numeric,
+          F: Fn(f64) -> f64
+{
+    xs.steps()
+      .map(|step| {
+          let value = step.value.to_f64();
+          PpPoint {
+              value: value,
+              x: cdf(value),
+              y: step.probability,
+          }
+      })
+      .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate quickcheck;
+    extern crate rand;
+
+    use self::quickcheck::{Arbitrary, Gen, QuickCheck, Testable, StdGen};
+    use std::cmp;
+    use super::{qq, qq_theoretical, pp, pp_theoretical};
+    use distributions::{ContinuousDistribution, Normal, Uniform};
+    use ecdf::Ecdf;
+    use numeric::{Numeric, OrderableF64};
+
+    fn check<A: Testable>(f: A) {
+        let g = StdGen::new(rand::thread_rng(), usize::MAX);
+        QuickCheck::new().gen(g).quickcheck(f);
+    }
+
+    /// Wrapper for generating sample data with QuickCheck.
+    ///
+    /// Samples must be non-empty sequences of u64 values.
+    #[derive(Debug, Clone)]
+    struct Samples {
+        vec: Vec<u64>,
+    }
+
+    impl Arbitrary for Samples {
+        fn arbitrary<G: Gen>(g: &mut G) -> Samples {
+            // Limit size of generated sample set to 1024
+            let max = cmp::min(g.size(), 1024);
+
+            let size = g.gen_range(1, max);
+            let vec = (0..size).map(|_| g.gen_range(0, 1024)).collect();
+
+            Samples { vec: vec }
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Samples>> {
+            let vec: Vec<u64> = self.vec.clone();
+            let shrunk: Box<dyn Iterator<Item = Vec<u64>>> = vec.shrink();
+
+            Box::new(shrunk.filter(|v| !v.is_empty()).map(|v| Samples { vec: v }))
+        }
+    }
+
+    #[test]
+    fn qq_of_identical_samples_is_diagonal() {
+        fn prop(xs: Samples) -> bool {
+            let ecdf = Ecdf::new(&xs.vec);
+
+            qq(&ecdf, &ecdf).iter().all(|point| point.x == point.y)
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn qq_has_min_sample_length_increasing_points() {
+        fn prop(xs: Samples, ys: Samples) -> bool {
+            let points = qq(&Ecdf::new(&xs.vec), &Ecdf::new(&ys.vec));
+
+            points.len() == cmp::min(xs.vec.len(), ys.vec.len()) &&
+            points.windows(2).all(|w| {
+                w[0].probability < w[1].probability && w[0].x <= w[1].x && w[0].y <= w[1].y
+            })
+        }
+
+        check(prop as fn(Samples, Samples) -> bool);
+    }
+
+    #[test]
+    fn qq_theoretical_of_distribution_quantiles_is_diagonal() {
+        let distributions: Vec<Box<dyn ContinuousDistribution>> =
+            vec![Box::new(Uniform::new(-2.0, 3.0)), Box::new(Normal::new(1.0, 2.0))];
+
+        for distribution in distributions.iter() {
+            for &n in [1, 2, 7, 50, 333].iter() {
+                // A sample of the distribution quantiles at the grid.
+                let xs: Vec<OrderableF64> = (0..n)
+                    .map(|i| OrderableF64::new(distribution.quantile((i as f64 + 0.5) / n as f64)))
+                    .collect();
+
+                let points = qq_theoretical(&Ecdf::new(&xs), |p| distribution.quantile(p));
+
+                assert_eq!(points.len(), n);
+                for (point, x) in points.iter().zip(xs.iter()) {
+                    assert_eq!(point.y, x.to_f64());
+                    assert!((point.x - point.y).abs() < 1e-12, "{:?}", point);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pp_has_point_for_each_distinct_pooled_value() {
+        fn prop(xs: Samples, ys: Samples) -> bool {
+            let mut pooled = xs.vec.clone();
+            pooled.extend(ys.vec.iter().cloned());
+            pooled.sort();
+            pooled.dedup();
+
+            let points = pp(&Ecdf::new(&xs.vec), &Ecdf::new(&ys.vec));
+            let values: Vec<u64> = points.iter().map(|point| point.value).collect();
+
+            values == pooled
+        }
+
+        check(prop as fn(Samples, Samples) -> bool);
+    }
+
+    #[test]
+    fn pp_points_are_ecdf_values() {
+        fn prop(xs: Samples, ys: Samples) -> bool {
+            let ecdf_xs = Ecdf::new(&xs.vec);
+            let ecdf_ys = Ecdf::new(&ys.vec);
+
+            pp(&ecdf_xs, &ecdf_ys).iter().all(|point| {
+                point.x == ecdf_xs.value(point.value) && point.y == ecdf_ys.value(point.value)
+            })
+        }
+
+        check(prop as fn(Samples, Samples) -> bool);
+    }
+
+    #[test]
+    fn pp_theoretical_of_sample_ecdf_is_diagonal() {
+        fn prop(xs: Samples) -> bool {
+            let ecdf = Ecdf::new(&xs.vec);
+            let points = pp_theoretical(&ecdf, |x| ecdf.value(x as u64));
+
+            points.iter().all(|point| point.x == point.y)
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+}
@@ -0,0 +1,476 @@
+//! Special functions used by the density and distribution calculations.
+
+use std::f64::consts::PI;
+
+/// Relative accuracy targeted by the iterative calculations.
+const EPSILON: f64 = 1e-15;
+
+/// Lanczos approximation coefficients for g = 7, n = 9.
+const LANCZOS: [f64; 9] = [0.999_999_999_999_809_9,
+                           676.520_368_121_885_1,
+                           -1_259.139_216_722_402_8,
+                           771.323_428_777_653_1,
+                           -176.615_029_162_140_6,
+                           12.507_343_278_686_905,
+                           -0.138_571_095_265_720_12,
+                           9.984_369_578_019_572e-6,
+                           1.505_632_735_149_311_6e-7];
+
+/// Calculate the natural logarithm of the Gamma function for x > 0.
+pub fn ln_gamma(x: f64) -> f64 {
+    assert!(x > 0.0);
+
+    if x < 0.5 {
+        // Reflection formula.
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+
+    let mut sum = LANCZOS[0];
+    for (i, &c) in LANCZOS.iter().enumerate().skip(1) {
+        sum += c / (x + i as f64);
+    }
+
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// Calculate the digamma function, the derivative of ln_gamma, for x > 0.
+pub fn digamma(x: f64) -> f64 {
+    assert!(x > 0.0);
+
+    // Shift x up with the recurrence psi(x) = psi(x + 1) - 1 / x until the
+    // asymptotic series is accurate.
+    let mut x = x;
+    let mut shift = 0.0;
+    while x < 10.0 {
+        shift -= 1.0 / x;
+        x += 1.0;
+    }
+
+    let r = 1.0 / (x * x);
+    shift + x.ln() - 0.5 / x -
+    r * (1.0 / 12.0 - r * (1.0 / 120.0 - r * (1.0 / 252.0 - r * (1.0 / 240.0 - r / 132.0))))
+}
+
+/// Calculate the trigamma function, the derivative of digamma, for x > 0.
+pub fn trigamma(x: f64) -> f64 {
+    assert!(x > 0.0);
+
+    // Shift x up with the recurrence psi'(x) = psi'(x + 1) + 1 / x^2.
+    let mut x = x;
+    let mut shift = 0.0;
+    while x < 10.0 {
+        shift += 1.0 / (x * x);
+        x += 1.0;
+    }
+
+    let r = 1.0 / (x * x);
+    shift + 1.0 / x + 0.5 * r +
+    r / x * (1.0 / 6.0 - r * (1.0 / 30.0 - r * (1.0 / 42.0 - r * (1.0 / 30.0 - r * 5.0 / 66.0))))
+}
+
+/// Calculate the regularized lower incomplete Gamma function P(a, x).
+///
+/// Returns NaN if a or x is NaN.
+pub fn gamma_p(a: f64, x: f64) -> f64 {
+    if a.is_nan() || x.is_nan() {
+        return f64::NAN;
+    }
+
+    assert!(a > 0.0 && x >= 0.0);
+
+    if x == 0.0 {
+        0.0
+    } else if x == f64::INFINITY {
+        1.0
+    } else if x < a + 1.0 {
+        gamma_series(a, x)
+    } else {
+        1.0 - gamma_continued_fraction(a, x)
+    }
+}
+
+/// Calculate the regularized upper incomplete Gamma function Q(a, x).
+///
+/// Returns NaN if a or x is NaN.
+pub fn gamma_q(a: f64, x: f64) -> f64 {
+    if a.is_nan() || x.is_nan() {
+        return f64::NAN;
+    }
+
+    assert!(a > 0.0 && x >= 0.0);
+
+    if x == 0.0 {
+        1.0
+    } else if x == f64::INFINITY {
+        0.0
+    } else if x < a + 1.0 {
+        1.0 - gamma_series(a, x)
+    } else {
+        gamma_continued_fraction(a, x)
+    }
+}
+
+/// Series representation of P(a, x), converges quickly for x < a + 1.
+fn gamma_series(a: f64, x: f64) -> f64 {
+    let mut ap = a;
+    let mut term = 1.0 / a;
+    let mut sum = term;
+
+    for _ in 0..1000 {
+        ap += 1.0;
+        term *= x / ap;
+        sum += term;
+
+        if term.abs() < sum.abs() * EPSILON {
+            return sum * (-x + a * x.ln() - ln_gamma(a)).exp();
+        }
+    }
+
+    panic!("No convergence in gamma_series({}, {}).", a, x);
+}
+
+/// Continued fraction representation of Q(a, x) evaluated using the modified
+/// Lentz method, converges quickly for x >= a + 1.
+fn gamma_continued_fraction(a: f64, x: f64) -> f64 {
+    let tiny = 1e-300;
+
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / tiny;
+    let mut d = 1.0 / b;
+    let mut h = d;
+
+    for i in 1..1000 {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+
+        d = an * d + b;
+        if d.abs() < tiny {
+            d = tiny;
+        }
+        c = b + an / c;
+        if c.abs() < tiny {
+            c = tiny;
+        }
+
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            return (-x + a * x.ln() - ln_gamma(a)).exp() * h;
+        }
+    }
+
+    panic!("No convergence in gamma_continued_fraction({}, {}).", a, x);
+}
+
+/// Bound beyond which erfc(x) underflows to zero, or is two for -x.
+const ERFC_UNDERFLOW: f64 = 27.0;
+
+/// Calculate the complementary error function, 1 - erf(x), without loss of
+/// precision in the upper tail.
+///
+/// Returns NaN if x is NaN.
+pub fn erfc(x: f64) -> f64 {
+    if x.is_nan() {
+        f64::NAN
+    } else if x > ERFC_UNDERFLOW {
+        // Also avoids x * x overflowing for large x.
+        0.0
+    } else if x < -ERFC_UNDERFLOW {
+        2.0
+    } else if x < 0.0 {
+        1.0 + gamma_p(0.5, x * x)
+    } else {
+        gamma_q(0.5, x * x)
+    }
+}
+
+/// Calculate the cumulative distribution function of the standard Normal
+/// distribution.
+pub fn normal_cdf(z: f64) -> f64 {
+    0.5 * erfc(-z / 2.0f64.sqrt())
+}
+
+/// Calculate the probability density function of the standard Normal
+/// distribution.
+pub fn normal_pdf(z: f64) -> f64 {
+    (-0.5 * z * z).exp() / (2.0 * PI).sqrt()
+}
+
+/// Coefficients of the central region rational approximation of the Normal
+/// quantile function, Wichura's algorithm AS 241.
+const NORMAL_QUANTILE_A: [f64; 8] = [3.387_132_872_796_366_6,
+                                     133.141_667_891_784_38,
+                                     1_971.590_950_306_551_4,
+                                     13_731.693_765_509_46,
+                                     45_921.953_931_549_87,
+                                     67_265.770_927_008_7,
+                                     33_430.575_583_588_13,
+                                     2_509.080_928_730_122_7];
+const NORMAL_QUANTILE_B: [f64; 8] = [1.0,
+                                     42.313_330_701_600_91,
+                                     687.187_007_492_057_9,
+                                     5_394.196_021_424_751,
+                                     21_213.794_301_586_597,
+                                     39_307.895_800_092_71,
+                                     28_729.085_735_721_943,
+                                     5_226.495_278_852_545];
+
+/// Coefficients of the intermediate tail rational approximation.
+const NORMAL_QUANTILE_C: [f64; 8] = [1.423_437_110_749_683_5,
+                                     4.630_337_846_156_545,
+                                     5.769_497_221_460_691,
+                                     3.647_848_324_763_204_5,
+                                     1.270_458_252_452_368_4,
+                                     0.241_780_725_177_450_6,
+                                     0.022_723_844_989_269_184,
+                                     7.745_450_142_783_414e-4];
+const NORMAL_QUANTILE_D: [f64; 8] = [1.0,
+                                     2.053_191_626_637_759,
+                                     1.676_384_830_183_803_8,
+                                     0.689_767_334_985_1,
+                                     0.148_103_976_427_480_08,
+                                     0.015_198_666_563_616_457,
+                                     5.475_938_084_995_345e-4,
+                                     1.050_750_071_644_416_8e-9];
+
+/// Coefficients of the far tail rational approximation.
+const NORMAL_QUANTILE_E: [f64; 8] = [6.657_904_643_501_103,
+                                     5.463_784_911_164_114,
+                                     1.784_826_539_917_291_3,
+                                     0.296_560_571_828_504_9,
+                                     0.026_532_189_526_576_124,
+                                     0.001_242_660_947_388_078_4,
+                                     2.711_555_568_743_487_6e-5,
+                                     2.010_334_399_292_288e-7];
+const NORMAL_QUANTILE_F: [f64; 8] = [1.0,
+                                     0.599_832_206_555_888,
+                                     0.136_929_880_922_735_8,
+                                     0.014_875_361_290_850_615,
+                                     7.868_691_311_456_133e-4,
+                                     1.846_318_317_510_054_8e-5,
+                                     1.421_511_758_316_446e-7,
+                                     2.044_263_103_389_939_7e-15];
+
+/// Evaluate a polynomial with coefficients in increasing order of degree.
+fn polynomial(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |sum, &c| sum * x + c)
+}
+
+/// Calculate the quantile function of the standard Normal distribution, i.e.
+/// the inverse of normal_cdf, for p in [0, 1].
+///
+/// Uses Wichura's algorithm AS 241, accurate to about 1 part in 10^16.
+pub fn normal_quantile(p: f64) -> f64 {
+    assert!((0.0..=1.0).contains(&p));
+
+    if p == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p == 1.0 {
+        return f64::INFINITY;
+    }
+
+    let q = p - 0.5;
+
+    if q.abs() <= 0.425 {
+        let r = 0.180_625 - q * q;
+        return q * polynomial(&NORMAL_QUANTILE_A, r) / polynomial(&NORMAL_QUANTILE_B, r);
+    }
+
+    // Tail probability, calculating the lower tail quantile.
+    let r = (-p.min(1.0 - p).ln()).sqrt();
+    let z = if r <= 5.0 {
+        let r = r - 1.6;
+        polynomial(&NORMAL_QUANTILE_C, r) / polynomial(&NORMAL_QUANTILE_D, r)
+    } else {
+        let r = r - 5.0;
+        polynomial(&NORMAL_QUANTILE_E, r) / polynomial(&NORMAL_QUANTILE_F, r)
+    };
+
+    if q < 0.0 { -z } else { z }
+}
+
+/// Calculate the natural logarithm of the Beta function for a, b > 0.
+pub fn ln_beta(a: f64, b: f64) -> f64 {
+    ln_gamma(a) + ln_gamma(b) - ln_gamma(a + b)
+}
+
+/// Calculate the regularized incomplete Beta function I_x(a, b).
+pub fn beta_inc(a: f64, b: f64, x: f64) -> f64 {
+    assert!(a > 0.0 && b > 0.0 && (0.0..=1.0).contains(&x));
+
+    if x == 0.0 || x == 1.0 {
+        return x;
+    }
+
+    let front = (a * x.ln() + b * (1.0 - x).ln() - ln_beta(a, b)).exp();
+
+    // The continued fraction converges quickly below the mean, so use the
+    // symmetry I_x(a, b) = 1 - I_(1-x)(b, a) above it.
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Continued fraction representation of I_x(a, b) evaluated using the
+/// modified Lentz method, converges quickly for x < (a + 1) / (a + b + 2).
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    let tiny = 1e-300;
+
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < tiny {
+        d = tiny;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..1000 {
+        let m = m as f64;
+
+        // Even and odd steps of the continued fraction.
+        for &an in [m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)),
+                    -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0))]
+            .iter() {
+            d = 1.0 + an * d;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = 1.0 + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+
+            d = 1.0 / d;
+            h *= d * c;
+        }
+
+        if (d * c - 1.0).abs() < EPSILON {
+            return h;
+        }
+    }
+
+    panic!("No convergence in beta_continued_fraction({}, {}, {}).", a, b, x);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ln_gamma, digamma, trigamma, gamma_p, gamma_q, erfc, normal_cdf, normal_quantile,
+                beta_inc};
+
+    const EPSILON: f64 = 1e-12;
+
+    fn close(actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() <= EPSILON * expected.abs().max(1.0)
+    }
+
+    #[test]
+    fn ln_gamma_of_integers_is_ln_factorial() {
+        let mut factorial = 1.0f64;
+
+        for n in 1..30 {
+            assert!(close(ln_gamma(n as f64), factorial.ln()));
+            factorial *= n as f64;
+        }
+    }
+
+    #[test]
+    fn ln_gamma_of_one_half_is_ln_root_pi() {
+        assert!(close(ln_gamma(0.5), 0.5 * ::std::f64::consts::PI.ln()));
+    }
+
+    #[test]
+    fn digamma_and_trigamma_known_values() {
+        // psi(1) is minus the Euler-Mascheroni constant and psi'(1) = pi^2 / 6.
+        assert!(close(digamma(1.0), -0.577_215_664_901_532_9));
+        assert!(close(trigamma(1.0), ::std::f64::consts::PI.powi(2) / 6.0));
+        assert!(close(digamma(0.5), -1.963_510_026_021_423_5));
+        assert!(close(trigamma(0.5), ::std::f64::consts::PI.powi(2) / 2.0));
+
+        for &x in [0.1, 1.5, 7.0, 120.0].iter() {
+            assert!(close(digamma(x + 1.0) - digamma(x), 1.0 / x));
+            assert!(close(trigamma(x) - trigamma(x + 1.0), 1.0 / (x * x)));
+        }
+    }
+
+    #[test]
+    fn gamma_p_and_gamma_q_sum_to_one() {
+        for &a in [0.1, 0.5, 1.0, 2.5, 10.0, 100.0].iter() {
+            for &x in [0.01, 0.5, 1.0, 3.0, 10.0, 150.0].iter() {
+                assert!(close(gamma_p(a, x) + gamma_q(a, x), 1.0));
+            }
+        }
+    }
+
+    #[test]
+    fn gamma_p_and_gamma_q_of_nan_and_infinity() {
+        assert!(gamma_p(2.0, f64::NAN).is_nan() && gamma_q(2.0, f64::NAN).is_nan());
+        assert!(gamma_p(f64::NAN, 1.0).is_nan() && gamma_q(f64::NAN, 1.0).is_nan());
+        assert_eq!(gamma_p(0.5, f64::INFINITY), 1.0);
+        assert_eq!(gamma_q(0.5, f64::INFINITY), 0.0);
+    }
+
+    #[test]
+    fn gamma_p_of_one_is_exponential_cdf() {
+        for &x in [0.01, 0.5, 1.0, 3.0, 10.0].iter() {
+            assert!(close(gamma_p(1.0, x), 1.0 - (-x).exp()));
+        }
+    }
+
+    #[test]
+    fn erfc_known_values() {
+        assert_eq!(erfc(0.0), 1.0);
+        assert!(close(erfc(0.5), 1.0 - 0.520_499_877_813_046_5));
+        assert!(close(erfc(1.0), 1.0 - 0.842_700_792_949_714_9));
+        assert!(close(erfc(-2.0), 1.0 + 0.995_322_265_018_952_7));
+        assert!(close(erfc(3.0), 2.209_049_699_858_544e-5));
+        assert!((erfc(6.0) - 2.151_973_671_249_892e-17).abs() < 1e-28);
+        assert!(erfc(26.5) > 0.0 && erfc(26.5) < 1e-300);
+        assert_eq!(erfc(1e200), 0.0);
+        assert_eq!(erfc(-1e200), 2.0);
+        assert!(erfc(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn normal_cdf_known_values() {
+        assert!(close(normal_cdf(0.0), 0.5));
+        assert!(close(normal_cdf(1.959_963_984_540_054), 0.975));
+        assert!(close(normal_cdf(-1.0), 0.158_655_253_931_457_05));
+    }
+
+    #[test]
+    fn normal_quantile_inverts_normal_cdf() {
+        assert_eq!(normal_quantile(0.5), 0.0);
+        assert!(close(normal_quantile(0.975), 1.959_963_984_540_054));
+        assert!(close(normal_quantile(0.001), -3.090_232_306_167_813_6));
+
+        // The upper tail of normal_cdf rounds to one, so only invert it for
+        // moderate z.
+        for &z in [-30.0, -8.0, -3.0, -1.0, -0.1, 0.2, 1.5, 4.0].iter() {
+            assert!((normal_quantile(normal_cdf(z)) - z).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn beta_inc_known_values() {
+        // I_x(1, 1) is the uniform cdf and I_x(a, 1) = x^a.
+        for &x in [0.0, 0.01, 0.3, 0.5, 0.99, 1.0].iter() {
+            assert!(close(beta_inc(1.0, 1.0, x), x));
+            assert!(close(beta_inc(2.5, 1.0, x), x.powf(2.5)));
+        }
+
+        // Symmetry about one half.
+        assert!(close(beta_inc(3.0, 3.0, 0.5), 0.5));
+        assert!(close(beta_inc(2.0, 5.0, 0.3) + beta_inc(5.0, 2.0, 0.7), 1.0));
+
+        // Binomial tail, P(X <= 3) for n = 10, p = 0.5, is I_0.5(7, 4).
+        assert!(close(beta_inc(7.0, 4.0, 0.5), 176.0 / 1024.0));
+    }
+}
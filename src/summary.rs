@@ -0,0 +1,265 @@
+//! Descriptive statistics of a sample.
+
+use std::cmp::Ordering;
+
+use ecdf::{Ecdf, EPSILON};
+use numeric::Numeric;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Summary of the descriptive statistics of a sample.
+///
+/// Quantiles, i.e. the median and quartiles, use the Nearest Rank method as
+/// for Ecdf::percentile. Statistics of weighted ECDFs, e.g. mixtures, are
+/// calculated using the sample weights.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Summary {
+    pub count: usize,
+    pub mean: f64,
+    /// Sample variance, i.e. with Bessel's correction. Zero for a sample of
+    /// size one.
+    pub variance: f64,
+    /// Sample skewness, i.e. the third central moment divided by the second
+    /// central moment to the power of 3/2. Zero for samples with no variation.
+    pub skewness: f64,
+    pub min: f64,
+    pub lower_quartile: f64,
+    pub median: f64,
+    pub upper_quartile: f64,
+    pub max: f64,
+    /// Interquartile range, the upper quartile less the lower quartile.
+    pub iqr: f64,
+    /// Median absolute deviation from the median.
+    pub mad: f64,
+}
+
+impl Summary {
+    /// Calculate the descriptive statistics summary for the sample of an ECDF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// let samples = vec!(9, 8, 7, 6, 5, 4, 3, 2, 1, 0);
+    /// let ecdf = ks::Ecdf::new(&samples);
+    /// let summary = ks::Summary::new(&ecdf);
+    ///
+    /// assert_eq!(summary.count, 10);
+    /// assert_eq!(summary.mean, 4.5);
+    /// assert_eq!(summary.median, 4.0);
+    /// assert_eq!(summary.iqr, 5.0);
+    /// ```
+    pub fn new<T: Numeric>(ecdf: &Ecdf<T>) -> Summary {
+        // Distinct sample values with their probability mass in the sample.
+        let mut count = 0;
+        let mut previous = 0.0;
+        let mut masses: Vec<(f64, f64)> = Vec::new();
+
+        for step in ecdf.steps() {
+            count += step.count;
+            masses.push((step.value.to_f64(), step.probability - previous));
+            previous = step.probability;
+        }
+
+        let mean: f64 = masses.iter().map(|&(x, p)| p * x).sum();
+        let moment = |k: i32| -> f64 { masses.iter().map(|&(x, p)| p * (x - mean).powi(k)).sum() };
+
+        let m2 = moment(2);
+        let m3 = moment(3);
+
+        let variance = if count > 1 {
+            m2 * count as f64 / (count - 1) as f64
+        } else {
+            0.0
+        };
+
+        let skewness = if m2 > 0.0 {
+            m3 / m2.powf(1.5)
+        } else {
+            0.0
+        };
+
+        let lower_quartile = ecdf.percentile(25).to_f64();
+        let median = ecdf.percentile(50).to_f64();
+        let upper_quartile = ecdf.percentile(75).to_f64();
+
+        // Median of the absolute deviations using the Nearest Rank method.
+        let mut deviations: Vec<(f64, f64)> = masses.iter()
+                                                    .map(|&(x, p)| ((x - median).abs(), p))
+                                                    .collect();
+        deviations.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+        let mut cumulative = 0.0;
+        let mut mad = deviations[deviations.len() - 1].0;
+        for &(deviation, p) in deviations.iter() {
+            cumulative += p;
+            if cumulative >= 0.5 - EPSILON {
+                mad = deviation;
+                break;
+            }
+        }
+
+        Summary {
+            count: count,
+            mean: mean,
+            variance: variance,
+            skewness: skewness,
+            min: ecdf.min().to_f64(),
+            lower_quartile: lower_quartile,
+            median: median,
+            upper_quartile: upper_quartile,
+            max: ecdf.max().to_f64(),
+            iqr: upper_quartile - lower_quartile,
+            mad: mad,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate quickcheck;
+    extern crate rand;
+
+    use self::quickcheck::{Arbitrary, Gen, QuickCheck, Testable, StdGen};
+    use std::cmp;
+    use super::Summary;
+    use ecdf::{Ecdf, percentile};
+
+    const EPSILON: f64 = 1e-6;
+
+    fn check<A: Testable>(f: A) {
+        let g = StdGen::new(rand::thread_rng(), usize::MAX);
+        QuickCheck::new().gen(g).quickcheck(f);
+    }
+
+    /// Wrapper for generating sample data with QuickCheck.
+    ///
+    /// Samples must be non-empty sequences of i64 values. Values are bounded
+    /// so that moments are calculated without overflow or catastrophic loss
+    /// of precision.
+    #[derive(Debug, Clone)]
+    struct Samples {
+        vec: Vec<i64>,
+    }
+
+    impl Arbitrary for Samples {
+        fn arbitrary<G: Gen>(g: &mut G) -> Samples {
+            // Limit size of generated sample set to 1024
+            let max = cmp::min(g.size(), 1024);
+
+            let size = g.gen_range(1, max);
+            let vec = (0..size).map(|_| g.gen_range(-1000, 1000)).collect();
+
+            Samples { vec: vec }
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Samples>> {
+            let vec: Vec<i64> = self.vec.clone();
+            let shrunk: Box<dyn Iterator<Item = Vec<i64>>> = vec.shrink();
+
+            Box::new(shrunk.filter(|v| !v.is_empty()).map(|v| Samples { vec: v }))
+        }
+    }
+
+    fn mean(xs: &[i64]) -> f64 {
+        xs.iter().map(|&x| x as f64).sum::<f64>() / xs.len() as f64
+    }
+
+    #[test]
+    fn count_is_sample_length() {
+        fn prop(xs: Samples) -> bool {
+            Summary::new(&Ecdf::new(&xs.vec)).count == xs.vec.len()
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn mean_is_sample_mean() {
+        fn prop(xs: Samples) -> bool {
+            let summary = Summary::new(&Ecdf::new(&xs.vec));
+
+            (summary.mean - mean(&xs.vec)).abs() < EPSILON
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn variance_is_sample_variance() {
+        fn prop(xs: Samples) -> bool {
+            let n = xs.vec.len();
+            let mean = mean(&xs.vec);
+            let sum_squares: f64 = xs.vec.iter().map(|&x| (x as f64 - mean).powi(2)).sum();
+            let expected = if n > 1 {
+                sum_squares / (n - 1) as f64
+            } else {
+                0.0
+            };
+
+            let summary = Summary::new(&Ecdf::new(&xs.vec));
+
+            (summary.variance - expected).abs() < EPSILON * expected.max(1.0)
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn skewness_is_negated_for_negated_sample() {
+        fn prop(xs: Samples) -> bool {
+            let negated: Vec<i64> = xs.vec.iter().map(|&x| -x).collect();
+
+            let skewness = Summary::new(&Ecdf::new(&xs.vec)).skewness;
+            let negated_skewness = Summary::new(&Ecdf::new(&negated)).skewness;
+
+            (skewness + negated_skewness).abs() < EPSILON
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn five_number_summary_is_increasing() {
+        fn prop(xs: Samples) -> bool {
+            let s = Summary::new(&Ecdf::new(&xs.vec));
+
+            s.min <= s.lower_quartile && s.lower_quartile <= s.median &&
+            s.median <= s.upper_quartile && s.upper_quartile <= s.max &&
+            s.iqr == s.upper_quartile - s.lower_quartile
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn mad_is_median_of_absolute_deviations() {
+        fn prop(xs: Samples) -> bool {
+            let median = percentile(&xs.vec, 50);
+            let deviations: Vec<i64> = xs.vec.iter().map(|&x| (x - median).abs()).collect();
+            let expected = percentile(&deviations, 50) as f64;
+
+            Summary::new(&Ecdf::new(&xs.vec)).mad == expected
+        }
+
+        check(prop as fn(Samples) -> bool);
+    }
+
+    #[test]
+    fn mixture_summary_weights_components() {
+        fn prop(xs: Samples, ys: Samples) -> bool {
+            let ecdf_xs = Ecdf::new(&xs.vec);
+            let ecdf_ys = Ecdf::new(&ys.vec);
+            let mixture = Ecdf::mixture(&[(&ecdf_xs, 0.25), (&ecdf_ys, 0.75)]);
+
+            let expected = 0.25 * mean(&xs.vec) + 0.75 * mean(&ys.vec);
+
+            (Summary::new(&mixture).mean - expected).abs() < EPSILON
+        }
+
+        check(prop as fn(Samples, Samples) -> bool);
+    }
+}
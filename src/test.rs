@@ -1,16 +1,430 @@
-//! Two Sample Kolmogorov-Smirnov Test
+//! One and Two Sample Kolmogorov-Smirnov Tests
 
-use std::cmp::{min, Ord, Ordering};
+use std::cmp::{min, Ord};
+use std::fmt;
+use std::iter::Peekable;
+use std::str::FromStr;
 
-/// Two sample test result.
+use bootstrap::{test_block_bootstrap, BlockBootstrap};
+use distributions::{ContinuousDistribution, DiscreteDistribution};
+use ecdf::{Ecdf, Steps};
+use numeric::{Numeric, OrderableF64};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// One or two sample test result.
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct TestResult {
     pub is_rejected: bool,
     pub statistic: f64,
     pub reject_probability: f64,
+    /// Probability of a test statistic at least as extreme under the null
+    /// hypothesis, i.e. one minus the reject probability.
+    pub p_value: f64,
     pub critical_value: f64,
     pub confidence: f64,
 }
 
+/// Outcome of a statistical test, common to the results of the tests so they
+/// can be corrected, tabulated and reported alike.
+pub trait TestOutcome {
+    /// Return the test statistic.
+    fn statistic(&self) -> f64;
+
+    /// Return the probability of a test statistic at least as extreme under
+    /// the null hypothesis.
+    fn p_value(&self) -> f64;
+
+    /// Return whether the null hypothesis is rejected at the confidence level.
+    fn is_rejected(&self) -> bool;
+}
+
+impl TestOutcome for TestResult {
+    fn statistic(&self) -> f64 {
+        self.statistic
+    }
+
+    fn p_value(&self) -> f64 {
+        self.p_value
+    }
+
+    fn is_rejected(&self) -> bool {
+        self.is_rejected
+    }
+}
+
+/// Configured test of whether two samples are from the same distribution.
+///
+/// Implemented by `KsTest` and by other tests so they are interchangeable in
+/// batches of tests, e.g. `pairwise::pairwise_with`.
+pub trait TwoSampleTest {
+    type Outcome: TestOutcome;
+
+    /// Return the name of the test, e.g. `kolmogorov-smirnov`.
+    fn name(&self) -> &'static str;
+
+    /// Perform the test on given samples.
+    fn test<T: Ord + Clone>(&self, xs: &[T], ys: &[T]) -> Self::Outcome;
+}
+
+/// Configured test of whether a sample is from a given distribution.
+pub trait OneSampleTest {
+    type Outcome: TestOutcome;
+
+    /// Return the name of the test, e.g. `kolmogorov-smirnov`.
+    fn name(&self) -> &'static str;
+
+    /// Perform the test on a given sample.
+    fn test<T: Numeric>(&self, xs: &[T]) -> Self::Outcome;
+}
+
+/// Alternative hypothesis of a two sample test.
+///
+/// The one-sided alternatives follow the convention of R's `ks.test`, i.e.
+/// they refer to the cumulative distribution functions of the samples rather
+/// than the sample values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "kebab-case"))]
+pub enum Alternative {
+    /// The samples are from different distributions. The test statistic is
+    /// the maximum of |ECDF(xs) - ECDF(ys)|.
+    TwoSided,
+    /// The distribution function of xs lies below that of ys, i.e. xs tends
+    /// to be larger. The test statistic is the maximum of ECDF(ys) - ECDF(xs).
+    Less,
+    /// The distribution function of xs lies above that of ys, i.e. xs tends
+    /// to be smaller. The test statistic is the maximum of ECDF(xs) - ECDF(ys).
+    Greater,
+}
+
+impl Alternative {
+    /// Difference between the ECDF values of xs and ys maximised by the test
+    /// statistic.
+    fn difference(&self, value_xs: f64, value_ys: f64) -> f64 {
+        match *self {
+            Alternative::TwoSided => (value_xs - value_ys).abs(),
+            Alternative::Less => value_ys - value_xs,
+            Alternative::Greater => value_xs - value_ys,
+        }
+    }
+}
+
+impl fmt::Display for Alternative {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Alternative::TwoSided => "two-sided".fmt(f),
+            Alternative::Less => "less".fmt(f),
+            Alternative::Greater => "greater".fmt(f),
+        }
+    }
+}
+
+impl FromStr for Alternative {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Alternative, String> {
+        match s {
+            "two-sided" => Ok(Alternative::TwoSided),
+            "less" => Ok(Alternative::Less),
+            "greater" => Ok(Alternative::Greater),
+            _ => Err("expected two-sided, less or greater".to_string()),
+        }
+    }
+}
+
+/// Method of calculating the distribution of the test statistic under the
+/// null hypothesis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "kebab-case"))]
+pub enum Method {
+    /// Limiting Kolmogorov distribution, with the Stephens correction for
+    /// finite samples in the two-sided case.
+    Asymptotic,
+    /// Exact distribution calculated by counting the lattice paths of the
+    /// merged samples. This takes time proportional to the product of the
+    /// sample sizes so is intended for smaller samples. The samples are
+    /// assumed to have no ties, otherwise the test is conservative.
+    Exact,
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Method::Asymptotic => "asymptotic".fmt(f),
+            Method::Exact => "exact".fmt(f),
+        }
+    }
+}
+
+impl FromStr for Method {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Method, String> {
+        match s {
+            "asymptotic" => Ok(Method::Asymptotic),
+            "exact" => Ok(Method::Exact),
+            _ => Err("expected asymptotic or exact".to_string()),
+        }
+    }
+}
+
+/// Handling of NaN values in f64 samples, which are not orderable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NanPolicy {
+    /// Panic if a sample contains NaN.
+    Panic,
+    /// Leave NaN values out of the samples.
+    Omit,
+}
+
+/// Two sample Kolmogorov-Smirnov test configured by chaining options, e.g.
+/// `KsTest::two_sample().confidence(0.99).run(&xs, &ys)`.
+///
+/// The options default to a confidence level of 0.95, the two-sided
+/// alternative, the asymptotic method and panicking on NaN.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::test::{Alternative, Method, NanPolicy};
+/// use ks::KsTest;
+///
+/// let xs = vec!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12);
+/// let ys = vec!(8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20);
+///
+/// let result = KsTest::two_sample()
+///     .confidence(0.99)
+///     .alternative(Alternative::Greater)
+///     .method(Method::Exact)
+///     .run(&xs, &ys);
+/// assert!(result.is_rejected);
+/// assert_eq!(result.confidence, 0.99);
+///
+/// let xs = vec!(0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, std::f64::NAN);
+/// let ys = vec!(0.5, 1.5, 2.5, 3.5, 4.5, 5.5, 6.5, 7.5);
+///
+/// let result = KsTest::two_sample().nan_policy(NanPolicy::Omit).run_f64(&xs, &ys);
+/// assert!(!result.is_rejected);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KsTest {
+    confidence: f64,
+    alternative: Alternative,
+    method: Method,
+    block_bootstrap: Option<BlockBootstrap>,
+    nan_policy: NanPolicy,
+}
+
+impl KsTest {
+    /// Configure a two sample test with the default options.
+    pub fn two_sample() -> KsTest {
+        KsTest {
+            confidence: 0.95,
+            alternative: Alternative::TwoSided,
+            method: Method::Asymptotic,
+            block_bootstrap: None,
+            nan_policy: NanPolicy::Panic,
+        }
+    }
+
+    /// Set the confidence level at which the test rejects.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic if the confidence level is not strictly
+    /// between zero and one.
+    pub fn confidence(mut self, confidence: f64) -> KsTest {
+        assert!(0.0 < confidence && confidence < 1.0);
+        self.confidence = confidence;
+        self
+    }
+
+    /// Set the alternative hypothesis.
+    pub fn alternative(mut self, alternative: Alternative) -> KsTest {
+        self.alternative = alternative;
+        self
+    }
+
+    /// Set the method of calculating the distribution of the test statistic.
+    pub fn method(mut self, method: Method) -> KsTest {
+        self.method = method;
+        self
+    }
+
+    /// Calculate the p-value by block bootstrap instead of the method, for
+    /// serially dependent samples. See `bootstrap::test_block_bootstrap`.
+    pub fn block_bootstrap(mut self, block_bootstrap: BlockBootstrap) -> KsTest {
+        self.block_bootstrap = Some(block_bootstrap);
+        self
+    }
+
+    /// Set the handling of NaN values by `run_f64`.
+    pub fn nan_policy(mut self, nan_policy: NanPolicy) -> KsTest {
+        self.nan_policy = nan_policy;
+        self
+    }
+
+    /// Perform the test on given samples.
+    ///
+    /// The samples must have length > 7 elements for the test to be valid.
+    ///
+    /// # Panics
+    ///
+    /// There are assertion panics if either sequence has <= 7 elements, or
+    /// if the block bootstrap is invalid for the samples.
+    #[allow(clippy::len_zero)]
+    pub fn run<T: Ord + Clone>(&self, xs: &[T], ys: &[T]) -> TestResult {
+        assert!(xs.len() > 0 && ys.len() > 0);
+
+        // Only supports samples of size > 7.
+        assert!(xs.len() > 7 && ys.len() > 7);
+
+        match self.block_bootstrap {
+            Some(ref block_bootstrap) => {
+                test_block_bootstrap(xs, ys, self.confidence, self.alternative, block_bootstrap)
+            }
+            None => self.run_ecdfs(&Ecdf::new(xs), &Ecdf::new(ys)),
+        }
+    }
+
+    /// Perform the test on the ECDFs of the samples, e.g. weighted mixtures
+    /// built by `Ecdf::mixture`, by `test_ecdfs`.
+    ///
+    /// The sample sizes of the test are the numbers of samples of the ECDFs.
+    ///
+    /// # Panics
+    ///
+    /// There are assertion panics if either ECDF has <= 7 samples, or if the
+    /// block bootstrap is set, as it resamples the samples themselves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate kolmogorov_smirnov as ks;
+    ///
+    /// use ks::{Ecdf, KsTest};
+    ///
+    /// let early = Ecdf::new(&vec!(0, 1, 2, 3, 4, 5, 6, 7));
+    /// let late = Ecdf::new(&vec!(4, 5, 6, 7, 8, 9, 10, 11));
+    /// let ys = Ecdf::new(&vec!(4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15));
+    ///
+    /// // Weight the later batch three times as heavily as the earlier one.
+    /// let xs = Ecdf::mixture(&[(&early, 1.0), (&late, 3.0)]);
+    ///
+    /// let result = KsTest::two_sample().run_ecdfs(&xs, &ys);
+    /// assert!((result.statistic - 1.0 / 3.0).abs() < 1e-12);
+    /// ```
+    pub fn run_ecdfs<T: Ord + Clone>(&self, ecdf_xs: &Ecdf<T>, ecdf_ys: &Ecdf<T>) -> TestResult {
+        assert!(self.block_bootstrap.is_none());
+
+        test_ecdfs(ecdf_xs, ecdf_ys, self.confidence, self.alternative, self.method)
+    }
+
+    /// Perform the test on given f64 samples, handling NaN values by the NaN
+    /// policy.
+    ///
+    /// # Panics
+    ///
+    /// As for `run`, with the samples after leaving out any NaN values, and
+    /// if a sample contains NaN with the panic policy.
+    pub fn run_f64(&self, xs: &[f64], ys: &[f64]) -> TestResult {
+        let orderable = |samples: &[f64]| -> Vec<OrderableF64> {
+            samples.iter()
+                .filter(|x| self.nan_policy == NanPolicy::Panic || !x.is_nan())
+                .map(|&x| OrderableF64::new(x))
+                .collect()
+        };
+
+        self.run(&orderable(xs), &orderable(ys))
+    }
+}
+
+impl TwoSampleTest for KsTest {
+    type Outcome = TestResult;
+
+    fn name(&self) -> &'static str {
+        "kolmogorov-smirnov"
+    }
+
+    fn test<T: Ord + Clone>(&self, xs: &[T], ys: &[T]) -> TestResult {
+        self.run(xs, ys)
+    }
+}
+
+/// One sample Kolmogorov-Smirnov test of a continuous distribution configured
+/// by chaining options as for `KsTest`.
+///
+/// The options default to a confidence level of 0.95 and the two-sided
+/// alternative.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::distributions::Uniform;
+/// use ks::test::{OneSampleKsTest, OneSampleTest, TestOutcome};
+/// use ks::OrderableF64;
+///
+/// let xs: Vec<OrderableF64> = (0..100).map(|i| OrderableF64::new(i as f64 / 100.0)).collect();
+/// let uniform = Uniform::new(0.0, 1.0);
+///
+/// let ks_test = OneSampleKsTest::new(&uniform).confidence(0.99);
+/// assert_eq!(ks_test.name(), "kolmogorov-smirnov");
+/// assert!(!ks_test.test(&xs).is_rejected());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct OneSampleKsTest<'a, D: ?Sized + 'a> {
+    distribution: &'a D,
+    confidence: f64,
+    alternative: Alternative,
+}
+
+impl<'a, D: ContinuousDistribution + ?Sized> OneSampleKsTest<'a, D> {
+    /// Configure a one sample test of the distribution with the default
+    /// options.
+    pub fn new(distribution: &'a D) -> OneSampleKsTest<'a, D> {
+        OneSampleKsTest {
+            distribution: distribution,
+            confidence: 0.95,
+            alternative: Alternative::TwoSided,
+        }
+    }
+
+    /// Set the confidence level at which the test rejects.
+    ///
+    /// # Panics
+    ///
+    /// There is an assertion panic if the confidence level is not strictly
+    /// between zero and one.
+    pub fn confidence(mut self, confidence: f64) -> OneSampleKsTest<'a, D> {
+        assert!(0.0 < confidence && confidence < 1.0);
+        self.confidence = confidence;
+        self
+    }
+
+    /// Set the alternative hypothesis, see `test_one_sample`.
+    pub fn alternative(mut self, alternative: Alternative) -> OneSampleKsTest<'a, D> {
+        self.alternative = alternative;
+        self
+    }
+}
+
+impl<'a, D: ContinuousDistribution + ?Sized> OneSampleTest for OneSampleKsTest<'a, D> {
+    type Outcome = TestResult;
+
+    fn name(&self) -> &'static str {
+        "kolmogorov-smirnov"
+    }
+
+    fn test<T: Numeric>(&self, xs: &[T]) -> TestResult {
+        test_one_sample(xs, self.distribution, self.confidence, self.alternative)
+    }
+}
+
 /// Perform a two sample Kolmogorov-Smirnov test on given samples.
 ///
 /// The samples must have length > 7 elements for the test to be valid.
@@ -36,53 +450,102 @@ pub struct TestResult {
 /// }
 /// ```
 pub fn test<T: Ord + Clone>(xs: &[T], ys: &[T], confidence: f64) -> TestResult {
-    assert!(xs.len() > 0 && ys.len() > 0);
+    KsTest::two_sample().confidence(confidence).run(xs, ys)
+}
+
+/// Perform a two sample Kolmogorov-Smirnov test on given samples with the
+/// specified alternative hypothesis and method of calculating the
+/// distribution of the test statistic.
+///
+/// The samples must have length > 7 elements for the test to be valid.
+///
+/// # Panics
+///
+/// There are assertion panics if either sequence has <= 7 elements.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::test::{Alternative, Method};
+///
+/// let xs = vec!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12);
+/// let ys = vec!(8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20);
+///
+/// // The distribution function of xs lies above that of ys.
+/// let result = ks::test::test_with(&xs, &ys, 0.95, Alternative::Greater, Method::Exact);
+/// assert!(result.is_rejected);
+///
+/// let result = ks::test::test_with(&xs, &ys, 0.95, Alternative::Less, Method::Exact);
+/// assert!(!result.is_rejected);
+/// ```
+pub fn test_with<T: Ord + Clone>(xs: &[T],
+                                 ys: &[T],
+                                 confidence: f64,
+                                 alternative: Alternative,
+                                 method: Method)
+                                 -> TestResult {
+    KsTest::two_sample()
+        .confidence(confidence)
+        .alternative(alternative)
+        .method(method)
+        .run(xs, ys)
+}
+
+/// Perform a two sample Kolmogorov-Smirnov test on the ECDFs of the samples,
+/// e.g. to reuse each ECDF in several tests.
+///
+/// The samples must have length > 7 elements for the test to be valid.
+///
+/// # Panics
+///
+/// There are assertion panics if either sample has <= 7 elements.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::test::{Alternative, Method};
+///
+/// let xs = ks::Ecdf::new(&vec!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12));
+/// let ys = ks::Ecdf::new(&vec!(12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0));
+///
+/// let result = ks::test::test_ecdfs(&xs, &ys, 0.95, Alternative::TwoSided, Method::Asymptotic);
+/// assert!(!result.is_rejected);
+/// ```
+pub fn test_ecdfs<T: Ord + Clone>(ecdf_xs: &Ecdf<T>,
+                                  ecdf_ys: &Ecdf<T>,
+                                  confidence: f64,
+                                  alternative: Alternative,
+                                  method: Method)
+                                  -> TestResult {
     assert!(0.0 < confidence && confidence < 1.0);
 
+    let (n1, n2) = (ecdf_xs.num_samples(), ecdf_ys.num_samples());
+
     // Only supports samples of size > 7.
-    assert!(xs.len() > 7 && ys.len() > 7);
+    assert!(n1 > 7 && n2 > 7);
 
-    let statistic = calculate_statistic(xs, ys);
-    let critical_value = calculate_critical_value(xs.len(), ys.len(), confidence);
+    let statistic = max_difference(ecdf_xs, ecdf_ys, alternative).statistic;
+    let critical_value = search_critical_value(confidence, |d| {
+        reject_probability(d, n1, n2, alternative, method)
+    });
 
-    let reject_probability = calculate_reject_probability(statistic, xs.len(), ys.len());
+    let reject_probability = reject_probability(statistic, n1, n2, alternative, method);
     let is_rejected = reject_probability > confidence;
 
     TestResult {
         is_rejected: is_rejected,
         statistic: statistic,
         reject_probability: reject_probability,
+        p_value: 1.0 - reject_probability,
         critical_value: critical_value,
         confidence: confidence,
     }
 }
 
-/// Wrapper type for f64 to implement Ord and make usable with test.
-#[derive(PartialEq, Clone)]
-struct OrderableF64 {
-    val: f64,
-}
-
-impl OrderableF64 {
-    fn new(val: f64) -> OrderableF64 {
-        OrderableF64 { val: val }
-    }
-}
-
-impl Eq for OrderableF64 {}
-
-impl PartialOrd for OrderableF64 {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.val.partial_cmp(&other.val)
-    }
-}
-
-impl Ord for OrderableF64 {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.val.partial_cmp(&other.val).unwrap()
-    }
-}
-
 /// Perform a two sample Kolmogorov-Smirnov test on given f64 samples.
 ///
 /// This is necessary because f64 does not implement Ord in Rust as some
@@ -114,88 +577,360 @@ impl Ord for OrderableF64 {
 /// }
 /// ```
 pub fn test_f64(xs: &[f64], ys: &[f64], confidence: f64) -> TestResult {
-    let xs: Vec<OrderableF64> = xs.iter().map(|&f| OrderableF64::new(f)).collect();
-    let ys: Vec<OrderableF64> = ys.iter().map(|&f| OrderableF64::new(f)).collect();
+    KsTest::two_sample().confidence(confidence).run_f64(xs, ys)
+}
 
-    test(&xs, &ys, confidence)
+/// Perform a one sample Kolmogorov-Smirnov test of whether a sample is from a
+/// continuous distribution.
+///
+/// The alternatives refer to the ECDF of the sample and the distribution
+/// function, e.g. `Alternative::Greater` is that the ECDF lies above the
+/// distribution function. The p-value is calculated from the limiting
+/// Kolmogorov distribution, with the Stephens correction for finite samples in
+/// the two-sided case.
+///
+/// The sample must have length > 7 elements for the test to be valid.
+///
+/// # Panics
+///
+/// There are assertion panics if the sample has <= 7 elements or if the
+/// confidence level is not strictly between zero and one.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::distributions::{Normal, Uniform};
+/// use ks::test::Alternative;
+/// use ks::OrderableF64;
+///
+/// let xs: Vec<OrderableF64> = (0..100).map(|i| OrderableF64::new(i as f64 / 100.0)).collect();
+///
+/// let result = ks::test::test_one_sample(&xs, &Uniform::new(0.0, 1.0), 0.95, Alternative::TwoSided);
+/// assert!(!result.is_rejected);
+///
+/// let result = ks::test::test_one_sample(&xs, &Normal::new(0.0, 1.0), 0.95, Alternative::TwoSided);
+/// assert!(result.is_rejected);
+/// ```
+pub fn test_one_sample<T, D>(xs: &[T],
+                             distribution: &D,
+                             confidence: f64,
+                             alternative: Alternative)
+                             -> TestResult
+    where T: Numeric,
+          D: ContinuousDistribution + ?Sized
+{
+    let cdf = |x| distribution.cdf(x);
+    test_one_sample_cdf(xs, &cdf, &cdf, confidence, alternative)
 }
 
-/// Calculate the test statistic for the two sample Kolmogorov-Smirnov test.
+/// Perform a one sample Kolmogorov-Smirnov test of whether a sample is from a
+/// discrete distribution.
+///
+/// The test statistic accounts for the jumps of the distribution function, but
+/// the p-value is calculated as for a continuous distribution, so the test is
+/// conservative, i.e. rejects less often than the confidence level implies.
+///
+/// The sample must have length > 7 elements for the test to be valid.
+///
+/// # Panics
+///
+/// There are assertion panics if the sample has <= 7 elements or if the
+/// confidence level is not strictly between zero and one.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
 ///
-/// The test statistic is the maximum vertical distance between the ECDFs of
-/// the two samples.
-fn calculate_statistic<T: Ord + Clone>(xs: &[T], ys: &[T]) -> f64 {
-    let n = xs.len();
-    let m = ys.len();
+/// use ks::distributions::Poisson;
+/// use ks::test::Alternative;
+///
+/// let xs = vec!(0, 1, 1, 2, 2, 2, 3, 3, 4, 5);
+///
+/// let result = ks::test::test_one_sample_discrete(&xs, &Poisson::new(2.5), 0.95, Alternative::TwoSided);
+/// assert!(!result.is_rejected);
+/// ```
+pub fn test_one_sample_discrete<T, D>(xs: &[T],
+                                      distribution: &D,
+                                      confidence: f64,
+                                      alternative: Alternative)
+                                      -> TestResult
+    where T: Numeric,
+          D: DiscreteDistribution + ?Sized
+{
+    // P(X < x) is the cdf of the largest integer below x.
+    test_one_sample_cdf(xs,
+                        &|x| distribution.cdf(x),
+                        &|x: f64| distribution.cdf(x.ceil() - 1.0),
+                        confidence,
+                        alternative)
+}
 
-    assert!(n > 0 && m > 0);
+/// Perform a one sample test given the distribution function, P(X <= x), and
+/// its left limit, P(X < x).
+fn test_one_sample_cdf<T: Numeric>(xs: &[T],
+                                   cdf: &dyn Fn(f64) -> f64,
+                                   cdf_below: &dyn Fn(f64) -> f64,
+                                   confidence: f64,
+                                   alternative: Alternative)
+                                   -> TestResult {
+    assert!(0.0 < confidence && confidence < 1.0);
 
-    let mut xs = xs.to_vec();
-    let mut ys = ys.to_vec();
+    // Only supports samples of size > 7.
+    assert!(xs.len() > 7);
 
-    // xs and ys must be sorted for the stepwise ECDF calculations to work.
-    xs.sort();
-    ys.sort();
+    let ecdf = Ecdf::new(xs);
+    let n = ecdf.num_samples();
 
-    // The current value testing for ECDF difference. Sweeps up through elements
-    // present in xs and ys.
-    let mut current: &T;
+    let statistic = max_difference_to_cdf(&ecdf, cdf, cdf_below, alternative).statistic;
 
-    // i, j index the first values in xs and ys that are greater than current.
-    let mut i = 0;
-    let mut j = 0;
+    let critical_value = search_critical_value(confidence, |d| {
+        one_sample_reject_probability(d, n, alternative)
+    });
 
-    // ecdf_xs, ecdf_ys always hold the ECDF(current) of xs and ys.
-    let mut ecdf_xs = 0.0;
-    let mut ecdf_ys = 0.0;
+    let reject_probability = one_sample_reject_probability(statistic, n, alternative);
+    let is_rejected = reject_probability > confidence;
 
-    // The test statistic value computed over values <= current.
-    let mut statistic = 0.0;
+    TestResult {
+        is_rejected: is_rejected,
+        statistic: statistic,
+        reject_probability: reject_probability,
+        p_value: 1.0 - reject_probability,
+        critical_value: critical_value,
+        confidence: confidence,
+    }
+}
 
-    while i < n && j < m {
-        // Advance i through duplicate samples in xs.
-        let x_i = &xs[i];
-        while i + 1 < n && *x_i == xs[i + 1] {
-            i += 1;
+/// Calculate the maximum of the difference between an ECDF and a distribution
+/// function for an alternative hypothesis, i.e. the one sample test statistic,
+/// and where it occurs, given the distribution function and its left limit.
+fn max_difference_to_cdf<T: Numeric>(ecdf: &Ecdf<T>,
+                                     cdf: &dyn Fn(f64) -> f64,
+                                     cdf_below: &dyn Fn(f64) -> f64,
+                                     alternative: Alternative)
+                                     -> Distance<T> {
+    // The ECDF is constant between steps, so the differences are maximised at
+    // a step or just below it.
+    let mut statistic = 0.0;
+    let mut location = ecdf.min();
+    let mut previous = 0.0;
+
+    for step in ecdf.steps() {
+        let x = step.value.to_f64();
+        let above = step.probability - cdf(x);
+        let below = cdf_below(x) - previous;
+
+        let diff = match alternative {
+            Alternative::TwoSided => above.max(below),
+            Alternative::Less => below,
+            Alternative::Greater => above,
+        };
+        if diff > statistic {
+            statistic = diff;
+            location = step.value;
         }
+        previous = step.probability;
+    }
 
-        // Advance j through duplicate samples in ys.
-        let y_j = &ys[j];
-        while j + 1 < m && *y_j == ys[j + 1] {
-            j += 1;
-        }
+    Distance {
+        statistic: statistic,
+        location: location,
+    }
+}
+
+/// Calculate the maximum vertical distance between an ECDF and the
+/// distribution function of a continuous distribution, i.e. the two-sided one
+/// sample test statistic, and the sample value where it occurs.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// use ks::distributions::Uniform;
+///
+/// let xs = ks::Ecdf::new(&vec!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10));
+///
+/// let distance = ks::test::distance_to_distribution(&xs, &Uniform::new(0.0, 20.0));
+/// assert_eq!(distance.statistic, 0.5);
+/// assert_eq!(distance.location, 10);
+/// ```
+pub fn distance_to_distribution<T, D>(ecdf: &Ecdf<T>, distribution: &D) -> Distance<T>
+    where T: Numeric,
+          D: ContinuousDistribution + ?Sized
+{
+    let cdf = |x| distribution.cdf(x);
+    max_difference_to_cdf(ecdf, &cdf, &cdf, Alternative::TwoSided)
+}
 
-        // Step to the next sample value in the ECDF sweep from low to high.
-        current = min(x_i, y_j);
+/// Calculate the reject probability of a one sample test statistic from the
+/// limiting distribution for the alternative hypothesis.
+fn one_sample_reject_probability(statistic: f64, n: usize, alternative: Alternative) -> f64 {
+    let n = n as f64;
 
-        // Update invariant conditions for i, j, ecdf_xs, and ecdf_ys.
-        if current == x_i {
-            ecdf_xs = (i + 1) as f64 / n as f64;
-            i += 1;
-        }
-        if current == y_j {
-            ecdf_ys = (j + 1) as f64 / m as f64;
-            j += 1;
+    match alternative {
+        Alternative::TwoSided => {
+            let factor = n.sqrt();
+            let term = (factor + 0.12 + 0.11 / factor) * statistic;
+            1.0 - probability_kolmogorov_smirnov(term)
         }
+        _ => 1.0 - (-2.0 * n * statistic * statistic).exp(),
+    }
+}
+
+/// Maximum vertical distance between two ECDFs.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Distance<T> {
+    /// The distance, i.e. the two sample test statistic.
+    pub statistic: f64,
+    /// The lowest sample value at which the distance is attained.
+    pub location: T,
+}
+
+/// Calculate the maximum vertical distance between two ECDFs and the sample
+/// value where it occurs by sweeping up through their steps together.
+///
+/// # Examples
+///
+/// ```
+/// extern crate kolmogorov_smirnov as ks;
+///
+/// let xs = ks::Ecdf::new(&vec!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9));
+/// let ys = ks::Ecdf::new(&vec!(4, 5, 6, 7, 8, 9, 10, 11, 12, 13));
+///
+/// let distance = ks::test::distance(&xs, &ys);
+/// assert_eq!(distance.statistic, 0.4);
+/// assert_eq!(distance.location, 3);
+/// ```
+pub fn distance<T: Ord + Clone>(ecdf_xs: &Ecdf<T>, ecdf_ys: &Ecdf<T>) -> Distance<T> {
+    max_difference(ecdf_xs, ecdf_ys, Alternative::TwoSided)
+}
 
+/// Calculate the maximum of the difference between two ECDFs for an
+/// alternative hypothesis, i.e. the test statistic, and where it occurs.
+pub(crate) fn max_difference<T: Ord + Clone>(ecdf_xs: &Ecdf<T>,
+                                             ecdf_ys: &Ecdf<T>,
+                                             alternative: Alternative)
+                                             -> Distance<T> {
+    let mut steps = merged_steps(ecdf_xs, ecdf_ys);
+
+    // The test statistic value computed over values <= current, and the
+    // first value where it is attained.
+    let mut statistic = 0.0;
+    let mut location = min(ecdf_xs.min(), ecdf_ys.min());
+
+    while let Some(step) = steps.next() {
         // Update invariant conditions for the test statistic.
-        let diff = (ecdf_xs - ecdf_ys).abs();
+        let diff = alternative.difference(step.probability_xs, step.probability_ys);
         if diff > statistic {
             statistic = diff;
+            location = step.value;
+        }
+
+        // Don't need to walk the rest of the steps because one of the ecdfs
+        // is already one and the other will be increasing up to one. This
+        // means the difference will be monotonically approaching zero, so we
+        // have our test statistic value already.
+        if steps.is_either_exhausted() {
+            break;
         }
     }
 
-    // Don't need to walk the rest of the samples because one of the ecdfs is
-    // already one and the other will be increasing up to one. This means the
-    // difference will be monotonically decreasing, so we have our test
-    // statistic value already.
+    Distance {
+        statistic: statistic,
+        location: location,
+    }
+}
+
+/// Step of the sweep over the merged samples of two ECDFs from low to high
+/// sample values.
+pub(crate) struct MergedStep<T> {
+    /// Sample value at which either ECDF steps.
+    pub value: T,
+    /// ECDF values of xs and ys at the sample value.
+    pub probability_xs: f64,
+    pub probability_ys: f64,
+    /// Number of samples of xs and ys equal to the sample value.
+    pub count_xs: usize,
+    pub count_ys: usize,
+}
+
+/// Iterator over the merged steps of two ECDFs.
+///
+/// Constructed using merged_steps.
+pub(crate) struct MergedSteps<'a, T: 'a + Ord + Clone> {
+    steps_xs: Peekable<Steps<'a, T>>,
+    steps_ys: Peekable<Steps<'a, T>>,
+    // probability_xs, probability_ys always hold the ECDF(current) of xs and
+    // ys where current is the sample value of the latest step taken.
+    probability_xs: f64,
+    probability_ys: f64,
+}
+
+impl<'a, T: Ord + Clone> MergedSteps<'a, T> {
+    /// Return whether every step of either ECDF has been taken.
+    pub fn is_either_exhausted(&mut self) -> bool {
+        self.steps_xs.peek().is_none() || self.steps_ys.peek().is_none()
+    }
+}
+
+impl<'a, T: Ord + Clone> Iterator for MergedSteps<'a, T> {
+    type Item = MergedStep<T>;
+
+    fn next(&mut self) -> Option<MergedStep<T>> {
+        // Step to the next sample value in the ECDF sweep from low to high,
+        // updating the ECDF values of either sample with a step there.
+        let current = match (self.steps_xs.peek(), self.steps_ys.peek()) {
+            (Some(x), Some(y)) => min(&x.value, &y.value).clone(),
+            (Some(x), None) => x.value.clone(),
+            (None, Some(y)) => y.value.clone(),
+            (None, None) => return None,
+        };
+
+        let mut count_xs = 0;
+        if self.steps_xs.peek().is_some_and(|x| x.value == current) {
+            let step = self.steps_xs.next().unwrap();
+            self.probability_xs = step.probability;
+            count_xs = step.count;
+        }
+
+        let mut count_ys = 0;
+        if self.steps_ys.peek().is_some_and(|y| y.value == current) {
+            let step = self.steps_ys.next().unwrap();
+            self.probability_ys = step.probability;
+            count_ys = step.count;
+        }
+
+        Some(MergedStep {
+            value: current,
+            probability_xs: self.probability_xs,
+            probability_ys: self.probability_ys,
+            count_xs: count_xs,
+            count_ys: count_ys,
+        })
+    }
+}
 
-    statistic
+/// Sweep the merged samples of two ECDFs from low to high sample values,
+/// stepping at each distinct value of either sample.
+pub(crate) fn merged_steps<'a, T: Ord + Clone>(ecdf_xs: &'a Ecdf<T>,
+                                               ecdf_ys: &'a Ecdf<T>)
+                                               -> MergedSteps<'a, T> {
+    MergedSteps {
+        steps_xs: ecdf_xs.steps().peekable(),
+        steps_ys: ecdf_ys.steps().peekable(),
+        probability_xs: 0.0,
+        probability_ys: 0.0,
+    }
 }
 
 /// Calculate the probability that the null hypothesis is false for a two sample
 /// Kolmogorov-Smirnov test. Can only reject the null hypothesis if this
 /// evidence exceeds the confidence level required.
+#[allow(clippy::manual_range_contains)]
 fn calculate_reject_probability(statistic: f64, n1: usize, n2: usize) -> f64 {
     // Only supports samples of size > 7.
     assert!(n1 > 7 && n2 > 7);
@@ -234,6 +969,13 @@ pub fn calculate_critical_value(n1: usize, n2: usize, confidence: f64) -> f64 {
     // Only supports samples of size > 7.
     assert!(n1 > 7 && n2 > 7);
 
+    search_critical_value(confidence,
+                          |statistic| calculate_reject_probability(statistic, n1, n2))
+}
+
+/// Binary search for the smallest test statistic with reject probability
+/// exceeding the confidence level, given the reject probability function.
+fn search_critical_value<F: Fn(f64) -> f64>(confidence: f64, reject_probability: F) -> f64 {
     // The test statistic is between zero and one so can binary search quickly
     // for the critical value.
     let mut low = 0.0;
@@ -245,9 +987,8 @@ pub fn calculate_critical_value(n1: usize, n2: usize, confidence: f64) -> f64 {
         }
 
         let mid = low + (high - low) / 2.0;
-        let reject_probability = calculate_reject_probability(mid, n1, n2);
 
-        if reject_probability > confidence {
+        if reject_probability(mid) > confidence {
             // Maintain invariant that reject_probability(high) > confidence.
             high = mid;
         } else {
@@ -256,10 +997,64 @@ pub fn calculate_critical_value(n1: usize, n2: usize, confidence: f64) -> f64 {
         }
     }
 
-    panic!("No convergence in calculate_critical_value({}, {}, {}).",
-           n1,
-           n2,
-           confidence);
+    panic!("No convergence in search_critical_value({}).", confidence);
+}
+
+/// Calculate the reject probability of a test statistic for an alternative
+/// hypothesis and method.
+fn reject_probability(statistic: f64,
+                      n1: usize,
+                      n2: usize,
+                      alternative: Alternative,
+                      method: Method)
+                      -> f64 {
+    match (method, alternative) {
+        (Method::Asymptotic, Alternative::TwoSided) => {
+            calculate_reject_probability(statistic, n1, n2)
+        }
+        (Method::Asymptotic, _) => {
+            // Limiting distribution of the one-sided statistic, P(D > d) =
+            // exp(-2 n d^2) with n the effective sample size.
+            let n = (n1 * n2) as f64 / (n1 + n2) as f64;
+            1.0 - (-2.0 * n * statistic * statistic).exp()
+        }
+        (Method::Exact, _) => exact_reject_probability(statistic, n1, n2, alternative),
+    }
+}
+
+/// Calculate the exact probability that the test statistic is less than the
+/// observed value under the null hypothesis.
+///
+/// Under the null hypothesis every ordering of the merged samples is equally
+/// likely. Each ordering is a lattice path from (0, 0) to (n1, n2) and the
+/// statistic is less than the observed value if the path stays within the
+/// corresponding band around the diagonal. The probability of the paths
+/// within the band is accumulated row by row, normalising as it goes so the
+/// path counts do not overflow.
+fn exact_reject_probability(statistic: f64,
+                            n1: usize,
+                            n2: usize,
+                            alternative: Alternative)
+                            -> f64 {
+    let (m, n) = (n1 as f64, n2 as f64);
+
+    // Attainable statistic values are multiples of 1 / (n1 n2), so test paths
+    // against the midpoint below the observed value to avoid rounding issues.
+    let q = (0.5 + (statistic * m * n - 1e-7).floor()) / (m * n);
+    let outside = |i: usize, j: usize| alternative.difference(i as f64 / m, j as f64 / n) > q;
+
+    let mut u: Vec<f64> = (0..n2 + 1).map(|j| if outside(0, j) { 0.0 } else { 1.0 }).collect();
+
+    for i in 1..n1 + 1 {
+        let w = i as f64 / (i + n2) as f64;
+
+        u[0] = if outside(i, 0) { 0.0 } else { w * u[0] };
+        for j in 1..n2 + 1 {
+            u[j] = if outside(i, j) { 0.0 } else { w * u[j] + u[j - 1] };
+        }
+    }
+
+    u[n2].clamp(0.0, 1.0)
 }
 
 /// Calculate the Kolmogorov-Smirnov probability function.
@@ -297,14 +1092,21 @@ fn probability_kolmogorov_smirnov(lambda: f64) -> f64 {
 mod tests {
     extern crate quickcheck;
     extern crate rand;
+    #[cfg(feature = "serde")]
+    extern crate serde_json;
 
     use self::quickcheck::{Arbitrary, Gen, QuickCheck, Testable, StdGen};
     use self::rand::Rng;
     use std::cmp;
-    use std::usize;
 
-    use super::test;
+    use super::{distance, exact_reject_probability, test, test_one_sample,
+                test_one_sample_discrete, test_with, Alternative, KsTest, Method, NanPolicy,
+                OneSampleKsTest, OneSampleTest, TestOutcome, TwoSampleTest};
+    use bootstrap::{test_block_bootstrap, BlockBootstrap, Scheme};
+    use distributions::{Binomial, ContinuousDistribution, DiscreteDistribution, Exponential,
+                        Normal, Uniform};
     use ecdf::Ecdf;
+    use numeric::OrderableF64;
 
     const EPSILON: f64 = 1e-10;
 
@@ -350,9 +1152,9 @@ mod tests {
             Samples { vec: vec }
         }
 
-        fn shrink(&self) -> Box<Iterator<Item = Samples>> {
+        fn shrink(&self) -> Box<dyn Iterator<Item = Samples>> {
             let vec: Vec<u64> = self.vec.clone();
-            let shrunk: Box<Iterator<Item = Vec<u64>>> = vec.shrink();
+            let shrunk: Box<dyn Iterator<Item = Vec<u64>>> = vec.shrink();
 
             Box::new(shrunk.filter(|v| v.len() > 7).map(|v| Samples { vec: v }))
         }
@@ -393,6 +1195,7 @@ mod tests {
     /// Alternative calculation for the test statistic for the two sample
     /// Kolmogorov-Smirnov test. This simple implementation is used as a
     /// verification check against actual calculation used.
+    #[allow(clippy::len_zero)]
     fn calculate_statistic_alt<T: Ord + Clone>(xs: &[T], ys: &[T]) -> f64 {
         assert!(xs.len() > 0 && ys.len() > 0);
 
@@ -433,6 +1236,7 @@ mod tests {
 
     #[test]
     fn test_statistic_is_between_zero_and_one() {
+        #[allow(clippy::manual_range_contains)]
         fn prop(xs: Samples, ys: Samples) -> bool {
             let result = test(&xs.vec, &ys.vec, 0.95);
             let actual = result.statistic;
@@ -688,4 +1492,352 @@ mod tests {
 
         check(prop as fn(Samples) -> bool);
     }
+
+    #[test]
+    fn distance_statistic_is_ecdf_difference_at_location() {
+        fn prop(xs: Samples, ys: Samples) -> bool {
+            let ecdf_xs = Ecdf::new(&xs.vec);
+            let ecdf_ys = Ecdf::new(&ys.vec);
+
+            let distance = distance(&ecdf_xs, &ecdf_ys);
+            let difference = (ecdf_xs.value(distance.location) - ecdf_ys.value(distance.location)).abs();
+
+            (distance.statistic - difference).abs() < EPSILON &&
+            (distance.statistic - test(&xs.vec, &ys.vec, 0.95).statistic).abs() < EPSILON
+        }
+
+        check(prop as fn(Samples, Samples) -> bool);
+    }
+
+    #[test]
+    fn test_with_one_sided_statistics_bound_two_sided_statistic() {
+        fn prop(xs: Samples, ys: Samples) -> bool {
+            let statistic = |alternative| {
+                test_with(&xs.vec, &ys.vec, 0.95, alternative, Method::Asymptotic).statistic
+            };
+
+            let two_sided = statistic(Alternative::TwoSided);
+            let less = statistic(Alternative::Less);
+            let greater = statistic(Alternative::Greater);
+
+            two_sided == less.max(greater)
+        }
+
+        check(prop as fn(Samples, Samples) -> bool);
+    }
+
+    #[test]
+    fn test_with_p_value_is_one_minus_reject_probability() {
+        fn prop(xs: Samples, ys: Samples) -> bool {
+            [Alternative::TwoSided, Alternative::Less, Alternative::Greater].iter().all(|&alt| {
+                let result = test_with(&xs.vec, &ys.vec, 0.95, alt, Method::Asymptotic);
+
+                result.p_value == 1.0 - result.reject_probability &&
+                result.is_rejected == (result.statistic > result.critical_value)
+            })
+        }
+
+        check(prop as fn(Samples, Samples) -> bool);
+    }
+
+    #[test]
+    fn test_with_exact_method_rejects_shifted_sample() {
+        let xs: Vec<u64> = (0..20).collect();
+        let ys: Vec<u64> = (10..30).collect();
+
+        let result = test_with(&xs, &ys, 0.95, Alternative::TwoSided, Method::Exact);
+        assert!(result.is_rejected);
+        assert!((result.statistic - 0.5).abs() < EPSILON);
+
+        let result = test_with(&xs, &ys, 0.95, Alternative::Less, Method::Exact);
+        assert!(!result.is_rejected);
+        assert_eq!(result.statistic, 0.0);
+    }
+
+    #[test]
+    fn ks_test_matches_test_with() {
+        fn prop(xs: Samples, ys: Samples) -> bool {
+            [Alternative::TwoSided, Alternative::Less, Alternative::Greater].iter().all(|&alt| {
+                let expected = test_with(&xs.vec, &ys.vec, 0.9, alt, Method::Asymptotic);
+                let actual = KsTest::two_sample()
+                    .confidence(0.9)
+                    .alternative(alt)
+                    .run(&xs.vec, &ys.vec);
+
+                actual.statistic == expected.statistic &&
+                actual.p_value == expected.p_value &&
+                actual.critical_value == expected.critical_value &&
+                actual.confidence == 0.9
+            })
+        }
+
+        check(prop as fn(Samples, Samples) -> bool);
+    }
+
+    #[test]
+    fn ks_test_block_bootstrap_replaces_method() {
+        let xs: Vec<u64> = (0..40).map(|i| (i / 4) % 5).collect();
+        let ys: Vec<u64> = (0..40).map(|i| (i / 4) % 7).collect();
+        let bootstrap = BlockBootstrap {
+            scheme: Scheme::Stationary,
+            block_length: 4,
+            resamples: 49,
+            seed: 3,
+        };
+
+        let expected = test_block_bootstrap(&xs, &ys, 0.95, Alternative::Less, &bootstrap);
+        let actual = KsTest::two_sample()
+            .method(Method::Exact)
+            .alternative(Alternative::Less)
+            .block_bootstrap(bootstrap)
+            .run(&xs, &ys);
+
+        assert_eq!(actual.p_value, expected.p_value);
+        assert_eq!(actual.critical_value, expected.critical_value);
+    }
+
+    #[test]
+    fn ks_test_run_ecdfs_of_mixture_matches_pooled_samples() {
+        fn prop(xs: Samples, zs: Samples, ys: Samples) -> bool {
+            let mut pooled = xs.vec.clone();
+            pooled.extend(zs.vec.iter().cloned());
+
+            let (ecdf_xs, ecdf_zs) = (Ecdf::new(&xs.vec), Ecdf::new(&zs.vec));
+            let mixture = Ecdf::mixture(&[(&ecdf_xs, xs.vec.len() as f64),
+                                          (&ecdf_zs, zs.vec.len() as f64)]);
+
+            let ks_test = KsTest::two_sample().confidence(0.9);
+            let expected = ks_test.run(&pooled, &ys.vec);
+            let actual = ks_test.run_ecdfs(&mixture, &Ecdf::new(&ys.vec));
+
+            (actual.statistic - expected.statistic).abs() < EPSILON &&
+            (actual.p_value - expected.p_value).abs() < EPSILON &&
+            actual.critical_value == expected.critical_value
+        }
+
+        check(prop as fn(Samples, Samples, Samples) -> bool);
+    }
+
+    #[test]
+    fn ks_test_run_ecdfs_weights_mixture_components() {
+        let xs: Vec<u64> = (0..10).collect();
+        let zs: Vec<u64> = (20..30).collect();
+        let ys: Vec<u64> = (5..25).collect();
+
+        // Weighting xs twice as heavily as zs matches pooling two copies of xs.
+        let mut pooled = xs.clone();
+        pooled.extend(xs.iter().cloned());
+        pooled.extend(zs.iter().cloned());
+
+        let (ecdf_xs, ecdf_zs) = (Ecdf::new(&xs), Ecdf::new(&zs));
+        let mixture = Ecdf::mixture(&[(&ecdf_xs, 2.0), (&ecdf_zs, 1.0)]);
+
+        let expected = distance(&Ecdf::new(&pooled), &Ecdf::new(&ys)).statistic;
+        let actual = KsTest::two_sample().run_ecdfs(&mixture, &Ecdf::new(&ys));
+
+        assert!((actual.statistic - expected).abs() < EPSILON);
+        assert!((expected - 5.0 / 12.0).abs() < EPSILON);
+    }
+
+    #[test]
+    #[should_panic(expected="assertion failed: self.block_bootstrap.is_none()")]
+    fn ks_test_run_ecdfs_panics_with_block_bootstrap() {
+        let xs = Ecdf::new(&(0..10).collect::<Vec<u64>>());
+        let bootstrap = BlockBootstrap {
+            scheme: Scheme::MovingBlock,
+            block_length: 2,
+            resamples: 9,
+            seed: 1,
+        };
+
+        KsTest::two_sample().block_bootstrap(bootstrap).run_ecdfs(&xs, &xs);
+    }
+
+    #[test]
+    fn ks_test_run_f64_omits_nan_values() {
+        let xs: Vec<f64> = (0..10).map(|i| i as f64).collect();
+        let ys: Vec<f64> = (0..10).map(|i| i as f64 + 0.5).collect();
+
+        let mut xs_nan = xs.clone();
+        xs_nan.insert(3, f64::NAN);
+        let mut ys_nan = ys.clone();
+        ys_nan.push(f64::NAN);
+
+        let expected = KsTest::two_sample().run_f64(&xs, &ys);
+        let actual = KsTest::two_sample().nan_policy(NanPolicy::Omit).run_f64(&xs_nan, &ys_nan);
+
+        assert_eq!(actual.statistic, expected.statistic);
+        assert_eq!(actual.p_value, expected.p_value);
+    }
+
+    #[test]
+    #[should_panic(expected="assertion failed: 0.0 < confidence && confidence < 1.0")]
+    fn ks_test_panics_on_confidence_out_of_range() {
+        KsTest::two_sample().confidence(1.0);
+    }
+
+    #[test]
+    fn test_traits_delegate_to_ks_tests() {
+        fn outcome<R: TestOutcome>(result: &R) -> (f64, f64, bool) {
+            (result.statistic(), result.p_value(), result.is_rejected())
+        }
+
+        fn prop(xs: Samples, ys: Samples) -> bool {
+            let ks_test = KsTest::two_sample().confidence(0.9);
+            let expected = ks_test.run(&xs.vec, &ys.vec);
+
+            ks_test.name() == "kolmogorov-smirnov" &&
+            outcome(&ks_test.test(&xs.vec, &ys.vec)) ==
+                (expected.statistic, expected.p_value, expected.is_rejected)
+        }
+
+        check(prop as fn(Samples, Samples) -> bool);
+
+        let normal = Normal::new(0.0, 1.0);
+        let xs: Vec<OrderableF64> = (1..50).map(|i| OrderableF64::new(i as f64 / 10.0)).collect();
+
+        let ks_test = OneSampleKsTest::new(&normal).alternative(Alternative::Less);
+        let expected = test_one_sample(&xs, &normal, 0.95, Alternative::Less);
+        assert_eq!(outcome(&ks_test.test(&xs)),
+                   (expected.statistic, expected.p_value, expected.is_rejected));
+    }
+
+    /// Calculate the exact probability that the test statistic is less than a
+    /// value by enumerating every ordering of the merged samples.
+    fn exact_reject_probability_alt(statistic: f64,
+                                    n1: usize,
+                                    n2: usize,
+                                    alternative: Alternative)
+                                    -> f64 {
+        let mut below = 0;
+        let mut total = 0;
+
+        // Each bitmask with n1 bits set is an ordering of the samples, with
+        // set bits taken from xs.
+        for mask in 0u32..(1 << (n1 + n2)) {
+            if mask.count_ones() as usize != n1 {
+                continue;
+            }
+
+            let (mut i, mut j, mut max) = (0, 0, 0.0f64);
+            for k in 0..n1 + n2 {
+                if mask & (1 << k) != 0 {
+                    i += 1;
+                } else {
+                    j += 1;
+                }
+                max = max.max(alternative.difference(i as f64 / n1 as f64, j as f64 / n2 as f64));
+            }
+
+            total += 1;
+            if max < statistic - EPSILON {
+                below += 1;
+            }
+        }
+
+        below as f64 / total as f64
+    }
+
+    #[test]
+    fn exact_reject_probability_matches_enumeration() {
+        for n1 in 1..7 {
+            for n2 in 1..7 {
+                for k in 0..(n1 * n2 + 1) {
+                    let statistic = k as f64 / (n1 * n2) as f64;
+
+                    for &alt in [Alternative::TwoSided, Alternative::Less, Alternative::Greater]
+                                    .iter() {
+                        let actual = exact_reject_probability(statistic, n1, n2, alt);
+                        let expected = exact_reject_probability_alt(statistic, n1, n2, alt);
+
+                        assert!((actual - expected).abs() < EPSILON);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn alternative_and_method_round_trip_through_strings() {
+        for &alt in [Alternative::TwoSided, Alternative::Less, Alternative::Greater].iter() {
+            assert_eq!(alt.to_string().parse::<Alternative>(), Ok(alt));
+        }
+        for &method in [Method::Asymptotic, Method::Exact].iter() {
+            assert_eq!(method.to_string().parse::<Method>(), Ok(method));
+        }
+
+        assert!("sideways".parse::<Alternative>().is_err());
+        assert!("magic".parse::<Method>().is_err());
+    }
+
+    /// Sample of size n at the midpoints of the probability grid of a
+    /// continuous distribution, which is as close to the distribution as
+    /// possible.
+    fn quantile_grid<D: ContinuousDistribution>(distribution: &D, n: usize) -> Vec<OrderableF64> {
+        (0..n).map(|i| OrderableF64::new(distribution.quantile((i as f64 + 0.5) / n as f64))).collect()
+    }
+
+    #[test]
+    fn test_one_sample_statistic_of_quantile_grid_is_half_step() {
+        let n = 200;
+
+        for &alternative in [Alternative::TwoSided, Alternative::Less, Alternative::Greater].iter() {
+            let normal = Normal::new(3.0, 2.0);
+            let result = test_one_sample(&quantile_grid(&normal, n), &normal, 0.95, alternative);
+
+            assert!((result.statistic - 0.5 / n as f64).abs() < EPSILON);
+            assert!(!result.is_rejected);
+            assert!(result.critical_value > result.statistic);
+        }
+    }
+
+    #[test]
+    fn test_one_sample_rejects_wrong_distribution() {
+        let n = 200;
+        let xs = quantile_grid(&Exponential::new(1.0), n);
+
+        let result = test_one_sample(&xs, &Uniform::new(0.0, 3.0), 0.95, Alternative::TwoSided);
+        assert!(result.is_rejected);
+
+        // The exponential distribution function lies above the uniform on
+        // [0, 3] everywhere.
+        let less = test_one_sample(&xs, &Uniform::new(0.0, 3.0), 0.95, Alternative::Less);
+        let greater = test_one_sample(&xs, &Uniform::new(0.0, 3.0), 0.95, Alternative::Greater);
+        assert_eq!(greater.statistic, result.statistic);
+        assert!(greater.is_rejected && !less.is_rejected);
+    }
+
+    #[test]
+    fn test_one_sample_discrete_accounts_for_jumps() {
+        let n = 200;
+        let binomial = Binomial::new(10, 0.5);
+        let xs: Vec<u64> = (0..n).map(|i| binomial.quantile((i as f64 + 0.5) / n as f64)).collect();
+
+        let result = test_one_sample_discrete(&xs, &binomial, 0.95, Alternative::TwoSided);
+        assert!(result.statistic <= 1.0 / n as f64);
+        assert!(!result.is_rejected);
+
+        // A shifted sample is rejected.
+        let ys: Vec<u64> = xs.iter().map(|&x| x + 2).collect();
+        let result = test_one_sample_discrete(&ys, &binomial, 0.95, Alternative::TwoSided);
+        assert!(result.is_rejected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_result_serializes_with_field_names() {
+        let xs: Vec<u64> = (0..20).collect();
+        let ys: Vec<u64> = (10..30).collect();
+        let result = test_with(&xs, &ys, 0.95, Alternative::Less, Method::Exact);
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["is_rejected"], result.is_rejected);
+        assert_eq!(json["statistic"], result.statistic);
+        assert_eq!(json["p_value"], result.p_value);
+        assert_eq!(json["critical_value"], result.critical_value);
+        assert_eq!(json["confidence"], 0.95);
+
+        assert_eq!(serde_json::to_value(Alternative::TwoSided).unwrap(), "two-sided");
+        assert_eq!(serde_json::to_value(Method::Exact).unwrap(), "exact");
+    }
 }